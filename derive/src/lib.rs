@@ -0,0 +1,222 @@
+//! # Roussillon : Type System derive macros
+//!
+//! `#[derive(RoussillonType)]` maps a Rust type to a Roussillon type, and implements the conversion traits of
+//! `roussillon_type_system::value::convert` :
+//! - a struct is a `Structure` converted to and from a `Record`, each field labelled by its name (or position),
+//! - an enum is an `Enumeration` converted to and from a `Union`, each variant labelled by its name.
+//!   Variants are units, with a unit payload, or have a single unnamed field, the payload.
+//!
+//! The fields and payloads are any types implementing the conversion traits and `RoussillonType` : `bool`, `i64`, `f64`,
+//! `u8` to `u128`, `usize`, `()`, tuples, fixed arrays `[T; N]`, `Option<T>`, and other derived types.
+//!
+//! The type is identified by its name, or by the `#[roussillon(name = "Space/Name")]` attribute.
+//! A value is read back only from a type with the same definition : two types with the same name but different
+//! fields or variants are not accepted as one another.
+//!
+//! The tag of a variant is its position, so variants cannot have explicit discriminants :
+//!
+//! ```compile_fail
+//! use roussillon_type_system::RoussillonType;
+//!
+//! #[derive(RoussillonType)]
+//! enum Level { Low = 1, High = 5 }
+//! ```
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::RoussillonType;
+//! use roussillon_type_system::types::concept::DataType;
+//! use roussillon_type_system::value::convert::{FromValue, IntoValue, RoussillonType};
+//!
+//! #[derive(RoussillonType, Debug, PartialEq)]
+//! #[roussillon(name = "Geometry/Point")]
+//! struct Point { x: f64, y: f64 }
+//!
+//! #[derive(RoussillonType, Debug, PartialEq)]
+//! enum Shape {
+//!     Empty,
+//!     Polygon([Point; 3]),
+//!     Circle((Point, f64)),
+//! }
+//!
+//! #[derive(RoussillonType, Debug, PartialEq)]
+//! struct Drawing { shape: Shape, layer: Option<u8> }
+//!
+//! assert_eq!(Point::data_type().typename(), "Geometry/Point");
+//!
+//! let drawing = Drawing {
+//!     shape: Shape::Polygon([Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }]),
+//!     layer: Some(2),
+//! };
+//! let value = drawing.into_value();
+//! assert_eq!(value.borrow().data_type().typename(), "Drawing");
+//! assert_eq!(Drawing::from_value(&value).unwrap().layer, Some(2));
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+#[proc_macro_derive(RoussillonType, attributes(roussillon))]
+pub fn derive_roussillon_type(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "RoussillonType cannot be derived for generic types"));
+    }
+    let identifier = identifier(input)?;
+    match &input.data {
+        Data::Struct(data) => Ok(expand_struct(input, &identifier, &data.fields)),
+        Data::Enum(data) => expand_enum(input, &identifier, data.variants.iter()),
+        Data::Union(_) => Err(Error::new_spanned(input, "RoussillonType cannot be derived for unions")),
+    }
+}
+
+/// The identifier of the type, from `#[roussillon(name = "...")]` or the name of the Rust type.
+fn identifier(input: &DeriveInput) -> syn::Result<String> {
+    let mut identifier = input.ident.to_string();
+    for attribute in input.attrs.iter().filter(|attribute| attribute.path().is_ident("roussillon")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                identifier = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(identifier)
+}
+
+fn expand_struct(input: &DeriveInput, identifier: &str, fields: &Fields) -> TokenStream2 {
+    let name = &input.ident;
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let labels: Vec<String> = fields.iter().enumerate()
+        .map(|(position, field)| field.ident.as_ref().map_or_else(|| position.to_string(), |ident| ident.to_string()))
+        .collect();
+    let members: Vec<_> = fields.iter().enumerate()
+        .map(|(position, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = syn::Index::from(position);
+                quote!(#index)
+            }
+        })
+        .collect();
+    let positions = 0..fields.len();
+    let construct = match fields {
+        Fields::Named(_) => quote!(Self { #(#members: <#types as FromValue>::from_raw(members[#positions])?),* }),
+        Fields::Unnamed(_) => quote!(Self ( #(<#types as FromValue>::from_raw(members[#positions])?),* )),
+        Fields::Unit => quote!(Self),
+    };
+
+    quote! {
+        const _: () = {
+            use ::roussillon_type_system::value::convert::{has_type, members_raw, FromValue, IntoValue, RoussillonType};
+
+            fn structure() -> ::std::rc::Rc<::roussillon_type_system::types::typedef::Structure> {
+                ::roussillon_type_system::facade::create_struct(
+                    #identifier,
+                    ::roussillon_type_system::identity::LabelBank::from(&[#(#labels),*]),
+                    &[#(<#types as RoussillonType>::data_type()),*],
+                )
+            }
+
+            impl RoussillonType for #name {
+                fn data_type() -> ::roussillon_type_system::types::concept::Type { structure() }
+            }
+
+            impl IntoValue for #name {
+                fn into_value(self) -> ::roussillon_type_system::value::concept::ValueCell {
+                    ::roussillon_type_system::value::record::Record::new(structure(), &[#(IntoValue::into_value(self.#members)),*])
+                        .expect("a field has its declared type")
+                        .to_cell()
+                }
+            }
+
+            impl FromValue for #name {
+                #[allow(unused_variables)]
+                fn from_raw(raw: &[u8]) -> ::roussillon_type_system::value::error::TypeResult<Self> {
+                    let members = members_raw::<Self>(raw, &[#(<#types as RoussillonType>::data_type()),*])?;
+                    Ok(#construct)
+                }
+
+                fn accepts(data_type: &::roussillon_type_system::types::concept::Type) -> bool { has_type::<Self>(data_type) }
+            }
+        };
+    }
+}
+
+fn expand_enum<'a>(input: &DeriveInput, identifier: &str, variants: impl Iterator<Item = &'a syn::Variant>) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let mut labels = Vec::new();
+    let mut payloads = Vec::new();
+    let mut into_arms = Vec::new();
+    let mut from_arms = Vec::new();
+    for (tag, variant) in variants.enumerate() {
+        if let Some((_, discriminant)) = &variant.discriminant {
+            return Err(Error::new_spanned(discriminant, "a variant cannot have an explicit discriminant, its tag is its position"));
+        }
+        let variant_name = &variant.ident;
+        labels.push(variant_name.to_string());
+        match &variant.fields {
+            Fields::Unit => {
+                payloads.push(quote!(()));
+                into_arms.push(quote!(Self::#variant_name => (#tag, IntoValue::into_value(()))));
+                from_arms.push(quote!(#tag => Ok(Self::#variant_name)));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let payload = &fields.unnamed[0].ty;
+                payloads.push(quote!(#payload));
+                into_arms.push(quote!(Self::#variant_name(payload) => (#tag, IntoValue::into_value(payload))));
+                from_arms.push(quote!(#tag => Ok(Self::#variant_name(<#payload as FromValue>::from_raw(payload)?))));
+            }
+            _ => return Err(Error::new_spanned(variant, "a variant is a unit or has a single unnamed field")),
+        }
+    }
+
+    Ok(quote! {
+        const _: () = {
+            use ::roussillon_type_system::value::convert::{has_type, variant_raw, FromValue, IntoValue, RoussillonType};
+
+            fn enumeration() -> ::std::rc::Rc<::roussillon_type_system::types::typedef::Enumeration> {
+                ::roussillon_type_system::facade::create_enum(
+                    #identifier,
+                    ::roussillon_type_system::identity::LabelBank::from(&[#(#labels),*]),
+                    &[#(<#payloads as RoussillonType>::data_type()),*],
+                )
+            }
+
+            impl RoussillonType for #name {
+                fn data_type() -> ::roussillon_type_system::types::concept::Type { enumeration() }
+            }
+
+            impl IntoValue for #name {
+                fn into_value(self) -> ::roussillon_type_system::value::concept::ValueCell {
+                    let (tag, payload) = match self { #(#into_arms),* };
+                    ::roussillon_type_system::value::union::Union::new(enumeration(), tag, payload)
+                        .expect("a variant has its declared type")
+                        .to_cell()
+                }
+            }
+
+            impl FromValue for #name {
+                #[allow(unused_variables)]
+                fn from_raw(raw: &[u8]) -> ::roussillon_type_system::value::error::TypeResult<Self> {
+                    let (tag, payload) = variant_raw::<Self>(raw, &enumeration())?;
+                    match tag {
+                        #(#from_arms,)*
+                        _ => unreachable!("the tag of a variant"),
+                    }
+                }
+
+                fn accepts(data_type: &::roussillon_type_system::types::concept::Type) -> bool { has_type::<Self>(data_type) }
+            }
+        };
+    })
+}
@@ -0,0 +1,65 @@
+use roussillon_type_system::facade::{create_enum, create_struct};
+use roussillon_type_system::identity::LabelBank;
+use roussillon_type_system::types::concept::DataType;
+use roussillon_type_system::types::primitive::Primitive;
+use roussillon_type_system::value::convert::{FromValue, IntoValue, RoussillonType};
+use roussillon_type_system::value::error::{ConversionError, TypeError};
+use roussillon_type_system::value::number::Float;
+use roussillon_type_system::value::record::Record;
+use roussillon_type_system::value::union::Union;
+use roussillon_type_system::RoussillonType;
+
+#[derive(RoussillonType, Debug, PartialEq)]
+struct Point { x: f64, y: f64 }
+
+#[derive(RoussillonType, Debug, PartialEq)]
+struct Pair(u8, bool);
+
+#[derive(RoussillonType, Debug, PartialEq)]
+enum Shape {
+    Empty,
+    Dot(Point),
+}
+
+fn is_mismatch<T: std::fmt::Debug>(result: Result<T, TypeError>) -> bool {
+    matches!(result, Err(TypeError::ConversionError(ConversionError::Mismatch { .. })))
+}
+
+#[test]
+fn test_round_trip() {
+    let point = Point { x: 1.0, y: -2.5 };
+    assert_eq!(Point::from_value(&Point { x: 1.0, y: -2.5 }.into_value()).unwrap(), point);
+    assert_eq!(Pair::from_value(&Pair(7, true).into_value()).unwrap(), Pair(7, true));
+    assert_eq!(Shape::from_value(&Shape::Empty.into_value()).unwrap(), Shape::Empty);
+    assert_eq!(Shape::from_value(&Shape::Dot(Point { x: 0.5, y: 0.0 }).into_value()).unwrap(), Shape::Dot(Point { x: 0.5, y: 0.0 }));
+    assert!(is_mismatch(Point::from_value(&Shape::Empty.into_value())));
+}
+
+#[test]
+fn test_same_name_other_fields() {
+    let float = Primitive::Float.to_rc();
+    let field = || Float::new(1.0).to_cell();
+    let renamed = create_struct("Point", LabelBank::from(&["x", "z"]), &[float.clone(), float.clone()]);
+    assert_eq!(renamed.typename(), Point::data_type().typename());
+    assert!(!Point::accepts(&(renamed.clone() as _)));
+    let value = Record::new(renamed, &[field(), field()]).unwrap().to_cell();
+    assert!(is_mismatch(Point::from_value(&value)));
+
+    let retyped = create_struct("Point", LabelBank::from(&["x", "y"]), &[float.clone(), Primitive::Integer.to_rc()]);
+    assert!(!Point::accepts(&(retyped as _)));
+    let same = create_struct("Point", LabelBank::from(&["x", "y"]), &[float.clone(), float]);
+    assert!(Point::accepts(&(same as _)));
+}
+
+#[test]
+fn test_same_name_other_variants() {
+    let unit = <()>::data_type();
+    let renamed = create_enum("Shape", LabelBank::from(&["Empty", "Line"]), &[unit.clone(), Point::data_type()]);
+    assert!(!Shape::accepts(&(renamed.clone() as _)));
+    let value = Union::new(renamed, 0, ().into_value()).unwrap().to_cell();
+    assert!(is_mismatch(Shape::from_value(&value)));
+    let nested = create_enum("Shape", LabelBank::from(&["Empty", "Dot"]), &[unit.clone(), Pair::data_type()]);
+    assert!(!Shape::accepts(&(nested as _)));
+    let same = create_enum("Shape", LabelBank::from(&["Empty", "Dot"]), &[unit, Point::data_type()]);
+    assert!(Shape::accepts(&(same as _)));
+}
@@ -0,0 +1,165 @@
+//! [Annotations] attached to types, fields, variants and functions.
+//!
+//! Annotations are metadata and change nothing to the values :
+//! - a doc string and a deprecation marker, used by the generated documentation and schemas,
+//! - serialization hints used by the [crate::json] mapping : a renamed key, or a skipped field,
+//! - arbitrary key/value attributes.
+//!
+//! They are attached to :
+//! - [crate::types::typedef::Structure] and [crate::types::typedef::Enumeration] with `with_annotations()`,
+//! - fields and variants through their [crate::identity::LabelBank] with `annotated()`,
+//! - [crate::types::functional::FunctionDeclaration] with `with_annotations()`.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::annotation::Annotations;
+//! use roussillon_type_system::identity::LabelBank;
+//!
+//! let labels = LabelBank::from(&["id", "legacy_name"])
+//!     .annotated("id", Annotations::new().with_doc("The unique identifier.").with_rename("ID"))
+//!     .annotated("legacy_name", Annotations::new().with_deprecated("use `name`").with_skip());
+//!
+//! assert_eq!(labels.annotations(0).unwrap().rename.as_deref(), Some("ID"));
+//! assert!(labels.annotations(1).unwrap().is_deprecated());
+//! ```
+
+/// Metadata attached to a type, a field, a variant or a function.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotations {
+    /// The documentation, in Markdown.
+    pub doc: Option<String>,
+    /// The reason of the deprecation, if deprecated.
+    pub deprecated: Option<String>,
+    /// The key of a field or a variant in serialized documents, instead of its label.
+    pub rename: Option<String>,
+    /// A skipped field is not serialized. It takes its default value when deserialized.
+    ///
+    /// Variants cannot be skipped.
+    pub skip: bool,
+    attributes: Vec<(String, String)>,
+}
+
+impl Annotations {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+
+    pub fn with_deprecated(mut self, reason: &str) -> Self {
+        self.deprecated = Some(reason.to_string());
+        self
+    }
+
+    pub fn with_rename(mut self, key: &str) -> Self {
+        self.rename = Some(key.to_string());
+        self
+    }
+
+    pub fn with_skip(mut self) -> Self {
+        self.skip = true;
+        self
+    }
+
+    pub fn with_attribute(mut self, key: &str, value: &str) -> Self {
+        self.set_attribute(key, value);
+        self
+    }
+
+    pub fn is_deprecated(&self) -> bool { self.deprecated.is_some() }
+
+    /// Sets an attribute, and returns its previous value.
+    pub fn set_attribute(&mut self, key: &str, value: &str) -> Option<String> {
+        match self.attributes.iter_mut().find(|(attribute, _)| attribute == key) {
+            Some((_, previous)) => Some(std::mem::replace(previous, value.to_string())),
+            None => {
+                self.attributes.push((key.to_string(), value.to_string()));
+                None
+            }
+        }
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|(attribute, _)| attribute == key).map(|(_, value)| value.as_str())
+    }
+
+    /// The attributes, in the order they were first set.
+    pub fn attributes(&self) -> &[(String, String)] { &self.attributes }
+
+    pub fn is_empty(&self) -> bool { *self == Self::default() }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::annotation::Annotations;
+    use crate::identity::{Identifier, LabelBank};
+    use crate::json::document::Json;
+    use crate::json::mapping::{json_to_raw, raw_to_json};
+    use crate::types::algebraic::ProductType;
+    use crate::types::concept::Type;
+    use crate::types::functional::{FunctionDeclaration, FunctionType};
+    use crate::types::primitive::Primitive;
+    use crate::types::registry::Registry;
+    use crate::types::sequence::Tuple;
+    use crate::types::typedef::Structure;
+    use crate::value::error::{JsonError, TypeError};
+
+    #[test]
+    fn test_attributes() {
+        let mut annotations = Annotations::new().with_attribute("unit", "m").with_attribute("range", "0..10");
+        assert_eq!(annotations.set_attribute("unit", "cm"), Some("m".to_string()));
+        assert_eq!(annotations.set_attribute("owner", "geometry"), None);
+        assert_eq!(annotations.attribute("unit"), Some("cm"));
+        assert_eq!(annotations.attribute("missing"), None);
+        let keys: Vec<&str> = annotations.attributes().iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["unit", "range", "owner"]);
+        assert!(!annotations.is_empty());
+        assert!(Annotations::new().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_labels() {
+        let mut labels = LabelBank::from(&["x"]);
+        assert!(!labels.annotate("y", Annotations::new().with_doc("Unknown.")));
+        assert!(labels.annotations(0).is_none());
+        let labels = labels.annotated("y", Annotations::new().with_skip()).annotated("x", Annotations::new().with_doc("Known."));
+        assert_eq!(labels.annotations(0).and_then(|annotations| annotations.doc.as_deref()), Some("Known."));
+        assert!(labels.annotations(1).is_none());
+    }
+
+    #[test]
+    fn test_json_keys() {
+        let labels = LabelBank::from(&["id", "cache"])
+            .annotated("id", Annotations::new().with_rename("ID"))
+            .annotated("cache", Annotations::new().with_skip());
+        let item: Type = Structure::new("Store/Item", labels, ProductType::new(&[
+            Primitive::Integer.to_rc(),
+            Primitive::Integer.to_rc(),
+        ])).to_rc();
+        let raw = json_to_raw(&item, &Json::parse("{\"ID\": 7}").unwrap()).unwrap();
+        assert_eq!(raw, [7i64.to_be_bytes(), 0i64.to_be_bytes()].concat());
+        assert_eq!(raw_to_json(&item, &raw).unwrap().to_string(), "{\"ID\":7}");
+        let Err(TypeError::JsonError(JsonError::UnknownKey { key, .. })) = json_to_raw(&item, &Json::parse("{\"ID\": 7, \"cache\": 1}").unwrap()) else {
+            panic!("a skipped field is not imported");
+        };
+        assert_eq!(key, "cache");
+        let Err(TypeError::JsonError(JsonError::UnknownKey { key, .. })) = json_to_raw(&item, &Json::parse("{\"id\": 7}").unwrap()) else {
+            panic!("a renamed field is not imported from its label");
+        };
+        assert_eq!(key, "id");
+        let Err(TypeError::JsonError(JsonError::MissingKey { key, .. })) = json_to_raw(&item, &Json::parse("{}").unwrap()) else {
+            panic!("a renamed field is imported from its key");
+        };
+        assert_eq!(key, "ID");
+    }
+
+    #[test]
+    fn test_definitions() {
+        let mut registry = Registry::new();
+        registry.add_function(FunctionDeclaration::new(Identifier::new("Core/noop"), FunctionType::new(Tuple::new(), Primitive::Integer.to_rc()))
+            .with_annotations(Annotations::new().with_deprecated("Unused.")));
+        assert!(registry.get("Core/noop").unwrap().annotations().is_deprecated());
+    }
+}
@@ -0,0 +1,293 @@
+//! Generation of source code from types.
+//!
+//! [Declarations] collects the composite types to declare, dependencies first, and names them :
+//! - [Structure] and [Enumeration] are named after their identifier, like `Space_Name`,
+//! - anonymous [Tuple]s, [ProductType]s and [SumType]s are named after the field where they appear,
+//! - [FunctionDeclaration]s are named after their identifier.
+//!
+//! Then :
+//! - [rust] emits `#[repr(C, packed)]` structs and `#[repr(C, u64)]` enums,
+//! - [c] emits a C header.
+//!
+//! [typescript] declares the JSON documents of values instead, [diagram] draws the types
+//! and [markdown] documents them, they do not use [Declarations].
+//!
+//! The generated Rust and C types have the layout of raw buffers : fields have no padding, and a sum is a `u64` tag
+//! followed by a union of its payloads. Numbers keep the byte order of raw buffers (big-endian),
+//! a [Primitive::Bytes] is an array of bytes and a [Primitive::Reference] is a `u64` address.
+//! Both generators assert the size and the offsets of each type, so the generated code
+//! does not compile if its layout does not match [crate::types::concept::DataType::size].
+//! Rust enums are the exception : they are aligned on their tag, see [rust::rust_size].
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::codegen::Declarations;
+//! use roussillon_type_system::codegen::rust::rust_module;
+//! use roussillon_type_system::facade::create_struct;
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::types::concept::Type;
+//!
+//! let point = create_struct("Geometry/Point", LabelBank::from(&["x", "y"]), &[
+//!     Primitive::Float.to_rc(),
+//!     Primitive::Float.to_rc(),
+//! ]);
+//! let mut declarations = Declarations::new();
+//! declarations.declare("Point", &(point as Type)).unwrap();
+//!
+//! let module = rust_module(&declarations);
+//! assert!(module.contains("pub struct Geometry_Point {\n    pub x: f64,\n    pub y: f64,\n}"));
+//! assert!(module.contains("const _: () = assert!(core::mem::size_of::<Geometry_Point>() == 16);"));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::identity::{Identified, Identifier};
+use crate::json::mapping::{field_labels, variant_labels};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::functional::{FunctionDeclaration, FunctionType};
+use crate::types::primitive::Primitive;
+use crate::types::registry::{Definition, Registry};
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::error::{CodegenError, TypeResult};
+
+pub mod c;
+pub mod diagram;
+pub mod markdown;
+pub mod rust;
+pub mod typescript;
+
+/// A declaration of the generated code.
+#[derive(Clone, Debug)]
+pub enum Item {
+    /// A structure, a tuple or a product, with its fields in order.
+    Record {
+        name: String,
+        size: usize,
+        fields: Vec<(String, Type)>,
+    },
+    /// An enumeration or a sum, with its variants in tag order.
+    Tagged {
+        name: String,
+        size: usize,
+        variants: Vec<(String, Type)>,
+    },
+    /// A function declaration.
+    Function {
+        name: String,
+        signature: FunctionType,
+    },
+}
+
+/// Returns `true` if `data_type` is an empty tuple or product, which has no representation.
+pub fn is_unit(data_type: &Type) -> bool {
+    data_type.downcast_ref::<Tuple>().is_some_and(|tuple| tuple.is_empty())
+        || data_type.downcast_ref::<ProductType>().is_some_and(ProductType::is_unit_type)
+}
+
+/// Replaces the characters which cannot be in an identifier by `_`.
+pub fn sanitize(name: &str) -> String {
+    let name: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) { format!("_{}", name) } else { name }
+}
+
+/// The name of the declaration of a named type : `Space_Name`, or `Name` without space.
+pub fn identifier_name(identifier: &Identifier) -> String {
+    if identifier.space.is_empty() {
+        sanitize(&identifier.name)
+    } else {
+        sanitize(&format!("{}_{}", identifier.space, identifier.name))
+    }
+}
+
+/// The identifier of a [Structure] or an [Enumeration].
+pub fn identifier_of(data_type: &Type) -> Option<Identifier> {
+    data_type.downcast_ref::<Structure>().map(Structure::identifier)
+        .or_else(|| data_type.downcast_ref::<Enumeration>().map(Enumeration::identifier))
+}
+
+/// Adds the [Structure]s and [Enumeration]s contained in `data_type`, itself included, which are not in `named` yet.
+///
+/// The targets of [Primitive::Reference]s are included if `references` is `true`.
+pub fn named_types(data_type: &Type, named: &mut Vec<Type>, references: bool) {
+    if identifier_of(data_type).is_some() {
+        if named.iter().any(|declared| declared.typename() == data_type.typename()) {
+            return;
+        }
+        named.push(data_type.clone());
+    }
+    for contained in contained_types(data_type, references) {
+        named_types(&contained, named, references);
+    }
+}
+
+/// The types directly contained in `data_type`.
+pub(crate) fn contained_types(data_type: &Type, references: bool) -> Vec<Type> {
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return match primitive {
+            Primitive::List((of_type, _)) => vec![of_type.clone()],
+            Primitive::Reference(to_type) if references => vec![to_type.clone()],
+            _ => Vec::new(),
+        };
+    }
+    if let Some(tuple) = data_type.downcast_ref::<Tuple>() {
+        return tuple.clone();
+    }
+    if let Some(product) = data_type.downcast_ref::<ProductType>() {
+        return product.to_tuple();
+    }
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        return structure.product_type.to_tuple();
+    }
+    let sum = data_type.downcast_ref::<SumType>()
+        .or_else(|| data_type.downcast_ref::<Enumeration>().map(|enumeration| &enumeration.sum_type));
+    match sum {
+        Some(sum) => (0..sum.len()).map(|tag| sum.variant(tag).unwrap()).collect(),
+        None => Vec::new(),
+    }
+}
+
+/// The offsets of fields laid out without padding.
+pub fn offsets(fields: &[(String, Type)]) -> Vec<usize> {
+    fields.iter().scan(0, |offset, (_, field)| {
+        let current = *offset;
+        *offset += field.size();
+        Some(current)
+    }).collect()
+}
+
+/// The composite types and functions to declare, in dependency order.
+#[derive(Clone, Debug, Default)]
+pub struct Declarations {
+    items: Vec<Item>,
+    names: HashMap<String, String>,
+}
+
+impl Declarations {
+    pub fn new() -> Self { Self::default() }
+
+    /// Declares every definition of a registry.
+    pub fn from_registry(registry: &Registry) -> TypeResult<Self> {
+        let mut declarations = Self::new();
+        for definition in registry.definitions() {
+            match definition {
+                Definition::Function(function) => declarations.declare_function(function)?,
+                definition => declarations.declare(&identifier_name(&definition.identifier()), &definition.to_type().unwrap())?,
+            }
+        }
+        Ok(declarations)
+    }
+
+    /// Declares a type and the types it contains.
+    ///
+    /// An anonymous type is named `name`, a [Structure] or an [Enumeration] is named after its identifier.
+    pub fn declare(&mut self, name: &str, data_type: &Type) -> TypeResult<()> {
+        self.walk(data_type, &sanitize(name))
+    }
+
+    pub fn declare_function(&mut self, declaration: &FunctionDeclaration) -> TypeResult<()> {
+        let name = identifier_name(&declaration.identifier);
+        self.function_types(&declaration.signature, &name)?;
+        self.items.push(Item::Function { name, signature: declaration.signature.clone() });
+        Ok(())
+    }
+
+    /// The name of the declaration of a composite type.
+    pub fn name_of(&self, data_type: &Type) -> Option<&str> {
+        self.names.get(&data_type.typename()).map(String::as_str)
+    }
+
+    pub fn items(&self) -> &[Item] { &self.items }
+
+    fn fresh(&self, hint: &str) -> String {
+        let taken = |name: &str| self.names.values().any(|taken| taken == name);
+        let mut name = hint.to_string();
+        let mut counter = 2;
+        while taken(&name) {
+            name = format!("{}_{}", hint, counter);
+            counter += 1;
+        }
+        name
+    }
+
+    fn function_types(&mut self, signature: &FunctionType, hint: &str) -> TypeResult<()> {
+        for (index, argument) in signature.arguments.iter().enumerate() {
+            self.walk(argument, &format!("{}_{}", hint, index))?;
+        }
+        self.walk(&signature.return_type, &format!("{}_return", hint))
+    }
+
+    /// The fields named after `keys`, a key which collides with a previous one once sanitized is numbered.
+    fn fields(&mut self, keys: Vec<String>, types: Tuple, name: &str) -> TypeResult<Vec<(String, Type)>> {
+        let mut fields: Vec<(String, Type)> = Vec::with_capacity(types.len());
+        for (key, field) in keys.into_iter().zip(types) {
+            self.walk(&field, &sanitize(&format!("{}_{}", name, key)))?;
+            let hint = sanitize(&key);
+            let mut key = hint.clone();
+            let mut counter = 2;
+            while fields.iter().any(|(taken, _)| *taken == key) {
+                key = format!("{}_{}", hint, counter);
+                counter += 1;
+            }
+            fields.push((key, field));
+        }
+        Ok(fields)
+    }
+
+    fn push(&mut self, data_type: &Type, item: Item) {
+        let name = match &item {
+            Item::Record { name, .. } | Item::Tagged { name, .. } | Item::Function { name, .. } => name.clone(),
+        };
+        self.names.insert(data_type.typename(), name);
+        self.items.push(item);
+    }
+
+    fn walk(&mut self, data_type: &Type, hint: &str) -> TypeResult<()> {
+        if is_unit(data_type) || self.names.contains_key(&data_type.typename()) {
+            return Ok(());
+        }
+        let size = data_type.size();
+        if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+            if let Primitive::List((of_type, _)) = primitive {
+                self.walk(of_type, hint)?;
+            }
+            return Ok(());
+        }
+        if let Some(function) = data_type.downcast_ref::<FunctionType>() {
+            return self.function_types(function, hint);
+        }
+        if let Some(structure) = data_type.downcast_ref::<Structure>() {
+            let name = self.fresh(&identifier_name(&structure.identifier()));
+            let fields = self.fields(field_labels(structure), structure.product_type.to_tuple(), &name)?;
+            self.push(data_type, Item::Record { name, size, fields });
+            return Ok(());
+        }
+        if let Some(enumeration) = data_type.downcast_ref::<Enumeration>() {
+            let name = self.fresh(&identifier_name(&enumeration.identifier()));
+            let sum = &enumeration.sum_type;
+            let variants = (0..sum.len()).map(|tag| sum.variant(tag).unwrap()).collect();
+            let variants = self.fields(variant_labels(Some(enumeration.labels()), sum.len()), variants, &name)?;
+            self.push(data_type, Item::Tagged { name, size, variants });
+            return Ok(());
+        }
+        let members = data_type.downcast_ref::<Tuple>().cloned()
+            .or_else(|| data_type.downcast_ref::<ProductType>().map(ProductType::to_tuple));
+        if let Some(members) = members {
+            let name = self.fresh(hint);
+            let fields = self.fields(variant_labels(None, members.len()), members, &name)?;
+            self.push(data_type, Item::Record { name, size, fields });
+            return Ok(());
+        }
+        if let Some(sum) = data_type.downcast_ref::<SumType>() {
+            let name = self.fresh(hint);
+            let variants = (0..sum.len()).map(|tag| sum.variant(tag).unwrap()).collect();
+            let variants = self.fields(variant_labels(None, sum.len()), variants, &name)?;
+            self.push(data_type, Item::Tagged { name, size, variants });
+            return Ok(());
+        }
+        Err(CodegenError::Unsupported { name: hint.to_string(), data_type: data_type.clone() }.promote())
+    }
+}
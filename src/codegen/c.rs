@@ -0,0 +1,198 @@
+//! Generation of a C header.
+//!
+//! - records are structs declared in a `#pragma pack(push, 1)` section,
+//! - tagged types are structs of a `uint64_t tag` and an anonymous `payload` union,
+//!   with an enum constant `Name_variant` for the tag of each variant,
+//! - functions are prototypes,
+//! - each type is followed by `_Static_assert`s of its size and of the offsets of its fields.
+//!
+//! Types of zero bytes, like unit types or records of unit fields, have no representation in ISO C :
+//! they are not declared, fields of these types are left out and they are `void` elsewhere.
+
+use crate::codegen::{offsets, Declarations, Item};
+use crate::types::concept::Type;
+use crate::types::functional::FunctionType;
+use crate::types::primitive::Primitive;
+
+const KEYWORDS: [&str; 34] = [
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum", "extern",
+    "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return", "short", "signed",
+    "sizeof", "static", "struct", "switch", "typedef", "union", "unsigned", "void", "while",
+];
+
+fn field_name(key: &str) -> String {
+    if KEYWORDS.contains(&key) { format!("{}_", key) } else { key.to_string() }
+}
+
+fn is_empty(data_type: &Type) -> bool { data_type.size() == 0 }
+
+/// The C declaration of `declarator` with the type `data_type`, like `int64_t name[4]`.
+///
+/// The composite types of `data_type` must be declared.
+pub fn c_declaration(declarations: &Declarations, data_type: &Type, declarator: &str) -> String {
+    let declaration = |type_name: &str| format!("{} {}", type_name, declarator).trim_end().to_string();
+    if is_empty(data_type) {
+        return declaration("void");
+    }
+    if let Some(name) = declarations.name_of(data_type) {
+        return declaration(name);
+    }
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return match primitive {
+            Primitive::Boolean => declaration("bool"),
+            Primitive::Byte => declaration("uint8_t"),
+            Primitive::Bytes(size) => format!("uint8_t {}[{}]", declarator, size),
+            Primitive::Float => declaration("double"),
+            Primitive::Integer => declaration("int64_t"),
+            Primitive::Reference(_) => declaration("uint64_t"),
+            Primitive::List((of_type, len)) => c_declaration(declarations, of_type, &format!("{}[{}]", declarator, len)),
+        };
+    }
+    if let Some(function) = data_type.downcast_ref::<FunctionType>() {
+        return c_declaration(declarations, &function.return_type, &format!("(*{})({})", declarator, parameters(declarations, function)));
+    }
+    declaration(&data_type.typename())
+}
+
+fn parameters(declarations: &Declarations, signature: &FunctionType) -> String {
+    let parameters: Vec<String> = signature.arguments.iter()
+        .filter(|argument| !is_empty(argument))
+        .map(|argument| c_declaration(declarations, argument, ""))
+        .collect();
+    if parameters.is_empty() { "void".to_string() } else { parameters.join(", ") }
+}
+
+fn assertions(output: &mut String, name: &str, size: usize, fields: &[(String, Type)]) {
+    output.push_str(&format!("_Static_assert(sizeof({}) == {}, \"{} is {} bytes\");\n", name, size, name, size));
+    for ((key, field), offset) in fields.iter().zip(offsets(fields)) {
+        if !is_empty(field) {
+            output.push_str(&format!(
+                "_Static_assert(offsetof({}, {}) == {}, \"{}.{} is at offset {}\");\n",
+                name, field_name(key), offset, name, key, offset
+            ));
+        }
+    }
+}
+
+fn members(output: &mut String, declarations: &Declarations, fields: &[(String, Type)], indent: &str) {
+    for (key, field) in fields.iter().filter(|(_, field)| !is_empty(field)) {
+        output.push_str(&format!("{}{};\n", indent, c_declaration(declarations, field, &field_name(key))));
+    }
+}
+
+/// Returns a C header declaring every item of `declarations`, protected by the include `guard`.
+pub fn c_header(declarations: &Declarations, guard: &str) -> String {
+    let mut output = format!(
+        "/* Generated by roussillon-type-system, do not edit. */\n\
+         /* Numbers are in the byte order of raw buffers (big-endian). */\n\
+         #ifndef {guard}\n#define {guard}\n\n\
+         #include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n\n\
+         #pragma pack(push, 1)\n\n"
+    );
+    let mut prototypes = String::new();
+    for item in declarations.items() {
+        match item {
+            Item::Record { size: 0, .. } => continue,
+            Item::Record { name, size, fields } => {
+                output.push_str(&format!("typedef struct {} {{\n", name));
+                members(&mut output, declarations, fields, "    ");
+                output.push_str(&format!("}} {};\n\n", name));
+                assertions(&mut output, name, *size, fields);
+            }
+            Item::Tagged { name, size, variants } => {
+                let payloads: Vec<(String, Type)> = variants.iter().filter(|(_, variant)| !is_empty(variant)).cloned().collect();
+                let mut fields = vec![("tag".to_string(), Primitive::Bytes(8).to_rc())];
+                output.push_str(&format!("typedef struct {} {{\n    uint64_t tag;\n", name));
+                if !payloads.is_empty() {
+                    output.push_str("    union {\n");
+                    members(&mut output, declarations, &payloads, "        ");
+                    output.push_str("    } payload;\n");
+                    fields.push(("payload".to_string(), Primitive::Bytes(size - 8).to_rc()));
+                }
+                output.push_str(&format!("}} {};\n\nenum {{\n", name));
+                for (tag, (key, _)) in variants.iter().enumerate() {
+                    output.push_str(&format!("    {}_{} = {},\n", name, key, tag));
+                }
+                output.push_str("};\n\n");
+                assertions(&mut output, name, *size, &fields);
+            }
+            Item::Function { name, signature } => {
+                let declarator = format!("{}({})", name, parameters(declarations, signature));
+                prototypes.push_str(&format!("{};\n", c_declaration(declarations, &signature.return_type, &declarator)));
+                continue;
+            }
+        }
+        output.push('\n');
+    }
+    output.push_str("#pragma pack(pop)\n\n");
+    if !prototypes.is_empty() {
+        output.push_str(&prototypes);
+        output.push('\n');
+    }
+    output.push_str(&format!("#endif /* {} */\n", guard));
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+    use std::rc::Rc;
+
+    use crate::codegen::c::c_header;
+    use crate::codegen::Declarations;
+    use crate::facade::{create_enum, create_struct};
+    use crate::identity::{Identifier, LabelBank};
+    use crate::types::concept::Type;
+    use crate::types::functional::{FunctionDeclaration, FunctionType};
+    use crate::types::primitive::Primitive;
+    use crate::types::sequence::Tuple;
+
+    fn declarations() -> Declarations {
+        let unit: Type = Rc::new(Tuple::new());
+        let shape: Type = create_enum("Geometry/Shape", LabelBank::from(&["a", "A", "int", "a b", "a_b"]), &[
+            Primitive::Boolean.to_rc(),
+            unit.clone(),
+            Primitive::Float.to_rc(),
+            Primitive::Bytes(3).to_rc(),
+            Primitive::Integer.to_rc(),
+        ]);
+        let empty: Type = create_struct("Geometry/Empty", LabelBank::from(&["nothing"]), &[unit]);
+        let drawing: Type = create_struct("Geometry/Drawing", LabelBank::from(&["type", "shapes", "empty", "scale"]), &[
+            Primitive::Byte.to_rc(),
+            Primitive::List((shape.clone(), 2)).to_rc(),
+            empty.clone(),
+            Primitive::Float.to_rc(),
+        ]);
+        let mut declarations = Declarations::new();
+        declarations.declare("Drawing", &drawing).unwrap();
+        declarations.declare_function(&FunctionDeclaration::new(
+            Identifier::new("Geometry/draw"),
+            FunctionType::new(vec![drawing, empty.clone(), Primitive::Integer.to_rc()], empty),
+        )).unwrap();
+        declarations
+    }
+
+    #[test]
+    fn test_declarations() {
+        let header = c_header(&declarations(), "GEOMETRY_H");
+        assert!(!header.contains("Geometry_Empty"));
+        assert!(header.contains("    Geometry_Shape_a = 0,\n    Geometry_Shape_A = 1,\n    Geometry_Shape_int = 2,\n    Geometry_Shape_a_b = 3,\n    Geometry_Shape_a_b_2 = 4,\n"));
+        assert!(header.contains("typedef struct Geometry_Drawing {\n    uint8_t type;\n    Geometry_Shape shapes[2];\n    double scale;\n} Geometry_Drawing;"));
+        assert!(header.contains("_Static_assert(offsetof(Geometry_Drawing, scale) == 33, \"Geometry_Drawing.scale is at offset 33\");"));
+        assert!(header.contains("void Geometry_draw(Geometry_Drawing, int64_t);"));
+    }
+
+    #[test]
+    fn test_compiles() {
+        let directory = std::env::temp_dir().join(format!("roussillon-codegen-c-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let header = directory.join("geometry.h");
+        std::fs::write(&header, c_header(&declarations(), "GEOMETRY_H")).unwrap();
+        let output = Command::new("cc").args(["-std=c11", "-pedantic-errors", "-Wall", "-Werror", "-fsyntax-only"]).arg(&header).output();
+        std::fs::remove_dir_all(&directory).unwrap();
+        match output {
+            Ok(output) => assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr)),
+            Err(error) => eprintln!("The header is not compiled, cc is not available : {}", error),
+        }
+    }
+}
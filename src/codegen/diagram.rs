@@ -0,0 +1,325 @@
+//! Generation of class diagrams (PlantUML) and dependency graphs (Graphviz DOT).
+//!
+//! A [Diagram] has a node for each [Structure] and [Enumeration], grouped by [crate::identity::Identifier] space,
+//! listing its fields or its variants with their types. Its edges are :
+//! - the named types contained in a field or a variant, with the length of the lists containing them,
+//! - the targets of [Primitive::Reference]s, as dashed edges,
+//! - the implementations of interfaces (with the `experiments` feature).
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::codegen::diagram::Diagram;
+//! use roussillon_type_system::facade::create_struct;
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::types::concept::Type;
+//! use roussillon_type_system::types::primitive::Primitive;
+//!
+//! let point = create_struct("Geometry/Point", LabelBank::from(&["x", "y"]), &[
+//!     Primitive::Float.to_rc(),
+//!     Primitive::Float.to_rc(),
+//! ]);
+//! let polygon = create_struct("Geometry/Polygon", LabelBank::from(&["vertices"]), &[
+//!     Primitive::list(point, 3).to_rc(),
+//! ]);
+//! let mut diagram = Diagram::new();
+//! diagram.add(&(polygon as Type));
+//!
+//! assert!(diagram.plantuml().contains("Geometry_Polygon *-- \"3\" Geometry_Point : vertices"));
+//! assert!(diagram.dot().contains("Geometry_Polygon -> Geometry_Point [label=\"vertices [3]\", arrowtail=diamond, dir=both];"));
+//! ```
+
+use crate::codegen::{identifier_name, identifier_of, named_types};
+use crate::json::mapping::{field_labels, variant_labels};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::primitive::Primitive;
+use crate::types::registry::Registry;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+#[cfg(feature = "experiments")]
+use crate::types::concept::DataType;
+#[cfg(feature = "experiments")]
+use crate::types::interface::Interface;
+
+/// How a node depends on another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// The node contains the target, `count` times.
+    Contains { count: usize },
+    /// The node holds a reference to the target.
+    References,
+    /// The node implements the target interface.
+    Implements,
+}
+
+/// An edge of a [Diagram], between the names of two nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    pub kind: EdgeKind,
+}
+
+/// A set of types to draw.
+#[derive(Clone, Debug, Default)]
+pub struct Diagram {
+    types: Vec<Type>,
+    #[cfg(feature = "experiments")]
+    interfaces: Vec<(String, Interface)>,
+    #[cfg(feature = "experiments")]
+    implementations: Vec<(String, String)>,
+}
+
+impl Diagram {
+    pub fn new() -> Self { Self::default() }
+
+    /// A diagram of the structures and enumerations of a registry.
+    pub fn from_registry(registry: &Registry) -> Self {
+        let mut diagram = Self::new();
+        for data_type in registry.definitions().iter().filter_map(|definition| definition.to_type()) {
+            diagram.add(&data_type);
+        }
+        diagram
+    }
+
+    /// Adds the named types contained or referenced by `data_type`.
+    pub fn add(&mut self, data_type: &Type) {
+        named_types(data_type, &mut self.types, true);
+    }
+
+    /// Adds an interface node named `name`.
+    #[cfg(feature = "experiments")]
+    pub fn add_interface(&mut self, name: &str, interface: &Interface) {
+        self.interfaces.push((name.to_string(), interface.clone()));
+    }
+
+    /// Adds an edge from `data_type` to the interface named `interface`.
+    #[cfg(feature = "experiments")]
+    pub fn implements(&mut self, data_type: &Type, interface: &str) {
+        self.add(data_type);
+        self.implementations.push((node_name(data_type), interface.to_string()));
+    }
+
+    /// The edges between the nodes.
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for data_type in &self.types {
+            let from = node_name(data_type);
+            for (label, member) in members(data_type) {
+                member_edges(&from, &label, &member, 1, false, &mut edges);
+            }
+        }
+        #[cfg(feature = "experiments")]
+        for (from, to) in &self.implementations {
+            edges.push(Edge { from: from.clone(), to: to.clone(), label: String::new(), kind: EdgeKind::Implements });
+        }
+        edges
+    }
+
+    fn spaces(&self) -> Vec<String> {
+        let mut spaces: Vec<String> = Vec::new();
+        for data_type in &self.types {
+            let space = identifier_of(data_type).unwrap().space;
+            if !spaces.contains(&space) {
+                spaces.push(space);
+            }
+        }
+        spaces
+    }
+
+    fn in_space<'a>(&'a self, space: &'a str) -> impl Iterator<Item=&'a Type> + 'a {
+        self.types.iter().filter(move |data_type| identifier_of(data_type).unwrap().space == space)
+    }
+
+    /// Returns a PlantUML class diagram.
+    pub fn plantuml(&self) -> String {
+        let mut output = String::from("@startuml\n");
+        for space in self.spaces() {
+            let indent = if space.is_empty() { "" } else { "    " };
+            if !space.is_empty() {
+                output.push_str(&format!("package \"{}\" {{\n", space));
+            }
+            for data_type in self.in_space(&space) {
+                let keyword = if data_type.downcast_ref::<Structure>().is_some() { "class" } else { "enum" };
+                output.push_str(&format!("{}{} \"{}\" as {} {{\n", indent, keyword, data_type.typename(), node_name(data_type)));
+                for (label, member) in members(data_type) {
+                    output.push_str(&format!("{}    {} : {}\n", indent, label, member.typename()));
+                }
+                output.push_str(&format!("{}}}\n", indent));
+            }
+            if !space.is_empty() {
+                output.push_str("}\n");
+            }
+        }
+        #[cfg(feature = "experiments")]
+        for (name, interface) in &self.interfaces {
+            output.push_str(&format!("interface {} {{\n", name));
+            for function in interface.functions() {
+                output.push_str(&format!("    {} : {}\n", function.declaration.identifier.name, function.declaration.signature.typename()));
+            }
+            output.push_str("}\n");
+        }
+        for edge in self.edges() {
+            output.push_str(&match edge.kind {
+                EdgeKind::Contains { count: 1 } => format!("{} *-- {} : {}\n", edge.from, edge.to, edge.label),
+                EdgeKind::Contains { count } => format!("{} *-- \"{}\" {} : {}\n", edge.from, count, edge.to, edge.label),
+                EdgeKind::References => format!("{} --> {} : {}\n", edge.from, edge.to, edge.label),
+                EdgeKind::Implements => format!("{} <|.. {}\n", edge.to, edge.from),
+            });
+        }
+        output.push_str("@enduml\n");
+        output
+    }
+
+    /// Returns a Graphviz DOT dependency graph.
+    pub fn dot(&self) -> String {
+        let mut output = String::from("digraph types {\n    node [shape=record, fontname=\"monospace\"];\n");
+        for space in self.spaces() {
+            let indent = if space.is_empty() { "    " } else { "        " };
+            if !space.is_empty() {
+                output.push_str(&format!("    subgraph \"cluster_{}\" {{\n        label=\"{}\";\n", space, space));
+            }
+            for data_type in self.in_space(&space) {
+                let members: Vec<String> = members(data_type).iter()
+                    .map(|(label, member)| format!("{} : {}\\l", escape(label), escape(&member.typename())))
+                    .collect();
+                output.push_str(&format!("{}{} [label=\"{{{}|{}}}\"];\n", indent, node_name(data_type), escape(&data_type.typename()), members.join("")));
+            }
+            if !space.is_empty() {
+                output.push_str("    }\n");
+            }
+        }
+        #[cfg(feature = "experiments")]
+        for (name, interface) in &self.interfaces {
+            let functions: Vec<String> = interface.functions().iter()
+                .map(|function| format!("{} : {}\\l", escape(&function.declaration.identifier.name), escape(&function.declaration.signature.typename())))
+                .collect();
+            output.push_str(&format!("    {} [label=\"{{«interface» {}|{}}}\"];\n", name, escape(name), functions.join("")));
+        }
+        for edge in self.edges() {
+            output.push_str(&match edge.kind {
+                EdgeKind::Contains { count: 1 } => format!("    {} -> {} [label=\"{}\", arrowtail=diamond, dir=both];\n", edge.from, edge.to, edge.label),
+                EdgeKind::Contains { count } => format!("    {} -> {} [label=\"{} [{}]\", arrowtail=diamond, dir=both];\n", edge.from, edge.to, edge.label, count),
+                EdgeKind::References => format!("    {} -> {} [label=\"{}\", style=dashed];\n", edge.from, edge.to, edge.label),
+                EdgeKind::Implements => format!("    {} -> {} [style=dashed, arrowhead=empty];\n", edge.from, edge.to),
+            });
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+fn node_name(data_type: &Type) -> String { identifier_name(&identifier_of(data_type).unwrap()) }
+
+/// Escapes the characters of a DOT record label.
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        if "{}|<>\"\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+/// The labelled fields of a structure or variants of an enumeration.
+fn members(data_type: &Type) -> Vec<(String, Type)> {
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        return field_labels(structure).into_iter().zip(structure.product_type.to_tuple()).collect();
+    }
+    let enumeration = data_type.downcast_ref::<Enumeration>().unwrap();
+    let sum = &enumeration.sum_type;
+    variant_labels(Some(enumeration.labels()), sum.len()).into_iter()
+        .enumerate()
+        .map(|(tag, key)| (key, sum.variant(tag).unwrap()))
+        .collect()
+}
+
+fn member_edges(from: &str, label: &str, member: &Type, count: usize, reference: bool, edges: &mut Vec<Edge>) {
+    if identifier_of(member).is_some() {
+        let kind = if reference { EdgeKind::References } else { EdgeKind::Contains { count } };
+        edges.push(Edge { from: from.to_string(), to: node_name(member), label: label.to_string(), kind });
+        return;
+    }
+    if let Some(primitive) = member.downcast_ref::<Primitive>() {
+        match primitive {
+            Primitive::List((of_type, len)) => member_edges(from, label, of_type, count * len, reference, edges),
+            Primitive::Reference(to_type) => member_edges(from, label, to_type, 1, true, edges),
+            _ => {}
+        }
+        return;
+    }
+    let inner = member.downcast_ref::<Tuple>().cloned()
+        .or_else(|| member.downcast_ref::<ProductType>().map(ProductType::to_tuple))
+        .or_else(|| member.downcast_ref::<SumType>().map(|sum| (0..sum.len()).map(|tag| sum.variant(tag).unwrap()).collect()));
+    for (index, inner) in inner.unwrap_or_default().iter().enumerate() {
+        member_edges(from, &format!("{}.{}", label, index), inner, count, reference, edges);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::codegen::diagram::{Diagram, Edge, EdgeKind};
+    use crate::facade::{create_enum, create_struct};
+    use crate::identity::LabelBank;
+    use crate::types::algebraic::SumType;
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::types::registry::Registry;
+    use crate::types::sequence::Tuple;
+
+    fn edge(from: &str, to: &str, label: &str, kind: EdgeKind) -> Edge {
+        Edge { from: from.to_string(), to: to.to_string(), label: label.to_string(), kind }
+    }
+
+    fn diagram() -> Diagram {
+        let point: Type = create_struct("Geometry/Point", LabelBank::from(&["x", "y"]), &[
+            Primitive::Float.to_rc(),
+            Primitive::Float.to_rc(),
+        ]);
+        let owner: Type = create_struct("Owner", LabelBank::from(&["name"]), &[Primitive::Bytes(8).to_rc()]);
+        let either: Type = SumType::new(&[Primitive::Integer.to_rc(), Primitive::Float.to_rc()]).to_rc();
+        let shape = create_enum("Geometry/Shape", LabelBank::from(&["Grid", "Pair", "Owned", "Either"]), &[
+            Primitive::list(Primitive::list(point.clone(), 3).to_rc(), 2).to_rc(),
+            Rc::new(vec![Primitive::Integer.to_rc(), point] as Tuple),
+            Primitive::Reference(owner).to_rc(),
+            either,
+        ]);
+        let mut registry = Registry::new();
+        registry.add_enumeration(shape);
+        Diagram::from_registry(&registry)
+    }
+
+    #[test]
+    fn test_edges() {
+        assert_eq!(diagram().edges(), vec![
+            edge("Geometry_Shape", "Geometry_Point", "Grid", EdgeKind::Contains { count: 6 }),
+            edge("Geometry_Shape", "Geometry_Point", "Pair.1", EdgeKind::Contains { count: 1 }),
+            edge("Geometry_Shape", "Owner", "Owned", EdgeKind::References),
+        ]);
+    }
+
+    #[test]
+    fn test_plantuml() {
+        let output = diagram().plantuml();
+        assert!(output.starts_with("@startuml\npackage \"Geometry\" {\n    enum \"Geometry/Shape\" as Geometry_Shape {\n"));
+        assert!(output.contains("    class \"Geometry/Point\" as Geometry_Point {\n        x : float\n"));
+        assert!(output.contains("}\nclass \"Owner\" as Owner {\n    name : arch\n}\n"));
+        assert!(output.contains("Geometry_Shape *-- \"6\" Geometry_Point : Grid\n"));
+        assert!(output.contains("Geometry_Shape *-- Geometry_Point : Pair.1\n"));
+        assert!(output.ends_with("Geometry_Shape --> Owner : Owned\n@enduml\n"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let output = diagram().dot();
+        assert!(output.contains("    subgraph \"cluster_Geometry\" {\n        label=\"Geometry\";\n"));
+        assert!(output.contains("        Geometry_Shape [label=\"{Geometry/Shape|Grid : "));
+        assert!(output.contains("Owned : &Owner\\lEither : \\<integer\\|float\\>\\l}\"];\n"));
+        assert!(output.contains("    Geometry_Shape -> Owner [label=\"Owned\", style=dashed];\n"));
+    }
+}
@@ -0,0 +1,321 @@
+//! Generation of Markdown reference pages.
+//!
+//! A [Book] writes a [Page] for each namespace of a [Registry], with a section for each definition :
+//! - structures list their fields with their types, offsets and sizes,
+//! - enumerations list their variants with their tags, payloads and sizes,
+//! - functions show their signature,
+//! - interfaces list their functions (with the `experiments` feature).
+//!
+//! Each section starts with the doc string and the deprecation of the [Annotations] of its definition,
+//! and the annotated fields and variants have a description column.
+//! Named types are linked to their section, in the same page or in the page of their namespace.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::annotation::Annotations;
+//! use roussillon_type_system::codegen::markdown::Book;
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::types::algebraic::ProductType;
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::types::registry::Registry;
+//! use roussillon_type_system::types::typedef::Structure;
+//!
+//! let point = Structure::new("Geometry/Point", LabelBank::from(&["x", "y"]), ProductType::new(&[
+//!     Primitive::Float.to_rc(),
+//!     Primitive::Float.to_rc(),
+//! ])).with_annotations(Annotations::new().with_doc("A point of the plane.")).to_rc();
+//! let mut registry = Registry::new();
+//! registry.add_structure(point);
+//!
+//! let pages = Book::new(&registry).pages();
+//! assert_eq!(pages[0].file_name, "Geometry.md");
+//! assert!(pages[0].content.contains("## Point\n\n*Structure*, 16 bytes.\n\nA point of the plane.\n"));
+//! assert!(pages[0].content.contains("| `y` | `float` | 8 | 8 |"));
+//! ```
+
+use crate::annotation::Annotations;
+use crate::codegen::{identifier_of, offsets, sanitize};
+use crate::identity::{Identified, Identifier, LabelBank};
+use crate::json::mapping::{field_labels, variant_labels};
+use crate::types::concept::{DataType, Type};
+use crate::types::functional::FunctionDeclaration;
+use crate::types::primitive::Primitive;
+use crate::types::registry::{Definition, Registry};
+use crate::types::typedef::{Enumeration, Structure};
+#[cfg(feature = "experiments")]
+use crate::types::interface::Interface;
+
+/// A Markdown page.
+#[derive(Clone, Debug)]
+pub struct Page {
+    /// The namespace documented by the page.
+    pub space: String,
+    pub file_name: String,
+    pub content: String,
+}
+
+/// The name of the page of a namespace, `index.md` for the global namespace.
+pub fn file_name(space: &str) -> String {
+    if space.is_empty() { "index.md".to_string() } else { format!("{}.md", sanitize(space)) }
+}
+
+/// The anchor of a heading, following the GitHub rules.
+fn anchor(heading: &str) -> String {
+    heading.to_lowercase().chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Escapes the pipes of a table cell.
+fn cell(text: &str) -> String { text.replace('|', "\\|") }
+
+/// The link to the section of a definition, from the page of `space`.
+fn link(identifier: &Identifier, space: &str) -> String {
+    if identifier.space == space {
+        format!("[{}](#{})", identifier.name, anchor(&identifier.name))
+    } else {
+        format!("[{}]({}#{})", identifier, file_name(&identifier.space), anchor(&identifier.name))
+    }
+}
+
+/// The Markdown text of a type, with links to the named types.
+fn type_text(data_type: &Type, space: &str) -> String {
+    if let Some(identifier) = identifier_of(data_type) {
+        return link(&identifier, space);
+    }
+    match data_type.downcast_ref::<Primitive>() {
+        Some(Primitive::List((of_type, len))) if identifier_of(of_type).is_some() => format!("\\[{}; {}\\]", type_text(of_type, space), len),
+        Some(Primitive::Reference(to_type)) if identifier_of(to_type).is_some() => format!("&{}", type_text(to_type, space)),
+        _ => format!("`{}`", data_type.typename()),
+    }
+}
+
+/// The reference documentation of a registry.
+pub struct Book<'a> {
+    registry: &'a Registry,
+    #[cfg(feature = "experiments")]
+    interfaces: Vec<(Identifier, Interface)>,
+}
+
+impl<'a> Book<'a> {
+    pub fn new(registry: &'a Registry) -> Self {
+        Self {
+            registry,
+            #[cfg(feature = "experiments")]
+            interfaces: Vec::new(),
+        }
+    }
+
+    /// Documents an interface, named `identifier` like `Space/Name`.
+    #[cfg(feature = "experiments")]
+    pub fn add_interface(&mut self, identifier: &str, interface: &Interface) {
+        self.interfaces.push((Identifier::new(identifier), interface.clone()));
+    }
+
+    fn spaces(&self) -> Vec<String> {
+        #[allow(unused_mut)]
+        let mut spaces = self.registry.namespaces();
+        #[cfg(feature = "experiments")]
+        for (identifier, _) in &self.interfaces {
+            if !spaces.contains(&identifier.space) {
+                spaces.push(identifier.space.clone());
+            }
+        }
+        spaces
+    }
+
+    /// Returns a page for each namespace.
+    pub fn pages(&self) -> Vec<Page> {
+        self.spaces().into_iter().map(|space| {
+            let content = self.page(&space);
+            Page { file_name: file_name(&space), space, content }
+        }).collect()
+    }
+
+    fn page(&self, space: &str) -> String {
+        let mut sections: Vec<(String, String)> = Vec::new();
+        for definition in self.registry.in_namespace(space) {
+            let name = definition.name();
+            let mut section = format!("## {}\n\n", name);
+            match definition {
+                Definition::Structure(structure) => section.push_str(&format!("*Structure*, {} bytes.\n\n", structure.size())),
+                Definition::Enumeration(enumeration) => section.push_str(&format!("*Enumeration*, {} bytes.\n\n", enumeration.size())),
+                Definition::Function(_) => section.push_str("*Function*\n\n"),
+            }
+            section.push_str(&description(definition.annotations()));
+            match definition {
+                Definition::Structure(structure) => section.push_str(&fields(structure, space)),
+                Definition::Enumeration(enumeration) => section.push_str(&variants(enumeration, space)),
+                Definition::Function(function) => section.push_str(&signature(function, space)),
+            }
+            sections.push((name, section));
+        }
+        #[cfg(feature = "experiments")]
+        for (identifier, interface) in self.interfaces.iter().filter(|(identifier, _)| identifier.space == space) {
+            let mut section = format!("## {}\n\n*Interface*\n\n", identifier.name);
+            for function in interface.functions() {
+                section.push_str(&format!("- `{}` : {}\n", function.declaration.identifier.name, type_text(&(function.declaration.signature.clone().to_rc() as Type), space)));
+            }
+            section.push('\n');
+            sections.push((identifier.name.clone(), section));
+        }
+
+        let title = if space.is_empty() { "Global namespace".to_string() } else { format!("Namespace `{}`", space) };
+        let mut page = format!("# {}\n\n", title);
+        for (name, _) in &sections {
+            page.push_str(&format!("- [{}](#{})\n", name, anchor(name)));
+        }
+        for (_, section) in sections {
+            page.push('\n');
+            page.push_str(&section);
+        }
+        page
+    }
+}
+
+/// The doc string and the deprecation of a definition.
+fn description(annotations: &Annotations) -> String {
+    let mut text = String::new();
+    if let Some(reason) = &annotations.deprecated {
+        text.push_str(&format!("{}\n\n", format!("**Deprecated** {}", reason).trim_end()));
+    }
+    if let Some(doc) = &annotations.doc {
+        text.push_str(&format!("{}\n\n", doc));
+    }
+    text
+}
+
+/// The description cell of a field or a variant, on a single line.
+fn summary(annotations: Option<&Annotations>) -> String {
+    let Some(annotations) = annotations else { return String::new() };
+    let mut parts = Vec::new();
+    if let Some(reason) = &annotations.deprecated {
+        parts.push(format!("**Deprecated** {}", reason).trim_end().to_string());
+    }
+    if let Some(doc) = &annotations.doc {
+        parts.push(doc.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    cell(&parts.join(" "))
+}
+
+/// The description column of a table, if one of its rows is documented or deprecated.
+fn describes(labels: &LabelBank, count: usize) -> bool {
+    (0..count).any(|index| labels.annotations(index).is_some_and(|annotations| annotations.doc.is_some() || annotations.is_deprecated()))
+}
+
+fn fields(structure: &Structure, space: &str) -> String {
+    let fields: Vec<(String, Type)> = field_labels(structure).into_iter().zip(structure.product_type.to_tuple()).collect();
+    let described = describes(&structure.labels, fields.len());
+    let mut table = String::from(if described {
+        "| Field | Type | Offset | Size | Description |\n|---|---|---|---|---|\n"
+    } else {
+        "| Field | Type | Offset | Size |\n|---|---|---|---|\n"
+    });
+    for (index, ((label, field), offset)) in fields.iter().zip(offsets(&fields)).enumerate() {
+        table.push_str(&format!("| `{}` | {} | {} | {} |", label, cell(&type_text(field, space)), offset, field.size()));
+        if described {
+            table.push_str(&format!(" {} |", summary(structure.labels.annotations(index))));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+fn variants(enumeration: &Enumeration, space: &str) -> String {
+    let sum = &enumeration.sum_type;
+    let described = describes(enumeration.labels(), sum.len());
+    let mut table = String::from(if described {
+        "| Tag | Variant | Payload | Size | Description |\n|---|---|---|---|---|\n"
+    } else {
+        "| Tag | Variant | Payload | Size |\n|---|---|---|---|\n"
+    });
+    for (tag, label) in variant_labels(Some(enumeration.labels()), sum.len()).iter().enumerate() {
+        let payload = sum.variant(tag).unwrap();
+        table.push_str(&format!("| {} | `{}` | {} | {} |", tag, label, cell(&type_text(&payload, space)), payload.size()));
+        if described {
+            table.push_str(&format!(" {} |", summary(enumeration.labels().annotations(tag))));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+fn signature(function: &FunctionDeclaration, space: &str) -> String {
+    let mut text = format!("```\n{} : {}\n```\n\n| Argument | Type |\n|---|---|\n", function.identifier.name, function.signature.typename());
+    for (index, argument) in function.signature.arguments.iter().enumerate() {
+        text.push_str(&format!("| {} | {} |\n", index, cell(&type_text(argument, space))));
+    }
+    text.push_str(&format!("\nReturns {}.\n", type_text(&function.signature.return_type, space)));
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use crate::annotation::Annotations;
+    use crate::codegen::markdown::{file_name, Book};
+    use crate::facade::create_struct;
+    use crate::identity::{Identifier, LabelBank};
+    use crate::types::algebraic::SumType;
+    use crate::types::concept::Type;
+    use crate::types::functional::{FunctionDeclaration, FunctionType};
+    use crate::types::primitive::Primitive;
+    use crate::types::registry::Registry;
+    use crate::types::typedef::Enumeration;
+
+    fn registry() -> Registry {
+        let point = create_struct("Geometry/Point", LabelBank::from(&["x", "y"]), &[
+            Primitive::Float.to_rc(),
+            Primitive::Float.to_rc(),
+        ]);
+        let either: Type = SumType::new(&[Primitive::Integer.to_rc(), Primitive::Float.to_rc()]).to_rc();
+        let labels = LabelBank::from(&["Empty", "Points", "Either"])
+            .annotated("Points", Annotations::new().with_doc("Some\npoints.").with_deprecated("Use Path."));
+        let shape = Enumeration::new("Drawing Tools/Shape", labels, SumType::new(&[
+            Primitive::Bytes(0).to_rc(),
+            Primitive::list(point.clone(), 4).to_rc(),
+            either,
+        ])).with_annotations(Annotations::new().with_deprecated("").with_doc("A shape.")).to_rc();
+        let mut registry = Registry::new();
+        registry.add_structure(point.clone());
+        registry.add_enumeration(shape.clone());
+        registry.add_function(FunctionDeclaration::new(
+            Identifier::new("draw"),
+            FunctionType::new(vec![shape, Primitive::Reference(point).to_rc()], Primitive::Boolean.to_rc()),
+        ));
+        registry
+    }
+
+    #[test]
+    fn test_file_names() {
+        assert_eq!(file_name(""), "index.md");
+        assert_eq!(file_name("Drawing Tools"), "Drawing_Tools.md");
+        let names: Vec<String> = Book::new(&registry()).pages().into_iter().map(|page| page.file_name).collect();
+        assert_eq!(names, vec!["Geometry.md", "Drawing_Tools.md", "index.md"]);
+    }
+
+    #[test]
+    fn test_tables() {
+        let pages = Book::new(&registry()).pages();
+        let geometry = &pages[0].content;
+        assert!(geometry.starts_with("# Namespace `Geometry`\n\n- [Point](#point)\n\n## Point\n\n*Structure*, 16 bytes.\n\n| Field | Type | Offset | Size |\n"));
+        let shapes = &pages[1].content;
+        assert!(shapes.contains("## Shape\n\n*Enumeration*, 72 bytes.\n\n**Deprecated**\n\nA shape.\n\n| Tag | Variant | Payload | Size | Description |\n"));
+        assert!(shapes.contains("| 0 | `Empty` | `bytes<0>` | 0 |  |\n"));
+        assert!(shapes.contains("| 1 | `Points` | \\[[Geometry/Point](Geometry.md#point); 4\\] | 64 | **Deprecated** Use Path. Some points. |\n"));
+        assert!(shapes.contains("| 2 | `Either` | `<integer\\|float>` | 16 |  |\n"));
+    }
+
+    #[test]
+    fn test_functions() {
+        let pages = Book::new(&registry()).pages();
+        let global = &pages[2].content;
+        assert!(global.starts_with("# Global namespace\n\n- [draw](#draw)\n\n## draw\n\n*Function*\n\n```\ndraw : "));
+        assert!(global.contains("| 0 | [Drawing Tools/Shape](Drawing_Tools.md#shape) |\n| 1 | &[Geometry/Point](Geometry.md#point) |\n"));
+        assert!(global.ends_with("\nReturns `boolean`.\n"));
+    }
+}
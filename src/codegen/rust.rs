@@ -0,0 +1,206 @@
+//! Generation of Rust items.
+//!
+//! - records are `#[repr(C, packed)]` structs,
+//! - tagged types are `#[repr(C, u64)]` enums, the discriminant of each variant is its tag,
+//! - functions are `extern "C" fn` type aliases,
+//! - each type is followed by `const` assertions of its size and of the offsets of its fields.
+//!
+//! An enum is aligned on its `u64` tag, so its size is the size of its raw buffer rounded up to a multiple of 8,
+//! see [rust_size]. Its discriminant is in native byte order.
+
+use crate::codegen::{contained_types, is_unit, Declarations, Item};
+use crate::types::algebraic::{SumType, TAG_SIZE};
+use crate::types::concept::Type;
+use crate::types::functional::FunctionType;
+use crate::types::primitive::Primitive;
+use crate::types::typedef::Enumeration;
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "static", "struct",
+    "trait", "true", "try", "type", "unsafe", "use", "where", "while", "union", "yield",
+];
+
+fn field_name(key: &str) -> String {
+    match key {
+        "self" | "Self" | "super" | "crate" => format!("{}_", key),
+        key if KEYWORDS.contains(&key) => format!("r#{}", key),
+        key => key.to_string(),
+    }
+}
+
+/// The size of the Rust type of `data_type` : its raw size, with each tagged type padded to a multiple of 8.
+pub fn rust_size(data_type: &Type) -> usize {
+    if let Some(Primitive::List((of_type, len))) = data_type.downcast_ref::<Primitive>() {
+        return len * rust_size(of_type);
+    }
+    let members = contained_types(data_type, false);
+    if data_type.downcast_ref::<SumType>().is_some() || data_type.downcast_ref::<Enumeration>().is_some() {
+        let payload = members.iter().map(rust_size).max().unwrap_or_default();
+        (TAG_SIZE + payload).next_multiple_of(TAG_SIZE)
+    } else if members.is_empty() {
+        data_type.size()
+    } else {
+        members.iter().map(rust_size).sum()
+    }
+}
+
+/// The Rust type of `data_type`, its composite types must be declared.
+pub fn rust_type(declarations: &Declarations, data_type: &Type) -> String {
+    if is_unit(data_type) {
+        return "()".to_string();
+    }
+    if let Some(name) = declarations.name_of(data_type) {
+        return name.to_string();
+    }
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return match primitive {
+            Primitive::Boolean => "bool".to_string(),
+            Primitive::Byte => "u8".to_string(),
+            Primitive::Bytes(size) => format!("[u8; {}]", size),
+            Primitive::Float => "f64".to_string(),
+            Primitive::Integer => "i64".to_string(),
+            Primitive::Reference(_) => "u64".to_string(),
+            Primitive::List((of_type, len)) => format!("[{}; {}]", rust_type(declarations, of_type), len),
+        };
+    }
+    if let Some(function) = data_type.downcast_ref::<FunctionType>() {
+        return function_type(declarations, function);
+    }
+    data_type.typename()
+}
+
+fn function_type(declarations: &Declarations, signature: &FunctionType) -> String {
+    let arguments: Vec<String> = signature.arguments.iter()
+        .filter(|argument| !is_unit(argument))
+        .map(|argument| rust_type(declarations, argument))
+        .collect();
+    let mut function = format!("extern \"C\" fn({})", arguments.join(", "));
+    if !is_unit(&signature.return_type) {
+        function.push_str(&format!(" -> {}", rust_type(declarations, &signature.return_type)));
+    }
+    function
+}
+
+fn assertions(output: &mut String, name: &str, size: usize, fields: &[(String, Type)]) {
+    output.push_str(&format!("const _: () = assert!(core::mem::size_of::<{}>() == {});\n", name, size));
+    let mut offset = 0;
+    for (key, field) in fields {
+        output.push_str(&format!("const _: () = assert!(core::mem::offset_of!({}, {}) == {});\n", name, field_name(key), offset));
+        offset += rust_size(field);
+    }
+}
+
+/// Returns a Rust module declaring every item of `declarations`.
+pub fn rust_module(declarations: &Declarations) -> String {
+    let mut output = String::from(
+        "// Generated by roussillon-type-system, do not edit.\n\
+         // Numbers are in the byte order of raw buffers (big-endian), enum discriminants in native byte order.\n\
+         #![allow(non_camel_case_types, non_snake_case, non_upper_case_globals, dead_code)]\n\n"
+    );
+    for item in declarations.items() {
+        match item {
+            Item::Record { name, fields, .. } => {
+                output.push_str(&format!("#[repr(C, packed)]\n#[derive(Clone, Copy)]\npub struct {} {{\n", name));
+                for (key, field) in fields {
+                    output.push_str(&format!("    pub {}: {},\n", field_name(key), rust_type(declarations, field)));
+                }
+                output.push_str("}\n\n");
+                assertions(&mut output, name, fields.iter().map(|(_, field)| rust_size(field)).sum(), fields);
+            }
+            Item::Tagged { name, variants, .. } => {
+                output.push_str(&format!("#[repr(C, u64)]\n#[derive(Clone, Copy)]\npub enum {} {{\n", name));
+                for (tag, (key, variant)) in variants.iter().enumerate() {
+                    if is_unit(variant) {
+                        output.push_str(&format!("    {} = {},\n", field_name(key), tag));
+                    } else {
+                        output.push_str(&format!("    {}({}) = {},\n", field_name(key), rust_type(declarations, variant), tag));
+                    }
+                }
+                output.push_str("}\n\n");
+                let payload = variants.iter().map(|(_, variant)| rust_size(variant)).max().unwrap_or_default();
+                assertions(&mut output, name, (TAG_SIZE + payload).next_multiple_of(TAG_SIZE), &[]);
+            }
+            Item::Function { name, signature } => {
+                output.push_str(&format!("pub type {} = {};\n", name, function_type(declarations, signature)));
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+    use std::rc::Rc;
+
+    use crate::codegen::Declarations;
+    use crate::codegen::rust::{rust_module, rust_size};
+    use crate::facade::{create_enum, create_struct};
+    use crate::identity::{Identifier, LabelBank};
+    use crate::types::concept::Type;
+    use crate::types::functional::{FunctionDeclaration, FunctionType};
+    use crate::types::primitive::Primitive;
+    use crate::types::sequence::Tuple;
+
+    fn declarations() -> Declarations {
+        let unit: Type = Rc::new(Tuple::new());
+        let shape: Type = create_enum("Geometry/Shape", LabelBank::from(&["a", "A", "fn", "self"]), &[
+            Primitive::Boolean.to_rc(),
+            unit.clone(),
+            Primitive::Float.to_rc(),
+            Primitive::Bytes(3).to_rc(),
+        ]);
+        let empty: Type = create_struct("Geometry/Empty", LabelBank::from(&["nothing"]), &[unit]);
+        let drawing: Type = create_struct("Geometry/Drawing", LabelBank::from(&["type", "shapes", "empty", "scale"]), &[
+            Primitive::Byte.to_rc(),
+            Primitive::List((shape.clone(), 2)).to_rc(),
+            empty.clone(),
+            Primitive::Float.to_rc(),
+        ]);
+        let mut declarations = Declarations::new();
+        declarations.declare("Drawing", &drawing).unwrap();
+        declarations.declare_function(&FunctionDeclaration::new(
+            Identifier::new("Geometry/draw"),
+            FunctionType::new(vec![drawing, empty, Primitive::Integer.to_rc()], shape),
+        )).unwrap();
+        declarations
+    }
+
+    #[test]
+    fn test_enums() {
+        let module = rust_module(&declarations());
+        assert!(module.contains(
+            "#[repr(C, u64)]\n#[derive(Clone, Copy)]\npub enum Geometry_Shape {\n    a(bool) = 0,\n    A = 1,\n    r#fn(f64) = 2,\n    self_([u8; 3]) = 3,\n}"
+        ));
+        assert!(module.contains("const _: () = assert!(core::mem::size_of::<Geometry_Shape>() == 16);"));
+        assert!(module.contains("const _: () = assert!(core::mem::offset_of!(Geometry_Drawing, scale) == 33);"));
+        assert!(module.contains("pub type Geometry_draw = extern \"C\" fn(Geometry_Drawing, Geometry_Empty, i64) -> Geometry_Shape;"));
+    }
+
+    #[test]
+    fn test_sizes() {
+        let shape: Type = create_enum("Geometry/Shape", LabelBank::from(&["a"]), &[Primitive::Bytes(9).to_rc()]);
+        assert_eq!(shape.size(), 17);
+        assert_eq!(rust_size(&shape), 24);
+        assert_eq!(rust_size(&Primitive::List((shape, 3)).to_rc()), 72);
+        assert_eq!(rust_size(&Primitive::Integer.to_rc()), 8);
+    }
+
+    #[test]
+    fn test_compiles() {
+        let directory = std::env::temp_dir().join(format!("roussillon-codegen-rust-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("generated.rs");
+        std::fs::write(&source, rust_module(&declarations())).unwrap();
+        let output = Command::new("rustc")
+            .args(["--crate-type", "lib", "--edition", "2021", "--emit", "metadata", "--out-dir"])
+            .arg(&directory)
+            .arg(&source)
+            .output()
+            .expect("rustc is available");
+        std::fs::remove_dir_all(&directory).unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
@@ -0,0 +1,254 @@
+//! Generation of TypeScript declarations of the [crate::json::mapping] of values.
+//!
+//! - a [Structure] is an `interface` with a property for each field key, skipped fields are left out,
+//! - an [Enumeration] is a union of single-property objects keyed by variant label,
+//!   a variant is told apart with `"label" in value`,
+//! - [Primitive::List] are arrays, [Tuple]s and [ProductType]s are tuples,
+//! - [Primitive::Bytes] are hexadecimal strings, and floats may be `"NaN"`, `"Infinity"` or `"-Infinity"`,
+//! - the [crate::identity::Identifier] space of a named type is its `namespace`.
+//!
+//! Doc strings and deprecations of [Annotations] become JSDoc comments.
+//!
+//! Function declarations have no JSON mapping and are left out.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::codegen::typescript::typescript_declarations;
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::types::algebraic::{ProductType, SumType};
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::types::registry::Registry;
+//! use roussillon_type_system::types::typedef::Enumeration;
+//!
+//! let option = Enumeration::new("Core/Option", LabelBank::from(&["None", "Some"]), SumType::new(&[
+//!     ProductType::unit_type().to_rc(),
+//!     Primitive::Integer.to_rc(),
+//! ])).to_rc();
+//! let mut registry = Registry::new();
+//! registry.add_enumeration(option);
+//!
+//! assert!(typescript_declarations(&registry).unwrap().contains(
+//!     "export namespace Core {\n    export type Option =\n        | { None: [] }\n        | { Some: number };\n}"
+//! ));
+//! ```
+
+use crate::annotation::Annotations;
+use crate::codegen::{identifier_of, is_unit, named_types, sanitize};
+use crate::identity::{Identified, Identifier, LabelBank};
+use crate::json::mapping::{field_keys, variant_keys, FLOAT_SPECIALS};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::primitive::Primitive;
+use crate::types::registry::{Definition, Registry};
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::error::{CodegenError, TypeResult};
+
+/// The property key of an object type, quoted if it is not an identifier.
+fn property(key: &str) -> String {
+    if sanitize(key) == key { key.to_string() } else { format!("{:?}", key) }
+}
+
+/// The TypeScript namespace of an identifier space, like `a.b`.
+fn namespace(space: &str) -> String {
+    space.split('.').map(sanitize).collect::<Vec<_>>().join(".")
+}
+
+/// The reference to a named type from the namespace `space`.
+fn reference(identifier: &Identifier, space: &str) -> String {
+    let name = sanitize(&identifier.name);
+    if identifier.space.is_empty() || identifier.space == space {
+        name
+    } else {
+        format!("{}.{}", namespace(&identifier.space), name)
+    }
+}
+
+/// The TypeScript type of the JSON documents of `data_type`, used in the namespace `space`.
+pub fn typescript_type(data_type: &Type, space: &str) -> TypeResult<String> {
+    if is_unit(data_type) {
+        return Ok("[]".to_string());
+    }
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return Ok(match primitive {
+            Primitive::Boolean => "boolean".to_string(),
+            Primitive::Byte | Primitive::Integer | Primitive::Reference(_) => "number".to_string(),
+            Primitive::Bytes(_) => "string".to_string(),
+            Primitive::Float => {
+                let specials: Vec<String> = FLOAT_SPECIALS.iter().map(|special| format!("{:?}", special)).collect();
+                format!("number | {}", specials.join(" | "))
+            }
+            Primitive::List((of_type, _)) => {
+                let element = typescript_type(of_type, space)?;
+                if element.contains(' ') { format!("({})[]", element) } else { format!("{}[]", element) }
+            }
+        });
+    }
+    let members = data_type.downcast_ref::<Tuple>().cloned()
+        .or_else(|| data_type.downcast_ref::<ProductType>().map(ProductType::to_tuple));
+    if let Some(members) = members {
+        let members = members.iter().map(|member| typescript_type(member, space)).collect::<TypeResult<Vec<_>>>()?;
+        return Ok(format!("[{}]", members.join(", ")));
+    }
+    if let Some(sum) = data_type.downcast_ref::<SumType>() {
+        return Ok(variants(sum, None, space)?.join(" | "));
+    }
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        return Ok(reference(&structure.identifier(), space));
+    }
+    if let Some(enumeration) = data_type.downcast_ref::<Enumeration>() {
+        return Ok(reference(&enumeration.identifier(), space));
+    }
+    Err(CodegenError::Unsupported { name: data_type.typename(), data_type: data_type.clone() }.promote())
+}
+
+fn variants(sum: &SumType, labels: Option<&LabelBank>, space: &str) -> TypeResult<Vec<String>> {
+    if sum.is_empty() {
+        return Ok(vec!["never".to_string()]);
+    }
+    variant_keys(labels, sum.len()).iter().enumerate()
+        .map(|(tag, key)| Ok(format!("{{ {}: {} }}", property(key), typescript_type(&sum.variant(tag).unwrap(), space)?)))
+        .collect()
+}
+
+/// The JSDoc comment of annotations with a doc string or a deprecation, each line starting with `indent`.
+fn jsdoc(annotations: Option<&Annotations>, indent: &str) -> String {
+    let Some(annotations) = annotations else { return String::new() };
+    let mut lines: Vec<String> = annotations.doc.iter().flat_map(|doc| doc.lines()).map(str::to_string).collect();
+    if let Some(reason) = &annotations.deprecated {
+        lines.push(format!("@deprecated {}", reason).trim_end().to_string());
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+    let mut comment = format!("{}/**\n", indent);
+    for line in lines {
+        comment.push_str(&if line.is_empty() { format!("{} *\n", indent) } else { format!("{} * {}\n", indent, line) });
+    }
+    comment.push_str(&format!("{} */\n", indent));
+    comment
+}
+
+fn declaration(data_type: &Type, space: &str) -> TypeResult<String> {
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        let mut declaration = jsdoc(Some(&structure.annotations), "");
+        declaration.push_str(&format!("export interface {} {{\n", sanitize(&structure.name())));
+        for (index, (key, field)) in field_keys(structure).iter().zip(structure.product_type.to_tuple().iter()).enumerate() {
+            let Some(key) = key else { continue };
+            declaration.push_str(&jsdoc(structure.labels.annotations(index), "    "));
+            declaration.push_str(&format!("    {}: {};\n", property(key), typescript_type(field, space)?));
+        }
+        declaration.push('}');
+        return Ok(declaration);
+    }
+    let enumeration = data_type.downcast_ref::<Enumeration>().unwrap();
+    let mut declaration = jsdoc(Some(&enumeration.annotations), "");
+    declaration.push_str(&format!("export type {} =", sanitize(&enumeration.name())));
+    for (tag, variant) in variants(&enumeration.sum_type, Some(enumeration.labels()), space)?.iter().enumerate() {
+        declaration.push('\n');
+        declaration.push_str(&jsdoc(enumeration.labels().annotations(tag), "    "));
+        declaration.push_str(&format!("    | {}", variant));
+    }
+    declaration.push(';');
+    Ok(declaration)
+}
+
+/// Returns the TypeScript declarations of the structures and enumerations of a registry,
+/// and of the named types they contain.
+pub fn typescript_declarations(registry: &Registry) -> TypeResult<String> {
+    let mut named = Vec::new();
+    for definition in registry.definitions() {
+        if let Definition::Structure(_) | Definition::Enumeration(_) = definition {
+            named_types(&definition.to_type().unwrap(), &mut named, false);
+        }
+    }
+    let mut spaces: Vec<String> = Vec::new();
+    for data_type in &named {
+        let space = identifier_of(data_type).unwrap().space;
+        if !spaces.contains(&space) {
+            spaces.push(space);
+        }
+    }
+    let mut output = String::from("// Generated by roussillon-type-system, do not edit.\n");
+    for space in spaces {
+        let mut declarations = Vec::new();
+        for data_type in named.iter().filter(|data_type| identifier_of(data_type).unwrap().space == space) {
+            declarations.push(declaration(data_type, &space)?);
+        }
+        output.push('\n');
+        if space.is_empty() {
+            output.push_str(&declarations.join("\n\n"));
+            output.push('\n');
+        } else {
+            let indented: Vec<String> = declarations.iter()
+                .map(|declaration| declaration.lines().map(|line| format!("    {}", line)).collect::<Vec<_>>().join("\n"))
+                .collect();
+            output.push_str(&format!("export namespace {} {{\n{}\n}}\n", namespace(&space), indented.join("\n\n")));
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::annotation::Annotations;
+    use crate::codegen::typescript::{typescript_declarations, typescript_type};
+    use crate::identity::LabelBank;
+    use crate::types::algebraic::{ProductType, SumType};
+    use crate::types::concept::Type;
+    use crate::types::functional::FunctionType;
+    use crate::types::primitive::Primitive;
+    use crate::types::registry::Registry;
+    use crate::types::sequence::Tuple;
+    use crate::types::typedef::{Enumeration, Structure};
+    use crate::value::error::{CodegenError, TypeError};
+
+    #[test]
+    fn test_types() {
+        let float: Type = Primitive::Float.to_rc();
+        let pair: Type = Rc::new(vec![Primitive::Boolean.to_rc(), Primitive::Bytes(4).to_rc()] as Tuple);
+        let sum: Type = SumType::new(&[ProductType::unit_type().to_rc(), Primitive::Integer.to_rc()]).to_rc();
+        assert_eq!(typescript_type(&float, "").unwrap(), "number | \"NaN\" | \"Infinity\" | \"-Infinity\"");
+        assert_eq!(typescript_type(&Primitive::List((float, 2)).to_rc(), "").unwrap(), "(number | \"NaN\" | \"Infinity\" | \"-Infinity\")[]");
+        assert_eq!(typescript_type(&Primitive::List((Primitive::Byte.to_rc(), 2)).to_rc(), "").unwrap(), "number[]");
+        assert_eq!(typescript_type(&pair, "").unwrap(), "[boolean, string]");
+        assert_eq!(typescript_type(&sum, "").unwrap(), "{ \"0\": [] } | { \"1\": number }");
+        assert_eq!(typescript_type(&(SumType::new(&[]).to_rc() as Type), "").unwrap(), "never");
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let function: Type = FunctionType::new(Tuple::new(), Primitive::Integer.to_rc()).to_rc();
+        let Err(TypeError::CodegenError(CodegenError::Unsupported { name, .. })) = typescript_type(&function, "") else {
+            panic!("a function has no JSON mapping");
+        };
+        assert_eq!(name, function.typename());
+        let list: Type = Primitive::List((function, 1)).to_rc();
+        assert!(matches!(typescript_type(&list, ""), Err(TypeError::CodegenError(CodegenError::Unsupported { .. }))));
+    }
+
+    #[test]
+    fn test_declarations() {
+        let labels = LabelBank::from(&["id", "secret", "first name"])
+            .annotated("id", Annotations::new().with_doc("The key.").with_rename("key"))
+            .annotated("secret", Annotations::new().with_skip());
+        let user = Structure::new("Users/User", labels, ProductType::new(&[
+            Primitive::Integer.to_rc(),
+            Primitive::Bytes(8).to_rc(),
+            Primitive::Boolean.to_rc(),
+        ])).with_annotations(Annotations::new().with_deprecated("Use Account.")).to_rc();
+        let event = Enumeration::new("Events/Event", LabelBank::from(&["Created"]), SumType::new(&[user])).to_rc();
+        let mut registry = Registry::new();
+        registry.add_enumeration(event);
+        let output = typescript_declarations(&registry).unwrap();
+        assert!(output.contains("export namespace Events {\n    export type Event =\n        | { Created: Users.User };\n}"));
+        assert!(output.contains(
+            "export namespace Users {\n    /**\n     * @deprecated Use Account.\n     */\n    export interface User {\n        /**\n         * The key.\n         */\n        key: number;\n        \"first name\": boolean;\n    }\n}"
+        ));
+        assert!(!output.contains("secret"));
+        assert!(output.find("namespace Events").unwrap() < output.find("namespace Users").unwrap());
+    }
+}
@@ -1,9 +1,10 @@
 use std::rc::Rc;
 use crate::identity::LabelBank;
-use crate::types::algebraic::ProductType;
+use crate::types::algebraic::{ProductType, SumType};
 use crate::types::concept::Type;
-use crate::types::typedef::Structure;
+use crate::types::typedef::{Enumeration, Structure};
 use crate::value::concept::ValueCell;
+use crate::value::copy::CloneCell;
 use crate::value::error::TypeResult;
 
 
@@ -12,6 +13,15 @@ pub fn create_struct(identifier: &str, labels: LabelBank, members: &[Type]) -> R
     Structure::new(identifier, labels, ProductType::new(members)).to_rc()
 }
 
+pub fn create_enum(identifier: &str, labels: LabelBank, variants: &[Type]) -> Rc<Enumeration> {
+    Enumeration::new(identifier, labels, SumType::new(variants)).to_rc()
+}
+
+/// Copies `from` as a value of `result_type` : a deep copy if it already has this type,
+/// otherwise a new value decoded from its raw data.
 pub fn copy_value(result_type: Type, from: &ValueCell) -> TypeResult<ValueCell> {
+    if from.borrow().data_type().typename() == result_type.typename() {
+        return from.deep_clone();
+    }
     result_type.construct_from_raw(&from.borrow().raw())
 }
\ No newline at end of file
@@ -6,6 +6,8 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::hash::{Hash, Hasher};
 
+use crate::annotation::Annotations;
+
 /// An identifier with a namespace.
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct Identifier {
@@ -58,6 +60,8 @@ impl Label {
     pub fn new(name: &str) -> Self {
         Label(name.to_string())
     }
+
+    pub fn as_str(&self) -> &str { &self.0 }
 }
 
 impl Display for Label {
@@ -72,20 +76,132 @@ pub trait Labelled<T> {
     fn labelled(&self, label: &Label) -> Option<T>;
 }
 
+/// Returns `true` if no label is in `labels` twice, in constants too.
+pub const fn distinct_labels(labels: &[&str]) -> bool {
+    let mut first = 0;
+    while first < labels.len() {
+        let mut second = first + 1;
+        while second < labels.len() {
+            if same_label(labels[first].as_bytes(), labels[second].as_bytes()) {
+                return false;
+            }
+            second += 1;
+        }
+        first += 1;
+    }
+    true
+}
+
+const fn same_label(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// The labels of the fields or the variants of a type, with their [Annotations].
 #[derive(Clone, Debug)]
-pub struct LabelBank(HashMap<Label, usize>);
+pub struct LabelBank {
+    labels: Vec<Label>,
+    indexes: HashMap<Label, usize>,
+    annotations: HashMap<usize, Annotations>,
+}
 
 impl LabelBank {
     pub fn from(labels: &[&str]) -> Self {
-        let mut label_bank = HashMap::new();
+        let labels: Vec<Label> = labels.iter().map(|label| Label::new(label)).collect();
+        let mut indexes = HashMap::new();
         for (index, label) in labels.iter().enumerate() {
-            label_bank.insert(Label::new(label), index);
+            indexes.insert(label.clone(), index);
         }
-        Self(label_bank)
+        Self { labels, indexes, annotations: HashMap::new() }
+    }
+
+    /// Iterates over the labels and their indexes, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Label, usize)> {
+        self.indexes.iter().map(|(label, index)| (label, *index))
     }
+
+    /// The label of the field or the variant at `index`.
+    pub fn label(&self, index: usize) -> Option<&Label> { self.labels.get(index) }
+
+    /// Annotates the field or the variant labelled `label`.
+    ///
+    /// Returns `false` if there is no such label.
+    pub fn annotate(&mut self, label: &str, annotations: Annotations) -> bool {
+        match self.labelled(&Label::new(label)) {
+            Some(index) => {
+                self.annotations.insert(index, annotations);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Annotates the field or the variant labelled `label`, if any.
+    pub fn annotated(mut self, label: &str, annotations: Annotations) -> Self {
+        self.annotate(label, annotations);
+        self
+    }
+
+    /// The annotations of the field or the variant at `index`.
+    pub fn annotations(&self, index: usize) -> Option<&Annotations> { self.annotations.get(&index) }
 }
 
 impl Labelled<usize> for LabelBank {
 
-    fn labelled(&self, label: &Label) -> Option<usize> { self.0.get(label).cloned() }
-}
\ No newline at end of file
+    fn labelled(&self, label: &Label) -> Option<usize> { self.indexes.get(label).cloned() }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::annotation::Annotations;
+    use crate::identity::{distinct_labels, Identifier, Label, LabelBank, Labelled};
+
+    #[test]
+    fn test_labels() {
+        let names: Vec<String> = (0..1000).map(|index| format!("field_{}", index)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let labels = LabelBank::from(&names);
+        for (index, name) in names.iter().enumerate() {
+            assert_eq!(labels.label(index).map(Label::as_str), Some(*name));
+            assert_eq!(labels.labelled(&Label::new(name)), Some(index));
+        }
+        assert_eq!(labels.label(1000), None);
+        assert_eq!(labels.labelled(&Label::new("field_1000")), None);
+        assert_eq!(labels.iter().count(), 1000);
+    }
+
+    #[test]
+    fn test_distinct_labels() {
+        const LABELS: &[&str] = &["x", "y", "xy"];
+        const _: () = assert!(distinct_labels(LABELS));
+        assert!(distinct_labels(&[]));
+        assert!(!distinct_labels(&["x", "y", "x"]));
+        assert!(!distinct_labels(&["", ""]));
+    }
+
+    #[test]
+    fn test_annotations() {
+        let labels = LabelBank::from(&["x", "y"]).annotated("y", Annotations::new().with_doc("Height."));
+        assert!(labels.annotations(0).is_none());
+        assert_eq!(labels.annotations(1).unwrap().doc.as_deref(), Some("Height."));
+        assert_eq!(labels.label(1).map(Label::as_str), Some("y"));
+    }
+
+    #[test]
+    fn test_identifiers() {
+        let identifier = Identifier::new("Geometry/Shapes/Point");
+        assert_eq!((identifier.space.as_str(), identifier.name.as_str()), ("Geometry", "Shapes/Point"));
+        assert_eq!(identifier.to_string(), "Geometry/Shapes/Point");
+        let global = Identifier::new("Point");
+        assert_eq!((global.space.as_str(), global.to_string().as_str()), ("", "Point"));
+    }
+}
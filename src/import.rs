@@ -0,0 +1,72 @@
+//! Importers of type definitions written in other languages.
+//!
+//! - [proto] reads a subset of proto3 (`.proto` files).
+//! - [c] reads the data structures declared in C sources and headers.
+//!
+//! Each importer produces a [crate::types::registry::Registry] and reports [Diagnostic]s
+//! located in the source text for anything it cannot import.
+
+use std::fmt::{Display, Formatter};
+
+use crate::types::registry::Registry;
+
+pub mod c;
+mod lexer;
+pub mod proto;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The construct cannot be imported.
+    Error,
+    /// The construct is imported with a loss of information.
+    Warning,
+}
+
+/// A message about a construct of an imported source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(line: usize, column: usize, message: &str) -> Self {
+        Self { severity: Severity::Error, line, column, message: message.to_string() }
+    }
+
+    pub fn warning(line: usize, column: usize, message: &str) -> Self {
+        Self { severity: Severity::Warning, line, column, message: message.to_string() }
+    }
+
+    pub fn is_error(&self) -> bool { self.severity == Severity::Error }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}:{}: {}: {}", self.line, self.column, severity, self.message)
+    }
+}
+
+/// The definitions imported from a source, with the warnings about them.
+#[derive(Clone, Debug)]
+pub struct Imported {
+    pub registry: Registry,
+    pub warnings: Vec<Diagnostic>,
+}
+
+/// The result of an import : the [Imported] definitions, or every [Diagnostic] if any of them is an error.
+pub type ImportResult = Result<Imported, Vec<Diagnostic>>;
+
+fn conclude(registry: Registry, diagnostics: Vec<Diagnostic>) -> ImportResult {
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        Err(diagnostics)
+    } else {
+        Ok(Imported { registry, warnings: diagnostics })
+    }
+}
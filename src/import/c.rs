@@ -0,0 +1,615 @@
+//! An importer for C declarations of data structures.
+//!
+//! The supported declarations are :
+//! - `struct`, imported as a [Structure] whose labels are the member names,
+//! - `union`, imported as an [Enumeration] with a variant for each member,
+//! - `enum`, imported as an [Enumeration] of unit variants, the values must be `0, 1, 2...` in order,
+//! - `typedef` of any supported type,
+//! - fixed arrays `T name[N]`, imported as [Primitive::List],
+//! - pointers `T *`, imported as [Primitive::Reference].
+//!
+//! The scalar types are imported as :
+//! - `char`, `int8_t` and `uint8_t` : [Primitive::Byte],
+//! - `bool` and `_Bool` : [Primitive::Boolean],
+//! - signed 64 bits integers (`int64_t`, `long long`...) : [Primitive::Integer],
+//! - `double` : [Primitive::Float],
+//! - other integers and `float` : [Primitive::Bytes] of their size.
+//!
+//! Members are laid out following a [DataLayout]. Imported types have no implicit padding,
+//! so the padding of the C layout is imported as `__pad0`, `__pad1`... [Primitive::Bytes] members,
+//! and the offsets of the members match the C ABI.
+//!
+//! Tagged types are larger than their C counterpart : an `enum` or a `union` is imported with a tag,
+//! which is reported as a warning, like pointers to incomplete types imported as `&byte`.
+//! Preprocessor directives are skipped and macros are not expanded.
+//! Bit-fields, function pointers, flexible arrays and anonymous members are reported as errors.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::import::c::{import_c, DataLayout};
+//! use roussillon_type_system::types::concept::DataType;
+//!
+//! let imported = import_c(r#"
+//!     #include <stdint.h>
+//!
+//!     typedef struct { int32_t x; int32_t y; } point_t;
+//!
+//!     struct packet {
+//!         uint8_t kind;
+//!         point_t origin;
+//!         uint16_t flags;
+//!         double weights[2];
+//!         struct packet *next;
+//!     };
+//! "#, "net", &DataLayout::LP64).unwrap();
+//!
+//! let packet = imported.registry.structure("net/packet").unwrap();
+//! assert_eq!(packet.size(), 40);
+//! assert_eq!(packet.product_type.typename(), "<byte&bytes<3>&net/point_t&word&word&[float;2]&&byte>");
+//! assert_eq!(imported.warnings[0].to_string(), "11:24: warning: pointer to incomplete `struct packet` is imported as `&byte`");
+//! ```
+
+use std::collections::HashMap;
+
+use crate::identity::LabelBank;
+use crate::import::lexer::{tokenize, Cursor, Token};
+use crate::import::{conclude, Diagnostic, ImportResult};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::{DataType, Type};
+use crate::types::primitive::Primitive;
+use crate::types::registry::Registry;
+use crate::types::typedef::{Enumeration, Structure};
+
+/// The sizes and alignments of the C types of a target.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DataLayout {
+    /// The size of pointers, `size_t` and `intptr_t`.
+    pub pointer_size: usize,
+    /// The size of `long`.
+    pub long_size: usize,
+    /// The size of `enum` types.
+    pub enum_size: usize,
+    /// The largest alignment of a scalar, `1` for packed structures.
+    pub max_alignment: usize,
+}
+
+impl DataLayout {
+    /// 64 bits Linux and macOS.
+    pub const LP64: Self = Self { pointer_size: 8, long_size: 8, enum_size: 4, max_alignment: 8 };
+    /// 64 bits Windows.
+    pub const LLP64: Self = Self { pointer_size: 8, long_size: 4, enum_size: 4, max_alignment: 8 };
+    /// 32 bits System V (i386).
+    pub const ILP32: Self = Self { pointer_size: 4, long_size: 4, enum_size: 4, max_alignment: 4 };
+
+    /// The same layout without padding, like `#pragma pack(1)`.
+    pub fn packed(self) -> Self { Self { max_alignment: 1, ..self } }
+
+    /// The alignment of a scalar of `size` bytes.
+    pub fn alignment(&self, size: usize) -> usize { size.clamp(1, self.max_alignment.max(1)) }
+}
+
+impl Default for DataLayout {
+    fn default() -> Self { Self::LP64 }
+}
+
+/// Imports the `struct`, `union`, `enum` and `typedef` declarations of a C source.
+///
+/// The imported definitions are in the `space` namespace.
+pub fn import_c(source: &str, space: &str, layout: &DataLayout) -> ImportResult {
+    let lexemes = tokenize(source, true).map_err(|diagnostic| vec![diagnostic])?;
+    let mut importer = Importer {
+        cursor: Cursor::new(lexemes),
+        space,
+        layout,
+        tags: HashMap::new(),
+        typedefs: HashMap::new(),
+        registry: Registry::new(),
+        diagnostics: Vec::new(),
+    };
+    while !importer.cursor.is_done() {
+        if let Err(diagnostic) = importer.declaration() {
+            importer.diagnostics.push(diagnostic);
+            importer.cursor.skip_statement();
+        }
+    }
+    conclude(importer.registry, importer.diagnostics)
+}
+
+/// A C type : its imported type and its size and alignment in the C layout.
+#[derive(Clone)]
+struct CType {
+    imported: Type,
+    size: usize,
+    align: usize,
+}
+
+/// The base type of a declaration.
+#[derive(Clone)]
+enum Base {
+    Complete(CType),
+    /// `void` or a `struct`, `union` or `enum` which is not defined yet.
+    Incomplete(String),
+}
+
+enum Declared {
+    Object { name: String, c_type: CType, line: usize, column: usize },
+    Function(String),
+}
+
+fn round_up(offset: usize, align: usize) -> usize { offset.div_ceil(align) * align }
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() { name.to_string() } else { format!("{}.{}", scope, name) }
+}
+
+const QUALIFIERS: [&str; 4] = ["const", "volatile", "restrict", "register"];
+const SCALAR_WORDS: [&str; 11] = ["void", "char", "short", "int", "long", "float", "double", "signed", "unsigned", "_Bool", "bool"];
+
+struct Importer<'a> {
+    cursor: Cursor,
+    space: &'a str,
+    layout: &'a DataLayout,
+    tags: HashMap<String, CType>,
+    typedefs: HashMap<String, Base>,
+    registry: Registry,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Importer<'_> {
+    fn identifier(&self, name: &str) -> String {
+        if self.space.is_empty() { name.to_string() } else { format!("{}/{}", self.space, name) }
+    }
+
+    fn warn(&mut self, line: usize, column: usize, message: &str) {
+        self.diagnostics.push(Diagnostic::warning(line, column, message));
+    }
+
+    fn scalar(&self, imported: Primitive, size: usize) -> CType {
+        CType { imported: imported.to_rc(), size, align: self.layout.alignment(size) }
+    }
+
+    fn integer(&self, size: usize, signed: bool) -> CType {
+        match size {
+            1 => self.scalar(Primitive::Byte, 1),
+            8 if signed => self.scalar(Primitive::Integer, 8),
+            _ => self.scalar(Primitive::Bytes(size), size),
+        }
+    }
+
+    /// The types of `<stdint.h>`, `<stddef.h>` and `<stdbool.h>`.
+    fn standard(&self, name: &str) -> Option<CType> {
+        let pointer_size = self.layout.pointer_size;
+        Some(match name {
+            "int8_t" | "uint8_t" => self.integer(1, true),
+            "int16_t" | "uint16_t" => self.integer(2, true),
+            "int32_t" | "uint32_t" => self.integer(4, true),
+            "int64_t" => self.integer(8, true),
+            "uint64_t" => self.integer(8, false),
+            "intptr_t" | "ssize_t" | "ptrdiff_t" => self.integer(pointer_size, true),
+            "uintptr_t" | "size_t" => self.integer(pointer_size, false),
+            _ => return None,
+        })
+    }
+
+    fn skip_qualifiers(&mut self) {
+        while QUALIFIERS.iter().any(|qualifier| self.cursor.is_keyword(qualifier)) {
+            self.cursor.next();
+        }
+    }
+
+    fn declaration(&mut self) -> Result<(), Diagnostic> {
+        if self.cursor.accept(';') {
+            return Ok(());
+        }
+        let typedef = self.cursor.is_keyword("typedef");
+        if typedef {
+            self.cursor.next();
+        }
+        while ["extern", "static", "inline"].iter().any(|keyword| self.cursor.is_keyword(keyword)) {
+            self.cursor.next();
+        }
+        let base = self.specifier("")?;
+        if self.cursor.accept(';') {
+            return Ok(());
+        }
+        loop {
+            let (line, column) = self.cursor.location();
+            if let (true, Base::Incomplete(_), Some(Token::Identifier(name))) = (typedef, &base, self.cursor.peek()) {
+                if matches!(self.cursor.peek_at(1), Some(Token::Symbol(';' | ','))) {
+                    self.typedefs.insert(name.clone(), base.clone());
+                    self.cursor.next();
+                    if !self.cursor.accept(',') {
+                        break;
+                    }
+                    continue;
+                }
+            }
+            match (typedef, self.declarator(&base)?) {
+                (true, Declared::Object { name, c_type, .. }) => {
+                    self.typedefs.insert(name, Base::Complete(c_type));
+                }
+                (true, Declared::Function(name)) => {
+                    return Err(Diagnostic::error(line, column, &format!("function type `{}` is not supported", name)));
+                }
+                (false, Declared::Object { name, .. }) | (false, Declared::Function(name)) => {
+                    self.warn(line, column, &format!("`{}` is not a type and is ignored", name));
+                }
+            }
+            if !self.cursor.accept(',') {
+                break;
+            }
+        }
+        self.cursor.expect(';')
+    }
+
+    /// Reads a type specifier, `scope` is the name of the enclosing structure.
+    fn specifier(&mut self, scope: &str) -> Result<Base, Diagnostic> {
+        self.skip_qualifiers();
+        let (line, column) = self.cursor.location();
+        let base = if ["struct", "union", "enum"].iter().any(|keyword| self.cursor.is_keyword(keyword)) {
+            self.aggregate(scope)?
+        } else if SCALAR_WORDS.iter().any(|word| self.cursor.is_keyword(word)) {
+            let mut words = Vec::new();
+            while let Some(Token::Identifier(word)) = self.cursor.peek() {
+                if SCALAR_WORDS.contains(&word.as_str()) {
+                    words.push(word.clone());
+                    self.cursor.next();
+                } else if QUALIFIERS.contains(&word.as_str()) {
+                    self.cursor.next();
+                } else {
+                    break;
+                }
+            }
+            self.scalar_words(&words).ok_or_else(|| Diagnostic::error(line, column, &format!("`{}` is not supported", words.join(" "))))?
+        } else {
+            let name = self.cursor.identifier()?;
+            match self.typedefs.get(&name) {
+                Some(base) => self.complete(base.clone()),
+                None => Base::Complete(self.standard(&name).ok_or_else(|| Diagnostic::error(line, column, &format!("unknown type `{}`", name)))?),
+            }
+        };
+        self.skip_qualifiers();
+        Ok(base)
+    }
+
+    fn scalar_words(&self, words: &[String]) -> Option<Base> {
+        let count = |word: &str| words.iter().filter(|w| w.as_str() == word).count();
+        let signed = count("unsigned") == 0;
+        Some(Base::Complete(match (count("void"), count("char"), count("short"), count("long"), count("float"), count("double"), count("_Bool") + count("bool")) {
+            (1, 0, 0, 0, 0, 0, 0) if words.len() == 1 => return Some(Base::Incomplete("void".to_string())),
+            (0, 1, 0, 0, 0, 0, 0) => self.integer(1, signed),
+            (0, 0, 1, 0, 0, 0, 0) => self.integer(2, signed),
+            (0, 0, 0, 0, 0, 0, 0) => self.integer(4, signed),
+            (0, 0, 0, 1, 0, 0, 0) => self.integer(self.layout.long_size, signed),
+            (0, 0, 0, 2, 0, 0, 0) => self.integer(8, signed),
+            (0, 0, 0, 0, 1, 0, 0) if words.len() == 1 => self.scalar(Primitive::Bytes(4), 4),
+            (0, 0, 0, 0, 0, 1, 0) if words.len() == 1 => self.scalar(Primitive::Float, 8),
+            (0, 0, 0, 0, 0, 0, 1) if words.len() == 1 => self.scalar(Primitive::Boolean, 1),
+            _ => return None,
+        }))
+    }
+
+    /// Replaces an incomplete tag by its definition, if it is defined now.
+    fn complete(&self, base: Base) -> Base {
+        match base {
+            Base::Incomplete(tag) => match self.tags.get(&tag) {
+                Some(c_type) => Base::Complete(c_type.clone()),
+                None => Base::Incomplete(tag),
+            },
+            complete => complete,
+        }
+    }
+
+    /// Looks for the name declared after the body starting at the current `{`.
+    fn name_after_body(&self) -> Option<String> {
+        let mut depth = 0usize;
+        let mut offset = 0;
+        loop {
+            match self.cursor.peek_at(offset)? {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            offset += 1;
+        }
+        offset += 1;
+        loop {
+            match self.cursor.peek_at(offset)? {
+                Token::Symbol('*') => offset += 1,
+                Token::Identifier(name) if QUALIFIERS.contains(&name.as_str()) => offset += 1,
+                Token::Identifier(name) => return Some(name.clone()),
+                _ => return None,
+            }
+        }
+    }
+
+    fn aggregate(&mut self, scope: &str) -> Result<Base, Diagnostic> {
+        let (line, column) = self.cursor.location();
+        let kind = self.cursor.identifier()?;
+        let tag = match self.cursor.peek() {
+            Some(Token::Identifier(_)) => Some(self.cursor.identifier()?),
+            _ => None,
+        };
+        if !self.cursor.is_symbol('{') {
+            let tag = tag.ok_or_else(|| self.cursor.error(&format!("expected a tag or the body of the `{}`", kind)))?;
+            return Ok(self.complete(Base::Incomplete(format!("{} {}", kind, tag))));
+        }
+        let name = match &tag {
+            Some(tag) => tag.clone(),
+            None => qualify(scope, &self.name_after_body().ok_or_else(|| Diagnostic::error(line, column, &format!(
+                "anonymous `{}` needs a tag or a declarator", kind
+            )))?),
+        };
+        let c_type = match kind.as_str() {
+            "struct" => self.structure(&name)?,
+            "union" => self.union(&name, line, column)?,
+            _ => self.enumeration(&name, line, column)?,
+        };
+        if let Some(tag) = tag {
+            self.tags.insert(format!("{} {}", kind, tag), c_type.clone());
+        }
+        Ok(Base::Complete(c_type))
+    }
+
+    /// Reads the declarators of the members of a `struct` or an `union` body.
+    fn members(&mut self, name: &str) -> Result<Vec<(String, CType, usize, usize)>, Diagnostic> {
+        let (line, column) = self.cursor.location();
+        self.cursor.expect('{')?;
+        let mut members = Vec::new();
+        while !self.cursor.accept('}') {
+            if self.cursor.is_done() {
+                return Err(Diagnostic::error(line, column, &format!("`{}` is not closed", name)));
+            }
+            if let Err(diagnostic) = self.member(name, &mut members) {
+                self.diagnostics.push(diagnostic);
+                self.cursor.skip_statement();
+            }
+        }
+        Ok(members)
+    }
+
+    fn member(&mut self, scope: &str, members: &mut Vec<(String, CType, usize, usize)>) -> Result<(), Diagnostic> {
+        let base = self.specifier(scope)?;
+        if self.cursor.is_symbol(';') {
+            return Err(self.cursor.error("anonymous members are not supported"));
+        }
+        loop {
+            match self.declarator(&base)? {
+                Declared::Object { name, c_type, line, column } => members.push((name, c_type, line, column)),
+                Declared::Function(name) => return Err(self.cursor.error(&format!("member `{}` cannot be a function", name))),
+            }
+            if !self.cursor.accept(',') {
+                break;
+            }
+        }
+        self.cursor.expect(';')
+    }
+
+    fn declarator(&mut self, base: &Base) -> Result<Declared, Diagnostic> {
+        let mut pointers = 0;
+        while self.cursor.accept('*') {
+            pointers += 1;
+            self.skip_qualifiers();
+        }
+        if self.cursor.is_symbol('(') {
+            return Err(self.cursor.error("function pointers are not supported"));
+        }
+        let (line, column) = self.cursor.location();
+        let name = self.cursor.identifier()?;
+        if self.cursor.accept('(') {
+            let mut depth = 1;
+            while depth > 0 {
+                match self.cursor.next() {
+                    Some(Token::Symbol('(')) => depth += 1,
+                    Some(Token::Symbol(')')) => depth -= 1,
+                    None => return Err(Diagnostic::error(line, column, "unterminated parameter list")),
+                    _ => {}
+                }
+            }
+            return Ok(Declared::Function(name));
+        }
+        let mut lengths = Vec::new();
+        while self.cursor.accept('[') {
+            if self.cursor.is_symbol(']') {
+                return Err(self.cursor.error("flexible array members are not supported"));
+            }
+            let length = match self.cursor.peek() {
+                Some(Token::Number(_)) => self.cursor.number()?,
+                _ => return Err(self.cursor.error("the length of an array must be an integer literal, macros are not expanded")),
+            };
+            lengths.push(usize::try_from(length).map_err(|_| self.cursor.error("the length of an array cannot be negative"))?);
+            self.cursor.expect(']')?;
+        }
+        if self.cursor.is_symbol(':') {
+            return Err(self.cursor.error("bit-fields are not supported"));
+        }
+        let mut c_type = if pointers > 0 {
+            self.pointer(base, pointers, line, column)
+        } else {
+            match base {
+                Base::Complete(c_type) => c_type.clone(),
+                Base::Incomplete(tag) => return Err(Diagnostic::error(line, column, &format!("`{}` has an incomplete type `{}`", name, tag))),
+            }
+        };
+        for length in lengths.into_iter().rev() {
+            c_type = CType {
+                imported: Primitive::list(c_type.imported, length).to_rc(),
+                size: c_type.size * length,
+                align: c_type.align,
+            };
+        }
+        Ok(Declared::Object { name, c_type, line, column })
+    }
+
+    fn pointer(&mut self, base: &Base, pointers: usize, line: usize, column: usize) -> CType {
+        let mut target = match self.complete(base.clone()) {
+            Base::Complete(c_type) => c_type.imported,
+            Base::Incomplete(tag) => {
+                self.warn(line, column, &format!("pointer to incomplete `{}` is imported as `&byte`", tag));
+                Primitive::Byte.to_rc()
+            }
+        };
+        for _ in 1..pointers {
+            target = Primitive::Reference(target).to_rc();
+        }
+        let size = self.layout.pointer_size;
+        let reference = Primitive::Reference(target);
+        if reference.size() == size {
+            self.scalar(reference, size)
+        } else {
+            self.warn(line, column, &format!("pointers are {} bytes in this layout and are imported as raw bytes", size));
+            self.scalar(Primitive::Bytes(size), size)
+        }
+    }
+
+    fn structure(&mut self, name: &str) -> Result<CType, Diagnostic> {
+        let members = self.members(name)?;
+        let mut labels = Vec::new();
+        let mut types = Vec::new();
+        let (mut offset, mut imported_offset, mut align) = (0, 0, 1);
+        let pad = |labels: &mut Vec<String>, types: &mut Vec<Type>, size: usize| {
+            labels.push(format!("__pad{}", labels.iter().filter(|label| label.starts_with("__pad")).count()));
+            types.push(Primitive::Bytes(size).to_rc());
+        };
+        for (label, c_type, line, column) in members {
+            offset = round_up(offset, c_type.align);
+            if offset > imported_offset {
+                pad(&mut labels, &mut types, offset - imported_offset);
+                imported_offset = offset;
+            }
+            if offset != imported_offset {
+                self.warn(line, column, &format!("`{}` is at offset {} in C but at offset {} imported", label, offset, imported_offset));
+            }
+            offset += c_type.size;
+            imported_offset += c_type.imported.size();
+            align = align.max(c_type.align);
+            labels.push(label);
+            types.push(c_type.imported);
+        }
+        let size = round_up(offset, align);
+        if size > imported_offset {
+            pad(&mut labels, &mut types, size - imported_offset);
+        }
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let structure = Structure::new(&self.identifier(name), LabelBank::from(&labels), ProductType::new(&types)).to_rc();
+        self.registry.add_structure(structure.clone());
+        Ok(CType { imported: structure, size, align })
+    }
+
+    fn union(&mut self, name: &str, line: usize, column: usize) -> Result<CType, Diagnostic> {
+        let members = self.members(name)?;
+        let size = members.iter().map(|(_, c_type, _, _)| c_type.size).max().unwrap_or(0);
+        let align = members.iter().map(|(_, c_type, _, _)| c_type.align).max().unwrap_or(1);
+        let labels: Vec<&str> = members.iter().map(|(label, _, _, _)| label.as_str()).collect();
+        let types: Vec<Type> = members.iter().map(|(_, c_type, _, _)| c_type.imported.clone()).collect();
+        let enumeration = Enumeration::new(&self.identifier(name), LabelBank::from(&labels), SumType::new(&types)).to_rc();
+        let c_type = CType { imported: enumeration.clone(), size: round_up(size, align), align };
+        self.tagged(name, &c_type, line, column);
+        self.registry.add_enumeration(enumeration);
+        Ok(c_type)
+    }
+
+    fn enumeration(&mut self, name: &str, line: usize, column: usize) -> Result<CType, Diagnostic> {
+        self.cursor.expect('{')?;
+        let mut labels = Vec::new();
+        while !self.cursor.accept('}') {
+            let (line, column) = self.cursor.location();
+            let label = self.cursor.identifier()?;
+            if self.cursor.accept('=') {
+                let value = match self.cursor.peek_at(1) {
+                    Some(Token::Symbol(',')) | Some(Token::Symbol('}')) => self.cursor.number()?,
+                    _ => return Err(self.cursor.error("enumerator values must be integer literals")),
+                };
+                if value != labels.len() as i64 {
+                    self.diagnostics.push(Diagnostic::error(line, column, &format!(
+                        "`{}` should be {} : enumerator values must be 0, 1, 2... in order", label, labels.len()
+                    )));
+                }
+            }
+            labels.push(label);
+            if !self.cursor.accept(',') {
+                self.cursor.expect('}')?;
+                break;
+            }
+        }
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let variants: Vec<Type> = labels.iter().map(|_| ProductType::unit_type().to_rc() as Type).collect();
+        let enumeration = Enumeration::new(&self.identifier(name), LabelBank::from(&labels), SumType::new(&variants)).to_rc();
+        let size = self.layout.enum_size;
+        let c_type = CType { imported: enumeration.clone(), size, align: self.layout.alignment(size) };
+        self.tagged(name, &c_type, line, column);
+        self.registry.add_enumeration(enumeration);
+        Ok(c_type)
+    }
+
+    /// Warns when a tagged type is larger than its C counterpart.
+    fn tagged(&mut self, name: &str, c_type: &CType, line: usize, column: usize) {
+        let imported = c_type.imported.size();
+        if imported != c_type.size {
+            self.warn(line, column, &format!("`{}` is {} bytes in C but {} bytes imported, with its tag", name, c_type.size, imported));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::import::c::{import_c, DataLayout};
+    use crate::import::Diagnostic;
+    use crate::types::concept::DataType;
+
+    fn errors(source: &str) -> Vec<String> {
+        import_c(source, "t", &DataLayout::LP64).unwrap_err().iter().filter(|diagnostic| diagnostic.is_error()).map(Diagnostic::to_string).collect()
+    }
+
+    fn warnings(source: &str, layout: &DataLayout) -> Vec<String> {
+        import_c(source, "t", layout).unwrap().warnings.iter().map(Diagnostic::to_string).collect()
+    }
+
+    #[test]
+    fn test_unsupported_members() {
+        assert_eq!(errors("struct s { int a : 3; };"), ["1:18: error: bit-fields are not supported"]);
+        assert_eq!(errors("struct s { int (*f)(int); };"), ["1:16: error: function pointers are not supported"]);
+        assert_eq!(errors("struct s { int n; char data[]; };"), ["1:29: error: flexible array members are not supported"]);
+        assert_eq!(errors("struct s { int a[N]; };"), ["1:18: error: the length of an array must be an integer literal, macros are not expanded"]);
+        assert_eq!(errors("struct s { struct { int x; }; };"), ["1:12: error: anonymous `struct` needs a tag or a declarator"]);
+        assert_eq!(errors("typedef int f(int);"), ["1:13: error: function type `f` is not supported"]);
+        assert_eq!(errors("struct s { long double d; };"), ["1:12: error: `long double` is not supported"]);
+    }
+
+    #[test]
+    fn test_unknown_and_incomplete_types() {
+        assert_eq!(errors("struct s { unknown_t u; };"), ["1:12: error: unknown type `unknown_t`"]);
+        assert_eq!(errors("struct s { struct other o; };"), ["1:25: error: `o` has an incomplete type `struct other`"]);
+        assert_eq!(errors("struct s { int a;"), ["1:10: error: `s` is not closed"]);
+        assert_eq!(warnings("struct s { struct other *o; };", &DataLayout::LP64), ["1:26: warning: pointer to incomplete `struct other` is imported as `&byte`"]);
+    }
+
+    #[test]
+    fn test_enumerators() {
+        assert_eq!(errors("enum e { A = 1, B };"), ["1:10: error: `A` should be 0 : enumerator values must be 0, 1, 2... in order"]);
+        assert_eq!(errors("enum e { A = X };"), ["1:14: error: expected an integer"]);
+        assert_eq!(warnings("enum e { A, B = 1 };", &DataLayout::LP64), ["1:1: warning: `e` is 4 bytes in C but 8 bytes imported, with its tag"]);
+    }
+
+    #[test]
+    fn test_layouts() {
+        let source = "struct s { char c; double d; int *p; };";
+        let lp64 = import_c(source, "t", &DataLayout::LP64).unwrap();
+        let structure = lp64.registry.structure("t/s").unwrap();
+        assert_eq!(structure.product_type.typename(), "<byte&bytes<7>&float&&quad>");
+        assert_eq!(structure.size(), 24);
+        assert!(lp64.warnings.is_empty());
+
+        let packed = import_c(source, "t", &DataLayout::LP64.packed()).unwrap();
+        assert_eq!(packed.registry.structure("t/s").unwrap().product_type.typename(), "<byte&float&&quad>");
+
+        assert_eq!(warnings(source, &DataLayout::ILP32), ["1:35: warning: pointers are 4 bytes in this layout and are imported as raw bytes"]);
+        assert_eq!(warnings("union u { int a; double b; };", &DataLayout::LP64), ["1:1: warning: `u` is 8 bytes in C but 16 bytes imported, with its tag"]);
+    }
+}
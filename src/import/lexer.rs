@@ -0,0 +1,222 @@
+//! A small lexer shared by the importers.
+//!
+//! It knows identifiers, numbers, strings and single character symbols,
+//! skips `//` and `/* */` comments, and optionally the lines starting with `#`.
+
+use crate::import::Diagnostic;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Identifier(String),
+    Number(String),
+    String(String),
+    Symbol(char),
+}
+
+#[derive(Clone, Debug)]
+pub struct Lexeme {
+    pub token: Token,
+    pub line: usize,
+    pub column: usize,
+}
+
+pub fn tokenize(source: &str, skip_directives: bool) -> Result<Vec<Lexeme>, Diagnostic> {
+    let characters: Vec<char> = source.chars().collect();
+    let mut lexemes = Vec::new();
+    let (mut position, mut line, mut column) = (0, 1, 1);
+    let mut line_start = true;
+    while position < characters.len() {
+        let c = characters[position];
+        let (start_line, start_column) = (line, column);
+        let advance = |count: usize, position: &mut usize, line: &mut usize, column: &mut usize| {
+            for _ in 0..count {
+                if characters.get(*position) == Some(&'\n') {
+                    *line += 1;
+                    *column = 1;
+                } else {
+                    *column += 1;
+                }
+                *position += 1;
+            }
+        };
+        if c.is_whitespace() {
+            if c == '\n' {
+                line_start = true;
+            }
+            advance(1, &mut position, &mut line, &mut column);
+            continue;
+        }
+        if skip_directives && line_start && c == '#' {
+            while position < characters.len() && characters[position] != '\n' {
+                let continued = characters[position] == '\\' && characters.get(position + 1) == Some(&'\n');
+                advance(if continued { 2 } else { 1 }, &mut position, &mut line, &mut column);
+            }
+            continue;
+        }
+        line_start = false;
+        if c == '/' && characters.get(position + 1) == Some(&'/') {
+            while position < characters.len() && characters[position] != '\n' {
+                advance(1, &mut position, &mut line, &mut column);
+            }
+            continue;
+        }
+        if c == '/' && characters.get(position + 1) == Some(&'*') {
+            advance(2, &mut position, &mut line, &mut column);
+            while position < characters.len() && !(characters[position] == '*' && characters.get(position + 1) == Some(&'/')) {
+                advance(1, &mut position, &mut line, &mut column);
+            }
+            if position >= characters.len() {
+                return Err(Diagnostic::error(start_line, start_column, "unterminated comment"));
+            }
+            advance(2, &mut position, &mut line, &mut column);
+            continue;
+        }
+        let token = if c.is_ascii_alphabetic() || c == '_' {
+            let start = position;
+            while position < characters.len() && (characters[position].is_ascii_alphanumeric() || characters[position] == '_') {
+                advance(1, &mut position, &mut line, &mut column);
+            }
+            Token::Identifier(characters[start..position].iter().collect())
+        } else if c.is_ascii_digit() {
+            let start = position;
+            while position < characters.len() && (characters[position].is_ascii_alphanumeric() || characters[position] == '.') {
+                advance(1, &mut position, &mut line, &mut column);
+            }
+            Token::Number(characters[start..position].iter().collect())
+        } else if c == '"' || c == '\'' {
+            advance(1, &mut position, &mut line, &mut column);
+            let mut value = String::new();
+            while position < characters.len() && characters[position] != c {
+                if characters[position] == '\n' {
+                    break;
+                }
+                if characters[position] == '\\' {
+                    advance(1, &mut position, &mut line, &mut column);
+                }
+                if let Some(escaped) = characters.get(position) {
+                    value.push(*escaped);
+                }
+                advance(1, &mut position, &mut line, &mut column);
+            }
+            if characters.get(position) != Some(&c) {
+                return Err(Diagnostic::error(start_line, start_column, "unterminated string"));
+            }
+            advance(1, &mut position, &mut line, &mut column);
+            Token::String(value)
+        } else {
+            advance(1, &mut position, &mut line, &mut column);
+            Token::Symbol(c)
+        };
+        lexemes.push(Lexeme { token, line: start_line, column: start_column });
+    }
+    Ok(lexemes)
+}
+
+/// A cursor over the lexemes of a source.
+pub struct Cursor {
+    lexemes: Vec<Lexeme>,
+    position: usize,
+}
+
+impl Cursor {
+    pub fn new(lexemes: Vec<Lexeme>) -> Self { Self { lexemes, position: 0 } }
+
+    pub fn is_done(&self) -> bool { self.position >= self.lexemes.len() }
+
+    pub fn peek(&self) -> Option<&Token> { self.lexemes.get(self.position).map(|lexeme| &lexeme.token) }
+
+    pub fn peek_at(&self, offset: usize) -> Option<&Token> { self.lexemes.get(self.position + offset).map(|lexeme| &lexeme.token) }
+
+    /// The line and column of the current lexeme, or of the end of the source.
+    pub fn location(&self) -> (usize, usize) {
+        self.lexemes.get(self.position).or(self.lexemes.last())
+            .map(|lexeme| (lexeme.line, lexeme.column))
+            .unwrap_or((1, 1))
+    }
+
+    pub fn error(&self, message: &str) -> Diagnostic {
+        let (line, column) = self.location();
+        Diagnostic::error(line, column, message)
+    }
+
+    pub fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    pub fn is_symbol(&self, symbol: char) -> bool { self.peek() == Some(&Token::Symbol(symbol)) }
+
+    pub fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(identifier)) if identifier == keyword)
+    }
+
+    /// Consumes the `symbol` if it is the current token.
+    pub fn accept(&mut self, symbol: char) -> bool {
+        let accepted = self.is_symbol(symbol);
+        if accepted {
+            self.position += 1;
+        }
+        accepted
+    }
+
+    pub fn expect(&mut self, symbol: char) -> Result<(), Diagnostic> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", symbol)))
+        }
+    }
+
+    pub fn identifier(&mut self) -> Result<String, Diagnostic> {
+        match self.peek() {
+            Some(Token::Identifier(identifier)) => {
+                let identifier = identifier.clone();
+                self.position += 1;
+                Ok(identifier)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    pub fn number(&mut self) -> Result<i64, Diagnostic> {
+        let negative = self.accept('-');
+        let value = match self.peek() {
+            Some(Token::Number(number)) => parse_integer(number),
+            _ => None,
+        }.ok_or_else(|| self.error("expected an integer"))?;
+        self.position += 1;
+        Ok(if negative { -value } else { value })
+    }
+
+    /// Skips the tokens up to the end of the current statement, with its nested blocks.
+    pub fn skip_statement(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Symbol('{') => depth += 1,
+                Token::Symbol('}') => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        self.accept(';');
+                        return;
+                    }
+                }
+                Token::Symbol(';') if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parses a decimal, hexadecimal (`0x`) or octal (`0`) integer, with an optional C suffix like `u` or `UL`.
+pub fn parse_integer(number: &str) -> Option<i64> {
+    let digits = number.trim_end_matches(['u', 'U', 'l', 'L']);
+    if let Some(hexadecimal) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        i64::from_str_radix(hexadecimal, 16).ok()
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()
+    } else {
+        digits.parse().ok()
+    }
+}
@@ -0,0 +1,638 @@
+//! An importer for a subset of proto3.
+//!
+//! The supported constructs are :
+//! - `syntax = "proto3";` and `package a.b;`, the package becomes the [crate::identity::Identifier] space,
+//! - `message`, imported as a [Structure] whose labels are the field names,
+//! - nested `message` and `enum`, named after their parent like `Outer.Inner`,
+//! - `enum`, imported as an [Enumeration] of unit variants numbered `0, 1, 2...` in order,
+//! - `oneof`, imported as an [Enumeration] named `Message.oneof` with a variant for each field,
+//! - `repeated` fields with a fixed bound given as the option `[(bound) = 4]`, imported as [Primitive::List].
+//!
+//! The scalar types are imported as :
+//! - `double` and `float` : [Primitive::Float],
+//! - `int32`, `int64`, `sint32`, `sint64`, `sfixed32` and `sfixed64` : [Primitive::Integer],
+//! - `uint32` and `fixed32` : [Primitive::Bytes] of 4 bytes,
+//! - `uint64` and `fixed64` : [Primitive::Bytes] of 8 bytes,
+//! - `bool` : [Primitive::Boolean].
+//!
+//! Variable-length types (`string`, `bytes`, unbounded `repeated`), `map`, `import`, `service`,
+//! `extend`, proto2 constructs and recursive messages are reported as errors.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::import::proto::import_proto;
+//! use roussillon_type_system::types::concept::DataType;
+//!
+//! let imported = import_proto(r#"
+//!     syntax = "proto3";
+//!     package shop;
+//!
+//!     message Item {
+//!         int64 id = 1;
+//!         repeated double prices = 2 [(bound) = 3];
+//!         Condition condition = 3;
+//!         enum Condition { NEW = 0; USED = 1; }
+//!         oneof payment {
+//!             int64 card = 4;
+//!             bool cash = 5;
+//!         }
+//!     }
+//! "#).unwrap();
+//!
+//! let item = imported.registry.structure("shop/Item").unwrap();
+//! assert_eq!(item.product_type.typename(), "<integer&[float;3]&shop/Item.Condition&shop/Item.payment>");
+//!
+//! let errors = import_proto("syntax = \"proto3\"; message Named { string name = 1; }").unwrap_err();
+//! assert_eq!(errors[0].to_string(), "1:36: error: `string` has no fixed size and cannot be imported");
+//! ```
+
+use std::collections::HashMap;
+
+use crate::identity::LabelBank;
+use crate::import::lexer::{tokenize, Cursor, Token};
+use crate::import::{conclude, Diagnostic, ImportResult};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::primitive::Primitive;
+use crate::types::registry::Registry;
+use crate::types::typedef::{Enumeration, Structure};
+
+struct Field {
+    name: String,
+    type_name: String,
+    number: i64,
+    bound: Option<usize>,
+    line: usize,
+    column: usize,
+}
+
+enum Member {
+    Field(Field),
+    Oneof {
+        name: String,
+        fields: Vec<Field>,
+        line: usize,
+        column: usize,
+    },
+}
+
+struct Message {
+    name: String,
+    members: Vec<Member>,
+    line: usize,
+    column: usize,
+}
+
+struct EnumValue {
+    name: String,
+    number: i64,
+    line: usize,
+    column: usize,
+}
+
+struct Enum {
+    name: String,
+    values: Vec<EnumValue>,
+}
+
+/// Imports the messages and enums of a proto3 source.
+pub fn import_proto(source: &str) -> ImportResult {
+    let lexemes = tokenize(source, false).map_err(|diagnostic| vec![diagnostic])?;
+    let mut parser = Parser {
+        cursor: Cursor::new(lexemes),
+        package: String::new(),
+        messages: Vec::new(),
+        enums: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    parser.file();
+
+    let mut builder = Builder {
+        package: &parser.package,
+        messages: parser.messages.iter().map(|message| (message.name.as_str(), message)).collect(),
+        enums: parser.enums.iter().map(|enumeration| (enumeration.name.as_str(), enumeration)).collect(),
+        built: HashMap::new(),
+        pending: Vec::new(),
+        registry: Registry::new(),
+        diagnostics: parser.diagnostics.clone(),
+    };
+    for enumeration in &parser.enums {
+        builder.build(&enumeration.name);
+    }
+    for message in &parser.messages {
+        builder.build(&message.name);
+    }
+    conclude(builder.registry, builder.diagnostics)
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() { name.to_string() } else { format!("{}.{}", scope, name) }
+}
+
+struct Parser {
+    cursor: Cursor,
+    package: String,
+    messages: Vec<Message>,
+    enums: Vec<Enum>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Parser {
+    fn warn(&mut self, message: &str) {
+        let (line, column) = self.cursor.location();
+        self.diagnostics.push(Diagnostic::warning(line, column, message));
+    }
+
+    /// Reports an unsupported statement and skips it.
+    fn unsupported(&mut self, message: &str) -> Result<(), Diagnostic> {
+        self.diagnostics.push(self.cursor.error(message));
+        self.cursor.skip_statement();
+        Ok(())
+    }
+
+    fn file(&mut self) {
+        while !self.cursor.is_done() {
+            if let Err(diagnostic) = self.statement() {
+                self.diagnostics.push(diagnostic);
+                self.cursor.skip_statement();
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), Diagnostic> {
+        if self.cursor.accept(';') {
+            return Ok(());
+        }
+        match self.cursor.peek() {
+            Some(Token::Identifier(keyword)) => match keyword.as_str() {
+                "syntax" => {
+                    self.cursor.next();
+                    self.cursor.expect('=')?;
+                    let error = self.cursor.error("only `syntax = \"proto3\";` is supported");
+                    if self.cursor.next() != Some(Token::String("proto3".to_string())) {
+                        return Err(error);
+                    }
+                    self.cursor.expect(';')
+                }
+                "package" => {
+                    self.cursor.next();
+                    self.package = self.full_name()?;
+                    self.cursor.expect(';')
+                }
+                "message" => self.message(""),
+                "enum" => self.enumeration(""),
+                "option" => {
+                    self.warn("options are ignored");
+                    self.cursor.skip_statement();
+                    Ok(())
+                }
+                "import" => self.unsupported("imports are not supported, all the types must be in the same source"),
+                "service" => self.unsupported("services are not supported"),
+                "extend" => self.unsupported("extensions are not supported"),
+                keyword => Err(self.cursor.error(&format!("unexpected `{}`", keyword))),
+            },
+            _ => Err(self.cursor.error("expected a declaration")),
+        }
+    }
+
+    /// Reads a name like `a.b.C` or `.a.b.C`.
+    fn full_name(&mut self) -> Result<String, Diagnostic> {
+        let mut name = if self.cursor.accept('.') { ".".to_string() } else { String::new() };
+        name.push_str(&self.cursor.identifier()?);
+        while self.cursor.accept('.') {
+            name.push('.');
+            name.push_str(&self.cursor.identifier()?);
+        }
+        Ok(name)
+    }
+
+    fn message(&mut self, scope: &str) -> Result<(), Diagnostic> {
+        let (line, column) = self.cursor.location();
+        self.cursor.next();
+        let name = qualify(scope, &self.cursor.identifier()?);
+        self.cursor.expect('{')?;
+        let mut members = Vec::new();
+        while !self.cursor.accept('}') {
+            if self.cursor.is_done() {
+                return Err(Diagnostic::error(line, column, &format!("message `{}` is not closed", name)));
+            }
+            if let Err(diagnostic) = self.member(&name, &mut members) {
+                self.diagnostics.push(diagnostic);
+                self.cursor.skip_statement();
+            }
+        }
+        self.messages.push(Message { name, members, line, column });
+        Ok(())
+    }
+
+    fn member(&mut self, scope: &str, members: &mut Vec<Member>) -> Result<(), Diagnostic> {
+        if self.cursor.accept(';') {
+            return Ok(());
+        }
+        if self.cursor.is_keyword("map") && self.cursor.peek_at(1) == Some(&Token::Symbol('<')) {
+            return self.unsupported("map fields are not supported");
+        }
+        let keyword = match self.cursor.peek() {
+            Some(Token::Identifier(keyword)) => keyword.clone(),
+            Some(Token::Symbol('.')) => String::new(),
+            _ => return Err(self.cursor.error("expected a field")),
+        };
+        match keyword.as_str() {
+            "message" => self.message(scope),
+            "enum" => self.enumeration(scope),
+            "oneof" => {
+                let (line, column) = self.cursor.location();
+                self.cursor.next();
+                let name = self.cursor.identifier()?;
+                self.cursor.expect('{')?;
+                let mut fields = Vec::new();
+                while !self.cursor.accept('}') {
+                    if self.cursor.is_done() {
+                        return Err(Diagnostic::error(line, column, &format!("oneof `{}` is not closed", name)));
+                    }
+                    if self.cursor.is_keyword("option") {
+                        self.warn("options are ignored");
+                        self.cursor.skip_statement();
+                        continue;
+                    }
+                    match self.field() {
+                        Ok(field) => fields.push(field),
+                        Err(diagnostic) => {
+                            self.diagnostics.push(diagnostic);
+                            self.cursor.skip_statement();
+                        }
+                    }
+                }
+                members.push(Member::Oneof { name, fields, line, column });
+                Ok(())
+            }
+            "reserved" => {
+                self.cursor.skip_statement();
+                Ok(())
+            }
+            "option" => {
+                self.warn("options are ignored");
+                self.cursor.skip_statement();
+                Ok(())
+            }
+            "extensions" | "extend" => self.unsupported("extensions are not supported"),
+            "required" | "group" => self.unsupported(&format!("`{}` is a proto2 construct", keyword)),
+            _ => {
+                members.push(Member::Field(self.field()?));
+                Ok(())
+            }
+        }
+    }
+
+    fn field(&mut self) -> Result<Field, Diagnostic> {
+        let repeated = self.cursor.is_keyword("repeated");
+        if repeated {
+            self.cursor.next();
+        } else if self.cursor.is_keyword("optional") {
+            self.warn("`optional` is ignored, the field is always present");
+            self.cursor.next();
+        }
+        let (line, column) = self.cursor.location();
+        let type_name = self.full_name()?;
+        let name = self.cursor.identifier()?;
+        self.cursor.expect('=')?;
+        let number = self.cursor.number()?;
+        let options = self.options()?;
+        let bound = options.iter().find(|(option, _)| option == "(bound)").map(|(_, value)| value);
+        let bound = match (repeated, bound) {
+            (true, Some(Token::Number(bound))) => Some(
+                crate::import::lexer::parse_integer(bound).and_then(|bound| usize::try_from(bound).ok())
+                    .ok_or_else(|| Diagnostic::error(line, column, "the bound must be a positive integer"))?
+            ),
+            (true, _) => return Err(Diagnostic::error(line, column, &format!(
+                "repeated field `{}` has no fixed size, add a bound like `[(bound) = 4]`", name
+            ))),
+            (false, Some(_)) => {
+                self.diagnostics.push(Diagnostic::warning(line, column, "`(bound)` is ignored on a field which is not repeated"));
+                None
+            }
+            (false, None) => None,
+        };
+        self.cursor.expect(';')?;
+        Ok(Field { name, type_name, number, bound, line, column })
+    }
+
+    fn options(&mut self) -> Result<Vec<(String, Token)>, Diagnostic> {
+        let mut options = Vec::new();
+        if !self.cursor.accept('[') {
+            return Ok(options);
+        }
+        loop {
+            let name = if self.cursor.accept('(') {
+                let name = format!("({})", self.full_name()?);
+                self.cursor.expect(')')?;
+                name
+            } else {
+                self.full_name()?
+            };
+            self.cursor.expect('=')?;
+            let negative = self.cursor.accept('-');
+            let value = match self.cursor.next() {
+                Some(Token::Number(number)) if negative => Token::Number(format!("-{}", number)),
+                Some(value) => value,
+                None => return Err(self.cursor.error("expected an option value")),
+            };
+            options.push((name, value));
+            if !self.cursor.accept(',') {
+                self.cursor.expect(']')?;
+                return Ok(options);
+            }
+        }
+    }
+
+    fn enumeration(&mut self, scope: &str) -> Result<(), Diagnostic> {
+        let (line, column) = self.cursor.location();
+        self.cursor.next();
+        let name = qualify(scope, &self.cursor.identifier()?);
+        self.cursor.expect('{')?;
+        let mut values = Vec::new();
+        while !self.cursor.accept('}') {
+            if self.cursor.is_done() {
+                return Err(Diagnostic::error(line, column, &format!("enum `{}` is not closed", name)));
+            }
+            if self.cursor.accept(';') || self.cursor.is_keyword("reserved") {
+                self.cursor.skip_statement();
+                continue;
+            }
+            if self.cursor.is_keyword("option") {
+                self.warn("options are ignored");
+                self.cursor.skip_statement();
+                continue;
+            }
+            let (line, column) = self.cursor.location();
+            let value = (|| {
+                let name = self.cursor.identifier()?;
+                self.cursor.expect('=')?;
+                let number = self.cursor.number()?;
+                self.options()?;
+                self.cursor.expect(';')?;
+                Ok(EnumValue { name, number, line, column })
+            })();
+            match value {
+                Ok(value) => values.push(value),
+                Err(diagnostic) => {
+                    self.diagnostics.push(diagnostic);
+                    self.cursor.skip_statement();
+                }
+            }
+        }
+        self.enums.push(Enum { name, values });
+        Ok(())
+    }
+}
+
+struct Builder<'a> {
+    package: &'a str,
+    messages: HashMap<&'a str, &'a Message>,
+    enums: HashMap<&'a str, &'a Enum>,
+    /// The declarations already built, [None] if they failed.
+    built: HashMap<String, Option<Type>>,
+    pending: Vec<String>,
+    registry: Registry,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn scalar(type_name: &str) -> Option<Result<Type, String>> {
+    Some(Ok(match type_name {
+        "double" | "float" => Primitive::Float,
+        "int32" | "int64" | "sint32" | "sint64" | "sfixed32" | "sfixed64" => Primitive::Integer,
+        "uint32" | "fixed32" => Primitive::Bytes(4),
+        "uint64" | "fixed64" => Primitive::Bytes(8),
+        "bool" => Primitive::Boolean,
+        "string" | "bytes" => return Some(Err(format!("`{}` has no fixed size and cannot be imported", type_name))),
+        _ => return None,
+    }.to_rc()))
+}
+
+impl Builder<'_> {
+    fn identifier(&self, name: &str) -> String {
+        if self.package.is_empty() { name.to_string() } else { format!("{}/{}", self.package, name) }
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.messages.contains_key(name) || self.enums.contains_key(name)
+    }
+
+    /// Resolves a type name used in the `scope` message to the name of its declaration.
+    fn resolve(&self, type_name: &str, scope: &str) -> Option<String> {
+        let package_prefix = format!("{}.", self.package);
+        if let Some(absolute) = type_name.strip_prefix('.') {
+            let name = if self.package.is_empty() { Some(absolute) } else { absolute.strip_prefix(&package_prefix) };
+            return name.filter(|name| self.is_declared(name)).map(str::to_string);
+        }
+        let mut scope = scope;
+        loop {
+            let candidate = qualify(scope, type_name);
+            if self.is_declared(&candidate) {
+                return Some(candidate);
+            }
+            if scope.is_empty() {
+                break;
+            }
+            scope = scope.rsplit_once('.').map(|(parent, _)| parent).unwrap_or("");
+        }
+        type_name.strip_prefix(&package_prefix).filter(|name| self.is_declared(name)).map(str::to_string)
+    }
+
+    fn field_type(&mut self, field: &Field, scope: &str) -> Option<Type> {
+        let element = match scalar(&field.type_name) {
+            Some(Ok(element)) => element,
+            Some(Err(message)) => {
+                self.diagnostics.push(Diagnostic::error(field.line, field.column, &message));
+                return None;
+            }
+            None => match self.resolve(&field.type_name, scope) {
+                Some(name) => self.build(&name)?,
+                None => {
+                    self.diagnostics.push(Diagnostic::error(field.line, field.column, &format!("unknown type `{}`", field.type_name)));
+                    return None;
+                }
+            },
+        };
+        Some(match field.bound {
+            Some(bound) => Primitive::list(element, bound).to_rc(),
+            None => element,
+        })
+    }
+
+    fn build(&mut self, name: &str) -> Option<Type> {
+        if let Some(built) = self.built.get(name) {
+            return built.clone();
+        }
+        if let Some(enumeration) = self.enums.get(name).copied() {
+            let built = self.build_enum(enumeration);
+            self.built.insert(name.to_string(), built.clone());
+            return built;
+        }
+        let message = self.messages.get(name).copied()?;
+        if self.pending.iter().any(|pending| pending == name) {
+            self.diagnostics.push(Diagnostic::error(message.line, message.column, &format!(
+                "message `{}` contains itself and cannot be imported", name
+            )));
+            return None;
+        }
+        self.pending.push(name.to_string());
+        let built = self.build_message(message);
+        self.pending.pop();
+        self.built.insert(name.to_string(), built.clone());
+        built
+    }
+
+    fn build_message(&mut self, message: &Message) -> Option<Type> {
+        let mut labels = Vec::new();
+        let mut types = Vec::new();
+        let mut numbers: Vec<i64> = Vec::new();
+        let mut names: Vec<&str> = Vec::new();
+        let mut oneofs = Vec::new();
+        let mut complete = true;
+        for member in &message.members {
+            let fields = match member {
+                Member::Field(field) => std::slice::from_ref(field),
+                Member::Oneof { name, fields, line, column } => {
+                    complete &= self.declare(name, *line, *column, &message.name, &mut names);
+                    fields.as_slice()
+                }
+            };
+            for field in fields {
+                complete &= self.declare(&field.name, field.line, field.column, &message.name, &mut names);
+                if numbers.contains(&field.number) {
+                    self.diagnostics.push(Diagnostic::error(field.line, field.column, &format!("field number {} is already used", field.number)));
+                    complete = false;
+                }
+                numbers.push(field.number);
+            }
+            match member {
+                Member::Field(field) => match self.field_type(field, &message.name) {
+                    Some(field_type) => {
+                        labels.push(field.name.clone());
+                        types.push(field_type);
+                    }
+                    None => complete = false,
+                },
+                Member::Oneof { name, fields, .. } => {
+                    let variants: Vec<Option<Type>> = fields.iter().map(|field| self.field_type(field, &message.name)).collect();
+                    if variants.iter().any(Option::is_none) {
+                        complete = false;
+                        continue;
+                    }
+                    let variant_labels: Vec<&str> = fields.iter().map(|field| field.name.as_str()).collect();
+                    let variant_types: Vec<Type> = variants.into_iter().flatten().collect();
+                    let enumeration = Enumeration::new(
+                        &self.identifier(&qualify(&message.name, name)),
+                        LabelBank::from(&variant_labels),
+                        SumType::new(&variant_types),
+                    ).to_rc();
+                    oneofs.push(enumeration.clone());
+                    labels.push(name.clone());
+                    types.push(enumeration);
+                }
+            }
+        }
+        if !complete {
+            return None;
+        }
+        for oneof in oneofs {
+            self.registry.add_enumeration(oneof);
+        }
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let structure = Structure::new(&self.identifier(&message.name), LabelBank::from(&labels), ProductType::new(&types)).to_rc();
+        self.registry.add_structure(structure.clone());
+        Some(structure)
+    }
+
+    /// Declares `name` in the message `scope`, and reports it at `line` and `column` if it is already declared.
+    fn declare<'a>(&mut self, name: &'a str, line: usize, column: usize, scope: &str, names: &mut Vec<&'a str>) -> bool {
+        if names.contains(&name) {
+            self.diagnostics.push(Diagnostic::error(line, column, &format!("`{}` is already declared in message `{}`", name, scope)));
+            return false;
+        }
+        names.push(name);
+        true
+    }
+
+    fn build_enum(&mut self, enumeration: &Enum) -> Option<Type> {
+        let mut complete = true;
+        for (index, value) in enumeration.values.iter().enumerate() {
+            if value.number != index as i64 {
+                self.diagnostics.push(Diagnostic::error(value.line, value.column, &format!(
+                    "`{}` should be numbered {} : enum values must be numbered 0, 1, 2... in order", value.name, index
+                )));
+                complete = false;
+            }
+        }
+        if !complete {
+            return None;
+        }
+        let labels: Vec<&str> = enumeration.values.iter().map(|value| value.name.as_str()).collect();
+        let variants: Vec<Type> = enumeration.values.iter().map(|_| ProductType::unit_type().to_rc() as Type).collect();
+        let built = Enumeration::new(&self.identifier(&enumeration.name), LabelBank::from(&labels), SumType::new(&variants)).to_rc();
+        self.registry.add_enumeration(built.clone());
+        Some(built)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::import::proto::import_proto;
+    use crate::import::Diagnostic;
+    use crate::types::concept::DataType;
+
+    fn errors(source: &str) -> Vec<String> {
+        import_proto(source).unwrap_err().iter().filter(|diagnostic| diagnostic.is_error()).map(Diagnostic::to_string).collect()
+    }
+
+    #[test]
+    fn test_repeated_bounds() {
+        let imported = import_proto("syntax = \"proto3\"; message M { repeated int64 a = 1 [(bound) = 2]; int64 b = 2; }").unwrap();
+        assert_eq!(imported.registry.structure("M").unwrap().product_type.typename(), "<[integer;2]&integer>");
+
+        let reported = errors("message M {\n  repeated int64 a = 1;\n  Unknown b = 2;\n  repeated bool c = 3 [(bound) = -1];\n}");
+        assert_eq!(reported, [
+            "2:12: error: repeated field `a` has no fixed size, add a bound like `[(bound) = 4]`",
+            "4:12: error: the bound must be a positive integer",
+            "3:3: error: unknown type `Unknown`",
+        ]);
+    }
+
+    #[test]
+    fn test_bound_on_single_field() {
+        let imported = import_proto("message M { int64 a = 1 [(bound) = 2]; }").unwrap();
+        assert_eq!(imported.warnings.len(), 1);
+        assert_eq!(imported.warnings[0].message, "`(bound)` is ignored on a field which is not repeated");
+    }
+
+    #[test]
+    fn test_absolute_names() {
+        let imported = import_proto("package shop.v1; message A { .shop.v1.B b = 1; shop.v1.B.C c = 2; } message B { enum C { X = 0; } }").unwrap();
+        assert_eq!(imported.registry.structure("shop.v1/A").unwrap().product_type.typename(), "<shop.v1/B&shop.v1/B.C>");
+        assert_eq!(errors("package shop; message A { .other.B b = 1; } message B {}"), ["1:27: error: unknown type `.other.B`"]);
+        assert!(import_proto("message A { .B b = 1; } message B {}").is_ok());
+    }
+
+    #[test]
+    fn test_failures_are_reported_once() {
+        let reported = errors("message Bad { string s = 1; }\nmessage A { Bad a = 1; }\nmessage B { Bad b = 1; oneof choice { Bad c = 2; } }");
+        assert_eq!(reported, ["1:15: error: `string` has no fixed size and cannot be imported"]);
+        let reported = errors("enum E { X = 1; }\nmessage A { E a = 1; E b = 2; }");
+        assert_eq!(reported, ["1:10: error: `X` should be numbered 0 : enum values must be numbered 0, 1, 2... in order"]);
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        assert_eq!(errors("message A { A a = 1; }"), ["1:1: error: message `A` contains itself and cannot be imported"]);
+        assert_eq!(errors("message A { int64 a = 1; bool b = 1; }"), ["1:26: error: field number 1 is already used"]);
+        assert_eq!(errors("message A { int64 a = 1; bool a = 2; }"), ["1:26: error: `a` is already declared in message `A`"]);
+        assert_eq!(errors("message A { oneof c { int64 a = 1; }\n  bool b = 2; oneof c { bool d = 3; } }"), ["2:15: error: `c` is already declared in message `A`"]);
+        assert_eq!(errors("message A { int64 a = 1; oneof c { bool a = 2; } }"), ["1:36: error: `a` is already declared in message `A`"]);
+        assert_eq!(errors("message A { map<int64, int64> m = 1; }"), ["1:13: error: map fields are not supported"]);
+        assert_eq!(errors("syntax = \"proto2\";"), ["1:10: error: only `syntax = \"proto3\";` is supported"]);
+        assert_eq!(errors("message A { required int64 a = 1; }"), ["1:13: error: `required` is a proto2 construct"]);
+        assert_eq!(errors("message A { int64 a = 1;"), ["1:1: error: message `A` is not closed"]);
+    }
+}
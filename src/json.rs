@@ -0,0 +1,34 @@
+//! A JSON import and export of values, driven by their [crate::types::concept::Type].
+//!
+//! - [document::Json] is a JSON document, rendered with [std::fmt::Display] or [document::Json::pretty],
+//! - [parser::parse] reads a JSON text,
+//! - [mapping] converts values to documents and back,
+//! - [schema] describes the documents of a type as a JSON Schema.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::facade::create_struct;
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::json::mapping::{from_json, to_json};
+//! use roussillon_type_system::json::parser::parse;
+//! use roussillon_type_system::types::concept::Type;
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::value::error::{JsonError, TypeError};
+//!
+//! let point: Type = create_struct("Point", LabelBank::from(&["x", "y"]), &[
+//!     Primitive::Integer.to_rc(),
+//!     Primitive::Float.to_rc(),
+//! ]);
+//!
+//! let value = from_json(&point, &parse(r#"{"x": 3, "y": 0.5}"#).unwrap()).unwrap();
+//! assert_eq!(to_json(&value).unwrap().to_string(), r#"{"x":3,"y":0.5}"#);
+//!
+//! let error = from_json(&point, &parse(r#"{"x": 3, "y": true}"#).unwrap()).unwrap_err();
+//! assert!(matches!(error, TypeError::JsonError(JsonError::Mismatch { pointer, .. }) if pointer == "/y"));
+//! ```
+
+pub mod document;
+pub mod parser;
+pub mod mapping;
+pub mod schema;
@@ -0,0 +1,242 @@
+//! The [Json] document model and its writers.
+
+use std::fmt::{Display, Formatter, Write};
+
+use crate::json::parser::parse;
+use crate::value::error::TypeResult;
+
+/// A JSON document.
+///
+/// Numbers keep their textual representation, so integers and addresses never lose precision.
+/// Object members keep their order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Boolean(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses a JSON text, see [crate::json::parser::parse].
+    pub fn parse(text: &str) -> TypeResult<Self> { parse(text) }
+
+    pub fn integer(value: i64) -> Self { Json::Number(value.to_string()) }
+
+    pub fn unsigned(value: u64) -> Self { Json::Number(value.to_string()) }
+
+    /// A finite float as a number, or `"NaN"`, `"Infinity"` and `"-Infinity"` as strings.
+    pub fn float(value: f64) -> Self {
+        if value.is_nan() {
+            Json::String("NaN".to_string())
+        } else if value.is_infinite() {
+            Json::String(if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string())
+        } else {
+            Json::Number(format!("{:?}", value))
+        }
+    }
+
+    pub fn string(value: &str) -> Self { Json::String(value.to_string()) }
+
+    pub fn object(members: &[(&str, Json)]) -> Self {
+        Json::Object(members.iter().map(|(key, value)| (key.to_string(), value.clone())).collect())
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Json::Boolean(value) = self { Some(*value) } else { None }
+    }
+
+    /// An integer number, which may be written with a zero fraction or an exponent like `1.0` or `1e3`.
+    pub fn as_i64(&self) -> Option<i64> {
+        if let Json::Number(text) = self { integral(text)?.parse().ok() } else { None }
+    }
+
+    /// A non-negative integer number, see [Json::as_i64].
+    pub fn as_u64(&self) -> Option<u64> {
+        if let Json::Number(text) = self { integral(text)?.parse().ok() } else { None }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        if let Json::Number(text) = self { text.parse().ok() } else { None }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        if let Json::String(value) = self { Some(value) } else { None }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        if let Json::Array(elements) = self { Some(elements) } else { None }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        if let Json::Object(members) = self { Some(members) } else { None }
+    }
+
+    /// Returns the first member of an object with the `key`.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        self.as_object()?.iter().find(|(name, _)| name == key).map(|(_, value)| value)
+    }
+
+    /// A short description of the kind of this document, for error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Json::Null => "null",
+            Json::Boolean(_) => "boolean",
+            Json::Number(_) => "number",
+            Json::String(_) => "string",
+            Json::Array(_) => "array",
+            Json::Object(_) => "object",
+        }
+    }
+
+    /// Renders this document with an indentation of two spaces.
+    pub fn pretty(&self) -> String {
+        let mut output = String::new();
+        self.write_pretty(&mut output, 0).unwrap();
+        output
+    }
+
+    fn write_pretty(&self, output: &mut String, depth: usize) -> std::fmt::Result {
+        let indent = "  ".repeat(depth + 1);
+        match self {
+            Json::Array(elements) if !elements.is_empty() => {
+                output.push('[');
+                for (index, element) in elements.iter().enumerate() {
+                    output.push_str(if index == 0 { "\n" } else { ",\n" });
+                    output.push_str(&indent);
+                    element.write_pretty(output, depth + 1)?;
+                }
+                write!(output, "\n{}]", "  ".repeat(depth))
+            }
+            Json::Object(members) if !members.is_empty() => {
+                output.push('{');
+                for (index, (key, value)) in members.iter().enumerate() {
+                    output.push_str(if index == 0 { "\n" } else { ",\n" });
+                    write!(output, "{}{}: ", indent, Json::String(key.clone()))?;
+                    value.write_pretty(output, depth + 1)?;
+                }
+                write!(output, "\n{}}}", "  ".repeat(depth))
+            }
+            _ => write!(output, "{}", self),
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, value: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            '\u{8}' => f.write_str("\\b")?,
+            '\u{c}' => f.write_str("\\f")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// The integer digits of a JSON number, with its sign, if it has no fractional part.
+fn integral(text: &str) -> Option<String> {
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (text, 0),
+    };
+    let (sign, mantissa) = mantissa.strip_prefix('-').map_or(("", mantissa), |mantissa| ("-", mantissa));
+    let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", integer, fraction).trim_start_matches('0').to_string();
+    if digits.is_empty() {
+        return Some("0".to_string());
+    }
+    let fraction_digits = (fraction.len() as i64).checked_sub(exponent)?;
+    if fraction_digits <= 0 {
+        // beyond 64 digits, the number does not fit in any integer
+        let zeros = usize::try_from(-fraction_digits).ok().filter(|zeros| digits.len() + zeros <= 64)?;
+        return Some(format!("{}{}{}", sign, digits, "0".repeat(zeros)));
+    }
+    let fraction_digits = usize::try_from(fraction_digits).ok()?;
+    let (integer, fraction) = digits.split_at(digits.len().saturating_sub(fraction_digits));
+    if fraction.trim_end_matches('0').is_empty() {
+        Some(format!("{}{}", sign, if integer.is_empty() { "0" } else { integer }))
+    } else {
+        None
+    }
+}
+
+macro_rules! from_number {
+    ($($rust:ty),+) => {
+        $(
+            impl From<$rust> for Json {
+                fn from(value: $rust) -> Self { Json::Number(value.to_string()) }
+            }
+        )+
+    };
+}
+
+from_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Self { Json::float(value) }
+}
+
+/// A finite `f32` is written with its own shortest digits, not those of its `f64` conversion.
+impl From<f32> for Json {
+    fn from(value: f32) -> Self {
+        if value.is_finite() { Json::Number(format!("{:?}", value)) } else { Json::float(value as f64) }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self { Json::Boolean(value) }
+}
+
+impl From<char> for Json {
+    fn from(value: char) -> Self { Json::String(value.to_string()) }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self { Json::string(value) }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self { Json::String(value) }
+}
+
+/// Renders the compact form of the document.
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Boolean(value) => write!(f, "{}", value),
+            Json::Number(text) => write!(f, "{}", text),
+            Json::String(value) => write_string(f, value),
+            Json::Array(elements) => {
+                f.write_char('[')?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_char(']')
+            }
+            Json::Object(members) => {
+                f.write_char('{')?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! The type-directed mapping between values and [Json] documents.
+//!
+//! | Type                                           | JSON                                            |
+//! |------------------------------------------------|-------------------------------------------------|
+//! | [Primitive::Boolean]                           | `true` or `false`                               |
+//! | [Primitive::Byte]                              | a number from `0` to `255`                      |
+//! | [Primitive::Bytes]                             | a string of lowercase hexadecimal digits        |
+//! | [Primitive::Integer]                           | a number                                        |
+//! | [Primitive::Float]                             | a number, `"NaN"`, `"Infinity"` or `"-Infinity"` |
+//! | [Primitive::Reference]                         | the address as a number                         |
+//! | [Primitive::List], [Tuple] and [ProductType]   | an array                                        |
+//! | [Structure]                                    | an object with a key for each field label       |
+//! | [SumType]                                      | `{"<tag>": payload}`                            |
+//! | [Enumeration]                                  | `{"<variant label>": payload}`                  |
+//!
+//! The [crate::annotation::Annotations] of fields and variants rename their keys, and skipped fields are left out : they take their default value when imported.
+//!
+//! Errors are located with a JSON pointer (RFC 6901) like `/field_b/3`.
+
+use std::fmt::{Display, Formatter};
+
+use crate::annotation::Annotations;
+use crate::identity::LabelBank;
+use crate::json::document::Json;
+use crate::types::algebraic::{read_tag, ProductType, SumType, TAG_SIZE};
+use crate::types::concept::{DataType, Type};
+use crate::types::primitive::Primitive;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::concept::ValueCell;
+use crate::value::error::{JsonError, TypeError, TypeResult};
+use crate::value::validation::validate;
+
+/// The strings used for the floats which are not JSON numbers.
+pub const FLOAT_SPECIALS: [&str; 3] = ["NaN", "Infinity", "-Infinity"];
+
+/// A JSON pointer (RFC 6901).
+#[derive(Clone, Debug, Default)]
+pub struct Pointer(Vec<String>);
+
+impl Pointer {
+    pub fn root() -> Self { Self(Vec::new()) }
+
+    pub fn push(&mut self, token: &str) { self.0.push(token.to_string()) }
+
+    pub fn pop(&mut self) { self.0.pop(); }
+}
+
+impl Display for Pointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for token in &self.0 {
+            write!(f, "/{}", token.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+/// The labels of the fields of a [Structure], in field order.
+///
+/// A field without label is named by its index.
+pub fn field_labels(structure: &Structure) -> Vec<String> {
+    variant_labels(Some(&structure.labels), structure.product_type.to_tuple().len())
+}
+
+/// The labels of the variants of a sum, in tag order.
+///
+/// Without labels, or for a variant without label, the label is the tag.
+pub fn variant_labels(labels: Option<&LabelBank>, count: usize) -> Vec<String> {
+    let mut keys = position_keys(count);
+    for (label, tag) in labels.iter().flat_map(|labels| labels.iter()) {
+        if let Some(key) = keys.get_mut(tag) {
+            *key = label.to_string();
+        }
+    }
+    keys
+}
+
+/// The object keys of the fields of a [Structure], in field order.
+///
+/// A field is keyed by its renamed key, its label or its index. Skipped fields have no key.
+pub fn field_keys(structure: &Structure) -> Vec<Option<String>> {
+    field_labels(structure).into_iter().enumerate()
+        .map(|(index, label)| match structure.labels.annotations(index) {
+            Some(annotations) if annotations.skip => None,
+            Some(Annotations { rename: Some(key), .. }) => Some(key.clone()),
+            _ => Some(label),
+        })
+        .collect()
+}
+
+/// The object keys of the variants of a sum, in tag order.
+///
+/// A variant is keyed by its renamed key, its label or its tag.
+pub fn variant_keys(labels: Option<&LabelBank>, count: usize) -> Vec<String> {
+    let mut keys = variant_labels(labels, count);
+    for (tag, key) in keys.iter_mut().enumerate() {
+        if let Some(rename) = labels.and_then(|labels| labels.annotations(tag)?.rename.as_ref()) {
+            *key = rename.clone();
+        }
+    }
+    keys
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes hexadecimal digits.
+pub fn from_hex(digits: &str) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) || !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..digits.len()).step_by(2)
+        .map(|index| u8::from_str_radix(&digits[index..index + 2], 16).ok())
+        .collect()
+}
+
+/// Exports a value as a [Json] document.
+pub fn to_json(value: &ValueCell) -> TypeResult<Json> {
+    let value = value.borrow();
+    raw_to_json(&value.data_type(), &value.raw())
+}
+
+/// Exports a raw value of `data_type` as a [Json] document.
+pub fn raw_to_json(data_type: &Type, raw: &[u8]) -> TypeResult<Json> {
+    validate(data_type, raw).map_err(TypeError::InvalidRaw)?;
+    export(data_type, raw, &mut Pointer::root())
+}
+
+/// Imports a value of `data_type` from a [Json] document.
+pub fn from_json(data_type: &Type, json: &Json) -> TypeResult<ValueCell> {
+    data_type.construct_from_raw(&json_to_raw(data_type, json)?)
+}
+
+/// Imports a raw value of `data_type` from a [Json] document.
+pub fn json_to_raw(data_type: &Type, json: &Json) -> TypeResult<Vec<u8>> {
+    let mut raw = Vec::with_capacity(data_type.size());
+    import(data_type, json, &mut Pointer::root(), &mut raw)?;
+    Ok(raw)
+}
+
+fn unsupported(data_type: &Type, pointer: &Pointer) -> TypeError {
+    JsonError::Unsupported { pointer: pointer.to_string(), data_type: data_type.clone() }.promote()
+}
+
+fn export(data_type: &Type, raw: &[u8], pointer: &mut Pointer) -> TypeResult<Json> {
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return Ok(match primitive {
+            Primitive::Boolean => Json::Boolean(raw[0] != 0),
+            Primitive::Byte => Json::unsigned(raw[0] as u64),
+            Primitive::Bytes(_) => Json::String(to_hex(raw)),
+            Primitive::Float => Json::float(f64::from_be_bytes(raw.try_into().unwrap())),
+            Primitive::Integer => Json::integer(i64::from_be_bytes(raw.try_into().unwrap())),
+            Primitive::Reference(_) => Json::unsigned(u64::from_be_bytes(raw.try_into().unwrap())),
+            Primitive::List((of_type, len)) => {
+                let size = of_type.size();
+                let mut elements = Vec::with_capacity(*len);
+                for index in 0..*len {
+                    pointer.push(&index.to_string());
+                    elements.push(export(of_type, &raw[index * size..(index + 1) * size], pointer)?);
+                    pointer.pop();
+                }
+                Json::Array(elements)
+            }
+        });
+    }
+    if let Some(tuple) = data_type.downcast_ref::<Tuple>() {
+        return Ok(Json::Array(export_members(tuple, &position_keys(tuple.len()), raw, pointer)?));
+    }
+    if let Some(product) = data_type.downcast_ref::<ProductType>() {
+        let tuple = product.to_tuple();
+        return Ok(Json::Array(export_members(&tuple, &position_keys(tuple.len()), raw, pointer)?));
+    }
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        let mut members = Vec::new();
+        let mut start = 0;
+        for (key, field) in field_keys(structure).into_iter().zip(structure.product_type.to_tuple()) {
+            let end = start + field.size();
+            if let Some(key) = key {
+                pointer.push(&key);
+                let member = export(&field, &raw[start..end], pointer)?;
+                pointer.pop();
+                members.push((key, member));
+            }
+            start = end;
+        }
+        return Ok(Json::Object(members));
+    }
+    if let Some(sum) = data_type.downcast_ref::<SumType>() {
+        return export_sum(sum, None, raw, pointer);
+    }
+    if let Some(enumeration) = data_type.downcast_ref::<Enumeration>() {
+        return export_sum(&enumeration.sum_type, Some(enumeration.labels()), raw, pointer);
+    }
+    Err(unsupported(data_type, pointer))
+}
+
+fn position_keys(count: usize) -> Vec<String> {
+    (0..count).map(|index| index.to_string()).collect()
+}
+
+fn export_members(members: &Tuple, keys: &[String], raw: &[u8], pointer: &mut Pointer) -> TypeResult<Vec<Json>> {
+    let mut start = 0;
+    let mut exported = Vec::with_capacity(members.len());
+    for (member, key) in members.iter().zip(keys) {
+        let end = start + member.size();
+        pointer.push(key);
+        exported.push(export(member, &raw[start..end], pointer)?);
+        pointer.pop();
+        start = end;
+    }
+    Ok(exported)
+}
+
+fn export_sum(sum: &SumType, labels: Option<&LabelBank>, raw: &[u8], pointer: &mut Pointer) -> TypeResult<Json> {
+    let tag = read_tag(raw).unwrap_or_default() as usize;
+    let variant = sum.variant(tag).unwrap();
+    let key = variant_keys(labels, sum.len()).swap_remove(tag);
+    pointer.push(&key);
+    let payload = export(&variant, &raw[TAG_SIZE..TAG_SIZE + variant.size()], pointer)?;
+    pointer.pop();
+    Ok(Json::Object(vec![(key, payload)]))
+}
+
+fn mismatch(expected: &Type, found: String, pointer: &Pointer) -> TypeError {
+    JsonError::Mismatch { pointer: pointer.to_string(), expected: expected.clone(), found }.promote()
+}
+
+fn import(data_type: &Type, json: &Json, pointer: &mut Pointer, raw: &mut Vec<u8>) -> TypeResult<()> {
+    let kind_mismatch = || mismatch(data_type, json.kind().to_string(), pointer);
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        match primitive {
+            Primitive::Boolean => raw.push(u8::from(json.as_bool().ok_or_else(kind_mismatch)?)),
+            Primitive::Byte => {
+                let byte = json.as_u64().and_then(|byte| u8::try_from(byte).ok())
+                    .ok_or_else(|| mismatch(data_type, format!("{}", json), pointer))?;
+                raw.push(byte);
+            }
+            Primitive::Bytes(size) => {
+                let bytes = json.as_str().and_then(from_hex).filter(|bytes| bytes.len() == *size)
+                    .ok_or_else(|| mismatch(data_type, format!("{}", json), pointer))?;
+                raw.extend(bytes);
+            }
+            Primitive::Float => {
+                let value = match json {
+                    Json::String(special) if FLOAT_SPECIALS.contains(&special.as_str()) => match special.as_str() {
+                        "NaN" => f64::NAN,
+                        "Infinity" => f64::INFINITY,
+                        _ => f64::NEG_INFINITY,
+                    },
+                    _ => json.as_f64().ok_or_else(kind_mismatch)?,
+                };
+                raw.extend(value.to_be_bytes());
+            }
+            Primitive::Integer => {
+                let value = json.as_i64().ok_or_else(|| mismatch(data_type, format!("{}", json), pointer))?;
+                raw.extend(value.to_be_bytes());
+            }
+            Primitive::Reference(_) => {
+                let address = json.as_u64().ok_or_else(|| mismatch(data_type, format!("{}", json), pointer))?;
+                raw.extend(address.to_be_bytes());
+            }
+            Primitive::List((of_type, len)) => {
+                let elements = json.as_array().ok_or_else(kind_mismatch)?;
+                if elements.len() != *len {
+                    return Err(mismatch(data_type, format!("array of {} elements", elements.len()), pointer));
+                }
+                for (index, element) in elements.iter().enumerate() {
+                    pointer.push(&index.to_string());
+                    import(of_type, element, pointer, raw)?;
+                    pointer.pop();
+                }
+            }
+        }
+        return Ok(());
+    }
+    if let Some(tuple) = data_type.downcast_ref::<Tuple>() {
+        return import_array(data_type, tuple, json, pointer, raw);
+    }
+    if let Some(product) = data_type.downcast_ref::<ProductType>() {
+        return import_array(data_type, &product.to_tuple(), json, pointer, raw);
+    }
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        let members = json.as_object().ok_or_else(kind_mismatch)?;
+        let keys = field_keys(structure);
+        if let Some((key, _)) = members.iter().find(|(key, _)| !keys.contains(&Some(key.clone()))) {
+            return Err(JsonError::UnknownKey { pointer: pointer.to_string(), key: key.clone() }.promote());
+        }
+        for (index, (key, field)) in keys.iter().zip(structure.product_type.to_tuple().iter()).enumerate() {
+            let Some(key) = key else {
+                raw.extend(structure.field_default_raw(index).unwrap());
+                continue;
+            };
+            let value = json.get(key)
+                .ok_or_else(|| JsonError::MissingKey { pointer: pointer.to_string(), key: key.clone() }.promote())?;
+            pointer.push(key);
+            import(field, value, pointer, raw)?;
+            pointer.pop();
+        }
+        return Ok(());
+    }
+    if let Some(sum) = data_type.downcast_ref::<SumType>() {
+        return import_sum(data_type, sum, None, json, pointer, raw);
+    }
+    if let Some(enumeration) = data_type.downcast_ref::<Enumeration>() {
+        return import_sum(data_type, &enumeration.sum_type, Some(enumeration.labels()), json, pointer, raw);
+    }
+    Err(unsupported(data_type, pointer))
+}
+
+fn import_array(data_type: &Type, members: &Tuple, json: &Json, pointer: &mut Pointer, raw: &mut Vec<u8>) -> TypeResult<()> {
+    let elements = json.as_array().ok_or_else(|| mismatch(data_type, json.kind().to_string(), pointer))?;
+    if elements.len() != members.len() {
+        return Err(mismatch(data_type, format!("array of {} elements", elements.len()), pointer));
+    }
+    for (index, (member, element)) in members.iter().zip(elements).enumerate() {
+        pointer.push(&index.to_string());
+        import(member, element, pointer, raw)?;
+        pointer.pop();
+    }
+    Ok(())
+}
+
+fn import_sum(data_type: &Type, sum: &SumType, labels: Option<&LabelBank>, json: &Json, pointer: &mut Pointer, raw: &mut Vec<u8>) -> TypeResult<()> {
+    let members = json.as_object().ok_or_else(|| mismatch(data_type, json.kind().to_string(), pointer))?;
+    let [(key, payload)] = members else {
+        return Err(mismatch(data_type, format!("object of {} members", members.len()), pointer));
+    };
+    let tag = variant_keys(labels, sum.len()).iter().position(|variant_key| variant_key == key).ok_or_else(|| JsonError::UnknownKey { pointer: pointer.to_string(), key: key.clone() }.promote())?;
+    let variant = sum.variant(tag).unwrap();
+    let start = raw.len();
+    raw.extend((tag as u64).to_be_bytes());
+    pointer.push(key);
+    import(&variant, payload, pointer, raw)?;
+    pointer.pop();
+    raw.resize(start + sum.size(), 0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::facade::create_struct;
+    use crate::identity::LabelBank;
+    use crate::json::document::Json;
+    use crate::json::mapping::{from_hex, json_to_raw, raw_to_json};
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::value::error::{JsonError, TypeError};
+
+    fn import_error(data_type: &Type, text: &str) -> JsonError {
+        match json_to_raw(data_type, &Json::parse(text).unwrap()) {
+            Err(TypeError::JsonError(error)) => error,
+            other => panic!("expected a JSON error for {}, got {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(from_hex("0aFf"), Some(vec![0x0a, 0xff]));
+        assert_eq!(from_hex("+f"), None);
+        assert_eq!(from_hex("-1"), None);
+        assert_eq!(from_hex("0"), None);
+        assert_eq!(from_hex("zz"), None);
+        let bytes = Primitive::Bytes(1).to_rc();
+        assert!(matches!(import_error(&bytes, "\"+f\""), JsonError::Mismatch { .. }));
+    }
+
+    #[test]
+    fn test_integers() {
+        let integer = Primitive::Integer.to_rc();
+        for (text, value) in [("1.0", 1i64), ("-1.50e1", -15), ("1e3", 1000), ("0.0", 0), ("-0", 0), ("120e-1", 12)] {
+            assert_eq!(json_to_raw(&integer, &Json::parse(text).unwrap()).unwrap(), value.to_be_bytes(), "{}", text);
+        }
+        for text in ["1.5", "1e-1", "1e400", "9223372036854775808", "\"1\""] {
+            assert!(matches!(import_error(&integer, text), JsonError::Mismatch { .. }), "{}", text);
+        }
+        let byte = Primitive::Byte.to_rc();
+        assert_eq!(json_to_raw(&byte, &Json::parse("2.55e2").unwrap()).unwrap(), vec![255]);
+        assert!(matches!(import_error(&byte, "256"), JsonError::Mismatch { .. }));
+        assert!(matches!(import_error(&byte, "-1"), JsonError::Mismatch { .. }));
+    }
+
+    #[test]
+    fn test_pointers() {
+        let point = create_struct("Point", LabelBank::from(&["x", "tags"]), &[
+            Primitive::Integer.to_rc(),
+            Primitive::list(Primitive::Boolean.to_rc(), 2).to_rc(),
+        ]) as Type;
+        assert!(matches!(import_error(&point, r#"{"x": 1, "tags": [true, 3]}"#), JsonError::Mismatch { pointer, .. } if pointer == "/tags/1"));
+        assert!(matches!(import_error(&point, r#"{"x": 1}"#), JsonError::MissingKey { key, .. } if key == "tags"));
+        assert!(matches!(import_error(&point, r#"{"x": 1, "tags": [true, true], "y": 2}"#), JsonError::UnknownKey { key, .. } if key == "y"));
+        assert!(matches!(import_error(&point, r#"{"x": 1, "tags": [true]}"#), JsonError::Mismatch { pointer, .. } if pointer == "/tags"));
+
+        let raw = json_to_raw(&point, &Json::parse(r#"{"x": -7, "tags": [true, false]}"#).unwrap()).unwrap();
+        assert_eq!(raw_to_json(&point, &raw).unwrap().to_string(), r#"{"x":-7,"tags":[true,false]}"#);
+    }
+}
@@ -0,0 +1,286 @@
+//! A strict parser of JSON text into a [Json] document.
+
+use crate::json::document::Json;
+use crate::value::error::{JsonError, TypeResult};
+
+/// The maximum nesting depth of arrays and objects.
+pub const MAX_DEPTH: usize = 128;
+
+/// Parses a JSON text.
+pub fn parse(text: &str) -> TypeResult<Json> {
+    let mut parser = Parser { input: text.as_bytes(), position: 0, depth: 0 };
+    parser.skip_whitespace();
+    let document = parser.value()?;
+    parser.skip_whitespace();
+    if parser.position < parser.input.len() {
+        return Err(parser.error("unexpected trailing characters"));
+    }
+    Ok(document)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> crate::value::error::TypeError {
+        JsonError::Syntax { position: self.position, message: message.to_string() }.promote()
+    }
+
+    fn peek(&self) -> Option<u8> { self.input.get(self.position).copied() }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> TypeResult<()> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> TypeResult<Json> {
+        if self.input[self.position..].starts_with(word.as_bytes()) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("invalid literal"))
+        }
+    }
+
+    fn value(&mut self) -> TypeResult<Json> {
+        match self.peek() {
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Boolean(true)),
+            Some(b'f') => self.literal("false", Json::Boolean(false)),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'{') => self.nested(Self::object),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested(&mut self, parse: fn(&mut Self) -> TypeResult<Json>) -> TypeResult<Json> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("too deeply nested"));
+        }
+        self.depth += 1;
+        let value = parse(self)?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn array(&mut self) -> TypeResult<Json> {
+        self.expect(b'[')?;
+        let mut elements = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(elements));
+        }
+        loop {
+            self.skip_whitespace();
+            elements.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(elements));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> TypeResult<Json> {
+        self.expect(b'{')?;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            self.skip_whitespace();
+            members.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.position;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn number(&mut self) -> TypeResult<Json> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        let leading_zero = self.peek() == Some(b'0');
+        match self.digits() {
+            0 => return Err(self.error("expected digits")),
+            n if n > 1 && leading_zero => return Err(self.error("leading zero")),
+            _ => {}
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            if self.digits() == 0 {
+                return Err(self.error("expected fraction digits"));
+            }
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.position += 1;
+            }
+            if self.digits() == 0 {
+                return Err(self.error("expected exponent digits"));
+            }
+        }
+        let text = std::str::from_utf8(&self.input[start..self.position]).unwrap();
+        Ok(Json::Number(text.to_string()))
+    }
+
+    fn hex4(&mut self) -> TypeResult<u32> {
+        let digits = self.input.get(self.position..self.position + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+        self.position += 4;
+        Ok(digits)
+    }
+
+    fn string(&mut self) -> TypeResult<String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some(b'"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = self.peek().ok_or_else(|| self.error("unterminated escape"))?;
+                    self.position += 1;
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&code) {
+                                if !self.input[self.position..].starts_with(b"\\u") {
+                                    return Err(self.error("unpaired surrogate"));
+                                }
+                                self.position += 2;
+                                let low = self.hex4()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(self.error("unpaired surrogate"));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) if byte < 0x20 => return Err(self.error("control character in string")),
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.position += 1;
+                }
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::json::document::Json;
+    use crate::json::parser::{parse, MAX_DEPTH};
+    use crate::value::error::{JsonError, TypeError};
+
+    fn syntax_error(text: &str) -> (usize, String) {
+        match parse(text) {
+            Err(TypeError::JsonError(JsonError::Syntax { position, message })) => (position, message),
+            other => panic!("expected a syntax error for {:?}, got {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn test_depth() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(syntax_error(&nested(MAX_DEPTH + 1)), (MAX_DEPTH, "too deeply nested".to_string()));
+        assert_eq!(syntax_error(&"{\"a\":".repeat(MAX_DEPTH + 1)).1, "too deeply nested");
+    }
+
+    #[test]
+    fn test_strict_numbers() {
+        assert_eq!(parse("-0.5e+3").unwrap(), Json::Number("-0.5e+3".to_string()));
+        assert_eq!(syntax_error("01").1, "leading zero");
+        assert_eq!(syntax_error("1.").1, "expected fraction digits");
+        assert_eq!(syntax_error("1e").1, "expected exponent digits");
+        assert_eq!(syntax_error("-").1, "expected digits");
+        assert_eq!(syntax_error("+1").1, "unexpected character");
+        assert_eq!(syntax_error(".5").1, "unexpected character");
+    }
+
+    #[test]
+    fn test_strict_strings() {
+        assert_eq!(parse(r#""😀 é""#).unwrap(), Json::string("😀 é"));
+        assert_eq!(syntax_error(r#""\ud83d""#).1, "unpaired surrogate");
+        assert_eq!(syntax_error(r#""\ud83dA""#).1, "unpaired surrogate");
+        assert_eq!(syntax_error(r#""\x""#).1, "invalid escape");
+        assert_eq!(syntax_error(r#""\u00g0""#).1, "invalid unicode escape");
+        assert_eq!(syntax_error("\"a\tb\"").1, "control character in string");
+        assert_eq!(syntax_error("\"open").1, "unterminated string");
+    }
+
+    #[test]
+    fn test_strict_structure() {
+        assert_eq!(syntax_error("[1,]").1, "unexpected character");
+        assert_eq!(syntax_error("{\"a\":1,}").1, "expected '\"'");
+        assert_eq!(syntax_error("{'a':1}").1, "expected '\"'");
+        assert_eq!(syntax_error("[1 2]").1, "expected ',' or ']'");
+        assert_eq!(syntax_error("nul").1, "invalid literal");
+        assert_eq!(syntax_error("true false"), (5, "unexpected trailing characters".to_string()));
+        assert_eq!(syntax_error("").1, "unexpected end of input");
+    }
+}
@@ -0,0 +1,334 @@
+//! Generation of JSON Schema (draft 2020-12) documents from types.
+//!
+//! A schema describes exactly the documents of the [crate::json::mapping] of the values of a type :
+//! - [Structure] and [Enumeration] are defined once in `$defs`, keyed by their identifier,
+//!   two different definitions with the same identifier are a [JsonError::Conflict],
+//! - records have `required` properties for each field key, skipped fields are left out,
+//! - sums are a `oneOf` of single-member objects keyed by variant,
+//! - the [Annotations] of definitions, fields and variants become `description`, `deprecated`
+//!   and `x-<attribute>` keywords,
+//! - lists, tuples and products are fixed-length arrays.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::json::schema::json_schema;
+//! use roussillon_type_system::types::primitive::Primitive;
+//!
+//! let list = Primitive::list(Primitive::Boolean.to_rc(), 4).to_rc();
+//! assert_eq!(
+//!     json_schema(&list).unwrap().to_string(),
+//!     r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","type":"array","items":{"type":"boolean"},"minItems":4,"maxItems":4}"#
+//! );
+//! ```
+
+use crate::annotation::Annotations;
+use crate::identity::{Identified, LabelBank};
+use crate::json::document::Json;
+use crate::json::mapping::{field_keys, variant_keys, Pointer, FLOAT_SPECIALS};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::primitive::Primitive;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::convert::same_definition;
+use crate::value::error::{JsonError, TypeResult};
+
+/// The URI of the JSON Schema dialect of the generated documents.
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Returns the JSON Schema document of the values of `data_type`.
+pub fn json_schema(data_type: &Type) -> TypeResult<Json> {
+    let mut definitions = Vec::new();
+    let root = schema(data_type, &mut definitions, &mut Pointer::root())?;
+    let mut document = vec![("$schema".to_string(), Json::string(SCHEMA_DIALECT))];
+    if let Json::Object(members) = root {
+        document.extend(members);
+    }
+    if !definitions.is_empty() {
+        document.push(("$defs".to_string(), Json::Object(definitions.into_iter().map(|(name, _, definition)| (name, definition)).collect())));
+    }
+    Ok(Json::Object(document))
+}
+
+fn definition_ref(name: &str) -> Json {
+    Json::object(&[("$ref", Json::String(format!("#/$defs/{}", name.replace('~', "~0").replace('/', "~1"))))])
+}
+
+/// Adds the `description`, `deprecated` and `x-<attribute>` keywords of `annotations` to an object schema.
+fn annotate(schema: Json, annotations: Option<&Annotations>) -> Json {
+    match (schema, annotations) {
+        (Json::Object(mut members), Some(annotations)) => {
+            if let Some(doc) = &annotations.doc {
+                members.push(("description".to_string(), Json::string(doc)));
+            }
+            if annotations.is_deprecated() {
+                members.push(("deprecated".to_string(), Json::Boolean(true)));
+            }
+            for (key, value) in annotations.attributes() {
+                members.push((format!("x-{}", key), Json::string(value)));
+            }
+            Json::Object(members)
+        }
+        (schema, _) => schema,
+    }
+}
+
+/// The `$defs` of a document, with the types they define.
+type Definitions = Vec<(String, Type, Json)>;
+
+/// Defines `data_type` in `$defs` under `name` once, or fails if another type is already defined under it.
+fn define(name: String, data_type: &Type, definitions: &mut Definitions, pointer: &mut Pointer, build: impl FnOnce(&mut Definitions, &mut Pointer) -> TypeResult<Json>) -> TypeResult<Json> {
+    match definitions.iter().find(|(defined, _, _)| *defined == name) {
+        Some((_, defined, _)) if !same_definition(defined, data_type) => {
+            return Err(JsonError::Conflict { pointer: pointer.to_string(), name }.promote());
+        }
+        Some(_) => {}
+        None => {
+            let index = definitions.len();
+            definitions.push((name.clone(), data_type.clone(), Json::Null));
+            let definition = build(definitions, pointer)?;
+            definitions[index].2 = definition;
+        }
+    }
+    Ok(definition_ref(&name))
+}
+
+fn schema(data_type: &Type, definitions: &mut Definitions, pointer: &mut Pointer) -> TypeResult<Json> {
+    if let Some(primitive) = data_type.downcast_ref::<Primitive>() {
+        return Ok(match primitive {
+            Primitive::Boolean => Json::object(&[("type", Json::string("boolean"))]),
+            Primitive::Byte => Json::object(&[
+                ("type", Json::string("integer")),
+                ("minimum", Json::integer(0)),
+                ("maximum", Json::integer(u8::MAX as i64)),
+            ]),
+            Primitive::Bytes(size) => Json::object(&[
+                ("type", Json::string("string")),
+                ("pattern", Json::string("^[0-9a-fA-F]*$")),
+                ("minLength", Json::unsigned(2 * *size as u64)),
+                ("maxLength", Json::unsigned(2 * *size as u64)),
+            ]),
+            Primitive::Float => Json::object(&[
+                ("oneOf", Json::Array(vec![
+                    Json::object(&[("type", Json::string("number"))]),
+                    Json::object(&[("enum", Json::Array(FLOAT_SPECIALS.iter().map(|special| Json::string(special)).collect()))]),
+                ])),
+            ]),
+            Primitive::Integer => Json::object(&[
+                ("type", Json::string("integer")),
+                ("minimum", Json::integer(i64::MIN)),
+                ("maximum", Json::integer(i64::MAX)),
+            ]),
+            Primitive::Reference(_) => Json::object(&[
+                ("type", Json::string("integer")),
+                ("minimum", Json::integer(0)),
+                ("maximum", Json::unsigned(u64::MAX)),
+            ]),
+            Primitive::List((of_type, len)) => {
+                pointer.push("items");
+                let items = schema(of_type, definitions, pointer)?;
+                pointer.pop();
+                Json::object(&[
+                    ("type", Json::string("array")),
+                    ("items", items),
+                    ("minItems", Json::unsigned(*len as u64)),
+                    ("maxItems", Json::unsigned(*len as u64)),
+                ])
+            }
+        });
+    }
+    if let Some(tuple) = data_type.downcast_ref::<Tuple>() {
+        return tuple_schema(tuple, definitions, pointer);
+    }
+    if let Some(product) = data_type.downcast_ref::<ProductType>() {
+        return tuple_schema(&product.to_tuple(), definitions, pointer);
+    }
+    if let Some(sum) = data_type.downcast_ref::<SumType>() {
+        return sum_schema(sum, None, definitions, pointer);
+    }
+    if let Some(structure) = data_type.downcast_ref::<Structure>() {
+        return define(structure.identifier().to_string(), data_type, definitions, pointer, |definitions, pointer| {
+            let mut properties = Vec::new();
+            for (index, (key, field)) in field_keys(structure).into_iter().zip(structure.product_type.to_tuple()).enumerate() {
+                let Some(key) = key else { continue };
+                pointer.push(&key);
+                let property = schema(&field, definitions, pointer)?;
+                pointer.pop();
+                properties.push((key, annotate(property, structure.labels.annotations(index))));
+            }
+            let required = properties.iter().map(|(key, _)| Json::string(key)).collect();
+            Ok(annotate(Json::object(&[
+                ("title", Json::String(structure.identifier().to_string())),
+                ("type", Json::string("object")),
+                ("properties", Json::Object(properties)),
+                ("required", Json::Array(required)),
+                ("additionalProperties", Json::Boolean(false)),
+            ]), Some(&structure.annotations)))
+        });
+    }
+    if let Some(enumeration) = data_type.downcast_ref::<Enumeration>() {
+        return define(enumeration.identifier().to_string(), data_type, definitions, pointer, |definitions, pointer| {
+            let mut members = vec![("title".to_string(), Json::String(enumeration.identifier().to_string()))];
+            if let Json::Object(variants) = sum_schema(&enumeration.sum_type, Some(enumeration.labels()), definitions, pointer)? {
+                members.extend(variants);
+            }
+            Ok(annotate(Json::Object(members), Some(&enumeration.annotations)))
+        });
+    }
+    Err(JsonError::Unsupported { pointer: pointer.to_string(), data_type: data_type.clone() }.promote())
+}
+
+fn tuple_schema(members: &Tuple, definitions: &mut Definitions, pointer: &mut Pointer) -> TypeResult<Json> {
+    let mut items = Vec::with_capacity(members.len());
+    for (index, member) in members.iter().enumerate() {
+        pointer.push(&index.to_string());
+        items.push(schema(member, definitions, pointer)?);
+        pointer.pop();
+    }
+    Ok(Json::object(&[
+        ("type", Json::string("array")),
+        ("prefixItems", Json::Array(items)),
+        ("items", Json::Boolean(false)),
+        ("minItems", Json::unsigned(members.len() as u64)),
+        ("maxItems", Json::unsigned(members.len() as u64)),
+    ]))
+}
+
+fn sum_schema(sum: &SumType, labels: Option<&LabelBank>, definitions: &mut Definitions, pointer: &mut Pointer) -> TypeResult<Json> {
+    if sum.is_empty() {
+        return Ok(Json::object(&[("not", Json::Object(Vec::new()))]));
+    }
+    let keys = variant_keys(labels, sum.len());
+    let mut variants = Vec::with_capacity(keys.len());
+    for (tag, key) in keys.iter().enumerate() {
+        pointer.push(key);
+        let payload = schema(&sum.variant(tag).unwrap(), definitions, pointer)?;
+        pointer.pop();
+        variants.push(annotate(Json::object(&[
+            ("type", Json::string("object")),
+            ("properties", Json::Object(vec![(key.clone(), payload)])),
+            ("required", Json::Array(vec![Json::string(key)])),
+            ("additionalProperties", Json::Boolean(false)),
+        ]), labels.and_then(|labels| labels.annotations(tag))));
+    }
+    Ok(Json::object(&[("oneOf", Json::Array(variants))]))
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use crate::facade::{create_enum, create_struct};
+    use crate::identity::LabelBank;
+    use crate::json::document::Json;
+    use crate::json::mapping::json_to_raw;
+    use crate::json::schema::json_schema;
+    use crate::types::algebraic::ProductType;
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::value::error::{JsonError, TypeError};
+
+    /// Validates `document` against the keywords the generated schemas use.
+    fn validates(schema: &Json, document: &Json, root: &Json) -> bool {
+        if let Some(Json::String(reference)) = schema.get("$ref") {
+            let name = reference.trim_start_matches("#/$defs/").replace("~1", "/").replace("~0", "~");
+            return validates(root.get("$defs").unwrap().get(&name).unwrap(), document, root);
+        }
+        let Json::Object(keywords) = schema else { return schema == &Json::Boolean(true) };
+        keywords.iter().all(|(keyword, value)| match keyword.as_str() {
+            "type" => match (value.as_str().unwrap(), document) {
+                ("boolean", Json::Boolean(_)) | ("number", Json::Number(_)) | ("string", Json::String(_))
+                | ("array", Json::Array(_)) | ("object", Json::Object(_)) => true,
+                ("integer", Json::Number(text)) => text.parse::<f64>().is_ok_and(|number| number.is_finite() && number.fract() == 0.0),
+                _ => false,
+            },
+            "minimum" => compare(document, value).is_none_or(Ordering::is_ge),
+            "maximum" => compare(document, value).is_none_or(Ordering::is_le),
+            "pattern" => document.as_str().is_none_or(|text| text.bytes().all(|byte| byte.is_ascii_hexdigit())),
+            "minLength" => document.as_str().is_none_or(|text| text.chars().count() as u64 >= value.as_u64().unwrap()),
+            "maxLength" => document.as_str().is_none_or(|text| text.chars().count() as u64 <= value.as_u64().unwrap()),
+            "minItems" => document.as_array().is_none_or(|items| items.len() as u64 >= value.as_u64().unwrap()),
+            "maxItems" => document.as_array().is_none_or(|items| items.len() as u64 <= value.as_u64().unwrap()),
+            "prefixItems" => document.as_array().is_none_or(|items| items.iter().zip(value.as_array().unwrap()).all(|(item, schema)| validates(schema, item, root))),
+            "items" => document.as_array().is_none_or(|items| {
+                let skipped = schema.get("prefixItems").and_then(Json::as_array).map_or(0, <[Json]>::len);
+                items.iter().skip(skipped).all(|item| validates(value, item, root))
+            }),
+            "properties" => document.as_object().is_none_or(|members| members.iter().all(|(key, member)| value.get(key).is_none_or(|schema| validates(schema, member, root)))),
+            "required" => document.as_object().is_none_or(|_| value.as_array().unwrap().iter().all(|key| document.get(key.as_str().unwrap()).is_some())),
+            "additionalProperties" => document.as_object().is_none_or(|members| members.iter().all(|(key, _)| schema.get("properties").unwrap().get(key).is_some())),
+            "oneOf" => value.as_array().unwrap().iter().filter(|schema| validates(schema, document, root)).count() == 1,
+            "enum" => value.as_array().unwrap().contains(document),
+            "not" => !validates(value, document, root),
+            _ => true,
+        })
+    }
+
+    /// Compares a number to a bound, exactly if both are written as integers.
+    fn compare(number: &Json, bound: &Json) -> Option<Ordering> {
+        let (Json::Number(number), Json::Number(bound)) = (number, bound) else { return None };
+        match (number.parse::<i128>(), bound.parse::<i128>()) {
+            (Ok(number), Ok(bound)) => Some(number.cmp(&bound)),
+            _ => number.parse::<f64>().ok()?.partial_cmp(&bound.parse::<f64>().ok()?),
+        }
+    }
+
+    fn assert_same_documents(data_type: &Type, documents: &[&str]) {
+        let schema = json_schema(data_type).unwrap();
+        for text in documents {
+            let document = Json::parse(text).unwrap();
+            assert_eq!(validates(&schema, &document, &schema), json_to_raw(data_type, &document).is_ok(), "{} as {}", text, data_type.typename());
+        }
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_same_documents(&Primitive::Integer.to_rc(), &["1", "1.0", "-1.5e1", "1.5", "-1e2", "1e400", "9223372036854775807", "9223372036854775808", "-9223372036854775808", "-9223372036854775809", "\"1\"", "true"]);
+        assert_same_documents(&Primitive::Byte.to_rc(), &["0", "255", "256", "2.55e2", "0.0", "-1", "1.5"]);
+        assert_same_documents(&Primitive::Bytes(2).to_rc(), &["\"0aFF\"", "\"+f0f\"", "\"0a\"", "\"zzzz\"", "\"0a0a0a\"", "10"]);
+        assert_same_documents(&Primitive::Float.to_rc(), &["1.5", "1", "\"NaN\"", "\"-Infinity\"", "\"nan\"", "null"]);
+        assert_same_documents(&Primitive::Boolean.to_rc(), &["true", "0", "null"]);
+        assert_same_documents(&Primitive::Reference(Primitive::Integer.to_rc()).to_rc(), &["0", "18446744073709551615", "18446744073709551616", "-1", "2.0"]);
+    }
+
+    #[test]
+    fn test_composites() {
+        let point = create_struct("Geometry/Point", LabelBank::from(&["x", "flags"]), &[
+            Primitive::Integer.to_rc(),
+            Primitive::list(Primitive::Boolean.to_rc(), 2).to_rc(),
+        ]);
+        assert_same_documents(&(point.clone() as Type), &[
+            r#"{"x": 1, "flags": [true, false]}"#,
+            r#"{"flags": [true, false], "x": 1.0}"#,
+            r#"{"x": 1}"#,
+            r#"{"x": 1, "flags": [true, false], "y": 2}"#,
+            r#"{"x": 1, "flags": [true]}"#,
+            r#"[1, [true, false]]"#,
+        ]);
+        let shape = create_enum("Geometry/Shape", LabelBank::from(&["Empty", "Dot"]), &[ProductType::unit_type().to_rc(), point]);
+        assert_same_documents(&(shape as Type), &[
+            r#"{"Empty": []}"#,
+            r#"{"Dot": {"x": 1, "flags": [true, true]}}"#,
+            r#"{"Empty": [], "Dot": {"x": 1, "flags": [true, true]}}"#,
+            r#"{"Line": []}"#,
+            r#"{}"#,
+            r#"{"Empty": [1]}"#,
+        ]);
+        let pair: Type = std::rc::Rc::new(vec![Primitive::Integer.to_rc(), Primitive::Boolean.to_rc()]);
+        assert_same_documents(&pair, &["[1, true]", "[1]", "[1, true, 2]", "[true, 1]"]);
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let point = |fields: &[&str]| -> Type { create_struct("Geometry/Point", LabelBank::from(fields), &[Primitive::Integer.to_rc(), Primitive::Integer.to_rc()]) };
+        let same: Type = std::rc::Rc::new(vec![point(&["x", "y"]), point(&["x", "y"])]);
+        let Some(Json::Object(definitions)) = json_schema(&same).unwrap().get("$defs").cloned() else { panic!("the points are defined") };
+        assert_eq!(definitions.len(), 1);
+        let different: Type = std::rc::Rc::new(vec![point(&["x", "y"]), point(&["x", "z"])]);
+        let Err(TypeError::JsonError(JsonError::Conflict { pointer, name })) = json_schema(&different) else {
+            panic!("two different points cannot be defined under the same identifier");
+        };
+        assert_eq!((pointer.as_str(), name.as_str()), ("/1", "Geometry/Point"));
+    }
+}
@@ -52,12 +52,34 @@
 //! - [types::dynamic::AnyType], [types::dynamic::Dynamic] and [value::value_type::ValueType],
 //! - [types::interface::Interface].
 //!
+//! ## Macros
+//!
+//! - [data_type!] writes types, [structure!] and [enumeration!] define named types,
+//! - [json!] writes JSON documents, and [record!] builds records from them.
+//! - With the `derive` feature, `#[derive(RoussillonType)]` maps Rust structs and enums to [types::typedef::Structure]s and [types::typedef::Enumeration]s,
+//!   with the conversions of [value::convert].
+//!
+//! ## Tooling
+//!
+//! - [annotation] attaches docs, deprecations, serialization hints and attributes to types, fields, variants and functions.
+//! - [json] imports and exports values as JSON documents, and describes types as JSON Schemas.
+//! - [import] reads type definitions written in other languages into a [types::registry::Registry].
+//! - [codegen] generates Rust and C declarations with the layout of raw buffers, and TypeScript declarations of the JSON documents, draws diagrams of types and writes their reference documentation.
+//!
 
+pub mod annotation;
 pub mod identity;
 mod parse;
 pub mod types;
 pub mod value;
 pub mod facade;
+pub mod json;
+pub mod import;
+pub mod codegen;
+mod macros;
+
+#[cfg(feature = "derive")]
+pub use roussillon_type_system_derive::RoussillonType;
 
 #[cfg(test)]
 mod test {
@@ -67,7 +89,12 @@ mod test {
     use crate::value::number::{Float, Integer};
     use crate::facade::{copy_value, create_struct};
     use crate::identity::LabelBank;
-    use crate::value::record::Record;
+    use crate::value::record::{ProductValue, Record};
+    use crate::types::algebraic::{ProductType, SumType};
+    use crate::types::concept::{DataType, Type};
+    use crate::value::boolean::Boolean;
+    use crate::value::union::SumValue;
+    use crate::value::validation::validate;
 
     #[test]
     fn test() {
@@ -96,4 +123,25 @@ mod test {
         let copy = copy_value(my_struct.clone(), &object.clone().to_cell()).unwrap();
         assert_eq!(object.data_type().typename(), copy.borrow().data_type().typename());
     }
+
+    #[test]
+    fn test_sum_encoding() {
+        let maybe = SumType::new(&[
+            ProductType::unit_type().to_rc(),
+            Primitive::Integer.to_rc(),
+        ]).to_rc();
+        let holder = create_struct("Holder", LabelBank::from(&["maybe", "flag"]), &[
+            maybe.clone(),
+            Primitive::Boolean.to_rc(),
+        ]);
+
+        let nothing = SumValue::new(maybe.clone(), 0, ProductValue::new(ProductType::unit_type().to_rc(), &[]).unwrap().to_cell()).unwrap();
+        let object = Record::new(holder.clone(), &[nothing.to_cell(), Boolean::create_true().to_cell()]).unwrap();
+        let raw = object.raw();
+        assert_eq!(raw.len(), holder.size());
+        assert!(validate(&(holder.clone() as Type), &raw).is_ok());
+
+        let copy = copy_value(holder, &object.to_cell()).unwrap();
+        assert_eq!(raw, copy.borrow().raw());
+    }
 }
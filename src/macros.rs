@@ -0,0 +1,255 @@
+//! Declarative macros to define types and values.
+//!
+//! - [data_type!](crate::data_type) writes a [crate::types::concept::Type],
+//! - [structure!](crate::structure) and [enumeration!](crate::enumeration) define named types, each label next to its type,
+//! - [json!](crate::json) writes a [crate::json::document::Json] document,
+//! - [record!](crate::record) builds a [crate::value::record::Record] from its [crate::json::mapping].
+
+/// Writes a [crate::types::concept::Type].
+///
+/// | Syntax                                              | Type                                                 |
+/// |-----------------------------------------------------|------------------------------------------------------|
+/// | `boolean`, `byte`, `integer`, `float`               | the [crate::types::primitive::Primitive]s            |
+/// | `arch`, `word`, `quad`, `long`, `wide`, `bytes<N>`  | [crate::types::primitive::Primitive::Bytes]          |
+/// | `&T`                                                | [crate::types::primitive::Primitive::Reference]      |
+/// | `[T; N]`                                            | [crate::types::primitive::Primitive::List]           |
+/// | `()` and `(T, U, ...)`                              | [crate::types::algebraic::ProductType]               |
+/// | `name`                                              | the type in the variable `name`, like a [structure!] |
+///
+/// ```
+/// use roussillon_type_system::data_type;
+///
+/// assert_eq!(data_type!([&integer; 4]).typename(), "[&integer;4]");
+/// assert_eq!(data_type!((boolean, bytes<3>)).typename(), "<boolean&bytes<3>>");
+/// ```
+#[macro_export]
+macro_rules! data_type {
+    (@list ($($of_type:tt)*) ; $len:expr) => {
+        $crate::types::primitive::Primitive::list($crate::data_type!($($of_type)*), $len).to_rc()
+    };
+    (@list ($($of_type:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::data_type!(@list ($($of_type)* $next) $($rest)*)
+    };
+    (@product [$($members:expr,)*] ()) => {
+        ($crate::types::algebraic::ProductType::new(&[$($members),*]).to_rc() as $crate::types::concept::Type)
+    };
+    (@product [$($members:expr,)*] ($($member:tt)+)) => {
+        $crate::data_type!(@product [$($members,)* $crate::data_type!($($member)+),] ())
+    };
+    (@product [$($members:expr,)*] ($($member:tt)+) , $($rest:tt)*) => {
+        $crate::data_type!(@product [$($members,)* $crate::data_type!($($member)+),] () $($rest)*)
+    };
+    (@product [$($members:expr,)*] ($($member:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::data_type!(@product [$($members,)*] ($($member)* $next) $($rest)*)
+    };
+    (boolean) => { $crate::types::primitive::Primitive::Boolean.to_rc() };
+    (byte) => { $crate::types::primitive::Primitive::Byte.to_rc() };
+    (integer) => { $crate::types::primitive::Primitive::Integer.to_rc() };
+    (float) => { $crate::types::primitive::Primitive::Float.to_rc() };
+    (arch) => { $crate::types::primitive::Primitive::Bytes(::std::mem::size_of::<usize>()).to_rc() };
+    (word) => { $crate::types::primitive::Primitive::Bytes(2).to_rc() };
+    (quad) => { $crate::types::primitive::Primitive::Bytes(4).to_rc() };
+    (long) => { $crate::types::primitive::Primitive::Bytes(8).to_rc() };
+    (wide) => { $crate::types::primitive::Primitive::Bytes(16).to_rc() };
+    (bytes < $size:literal >) => { $crate::types::primitive::Primitive::Bytes($size).to_rc() };
+    (& $($to_type:tt)+) => {
+        $crate::types::primitive::Primitive::Reference($crate::data_type!($($to_type)+)).to_rc()
+    };
+    ([$($list:tt)+]) => { $crate::data_type!(@list () $($list)+) };
+    (($($members:tt)*)) => { $crate::data_type!(@product [] () $($members)*) };
+    ($name:ident) => { ($name.clone() as $crate::types::concept::Type) };
+}
+
+/// Pairs labels with [data_type!]s, as `(&[labels], &[types])`.
+///
+/// The labels are checked at compile time : a label written twice does not compile.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __members {
+    (@next [$($labels:expr,)*] [$($types:expr,)*]) => {{
+        const LABELS: &[&str] = &[$($labels),*];
+        const _: () = assert!($crate::identity::distinct_labels(LABELS), "a label is written twice");
+        (LABELS, &[$($types),*])
+    }};
+    (@next [$($labels:expr,)*] [$($types:expr,)*] $label:ident : $($rest:tt)*) => {
+        $crate::__members!(@type [$($labels,)* stringify!($label),] [$($types,)*] () $($rest)*)
+    };
+    (@type [$($labels:expr,)*] [$($types:expr,)*] ($($member:tt)+)) => {
+        $crate::__members!(@next [$($labels,)*] [$($types,)* $crate::data_type!($($member)+),])
+    };
+    (@type [$($labels:expr,)*] [$($types:expr,)*] ($($member:tt)+) , $($rest:tt)*) => {
+        $crate::__members!(@next [$($labels,)*] [$($types,)* $crate::data_type!($($member)+),] $($rest)*)
+    };
+    (@type [$($labels:expr,)*] [$($types:expr,)*] ($($member:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::__members!(@type [$($labels,)*] [$($types,)*] ($($member)* $next) $($rest)*)
+    };
+    ($($members:tt)*) => { $crate::__members!(@next [] [] $($members)*) };
+}
+
+/// Defines a [crate::types::typedef::Structure], each field label next to its [data_type!].
+///
+/// A label written twice is a compile error.
+///
+/// ```
+/// use roussillon_type_system::structure;
+/// use roussillon_type_system::identity::Identified;
+/// use roussillon_type_system::types::concept::DataType;
+///
+/// let point = structure! { Geometry/Point { x: float, y: float } };
+/// let shape = structure! { Geometry/Shape { vertices: [point; 4], closed: boolean } };
+///
+/// assert_eq!(shape.identifier().to_string(), "Geometry/Shape");
+/// assert_eq!(shape.size(), 65);
+/// ```
+///
+/// ```compile_fail
+/// use roussillon_type_system::structure;
+///
+/// let point = structure! { Geometry/Point { x: float, x: float } };
+/// ```
+#[macro_export]
+macro_rules! structure {
+    ($space:ident / $name:ident { $($fields:tt)* }) => {
+        $crate::structure!(@define concat!(stringify!($space), "/", stringify!($name)), $($fields)*)
+    };
+    ($name:ident { $($fields:tt)* }) => {
+        $crate::structure!(@define stringify!($name), $($fields)*)
+    };
+    (@define $identifier:expr, $($fields:tt)*) => {{
+        let (labels, types): (&[&str], &[$crate::types::concept::Type]) = $crate::__members!($($fields)*);
+        $crate::facade::create_struct($identifier, $crate::identity::LabelBank::from(labels), types)
+    }};
+}
+
+/// Defines a [crate::types::typedef::Enumeration], each variant label next to its [data_type!].
+///
+/// A label written twice is a compile error.
+///
+/// ```
+/// use roussillon_type_system::enumeration;
+/// use roussillon_type_system::types::concept::DataType;
+///
+/// let option = enumeration! { Option { None: (), Some: integer } };
+///
+/// assert_eq!(option.variant(1).unwrap().typename(), "integer");
+/// assert_eq!(option.size(), 16);
+/// ```
+///
+/// ```compile_fail
+/// use roussillon_type_system::enumeration;
+///
+/// let option = enumeration! { Option { None: (), None: integer } };
+/// ```
+#[macro_export]
+macro_rules! enumeration {
+    ($space:ident / $name:ident { $($variants:tt)* }) => {
+        $crate::enumeration!(@define concat!(stringify!($space), "/", stringify!($name)), $($variants)*)
+    };
+    ($name:ident { $($variants:tt)* }) => {
+        $crate::enumeration!(@define stringify!($name), $($variants)*)
+    };
+    (@define $identifier:expr, $($variants:tt)*) => {{
+        let (labels, types): (&[&str], &[$crate::types::concept::Type]) = $crate::__members!($($variants)*);
+        $crate::facade::create_enum($identifier, $crate::identity::LabelBank::from(labels), types)
+    }};
+}
+
+/// Writes a [crate::json::document::Json] document.
+///
+/// Object keys are identifiers or string literals, and scalars are Rust literals converted with the `From` impls
+/// of [crate::json::document::Json] : an integer without suffix is an `i32`, and a float without suffix is an `f64`.
+///
+/// ```
+/// use roussillon_type_system::json;
+///
+/// let document = json!({ name: "origin", "0": [1_000, -2.5, true, null], size: 5_000_000_000u64, letter: '\u{41}' });
+/// assert_eq!(document.to_string(), r#"{"name":"origin","0":[1000,-2.5,true,null],"size":5000000000,"letter":"A"}"#);
+/// ```
+#[macro_export]
+macro_rules! json {
+    (@elements [$($elements:expr,)*] ()) => { ::std::vec![$($elements),*] };
+    (@elements [$($elements:expr,)*] ($($element:tt)+)) => {
+        $crate::json!(@elements [$($elements,)* $crate::json!($($element)+),] ())
+    };
+    (@elements [$($elements:expr,)*] ($($element:tt)+) , $($rest:tt)*) => {
+        $crate::json!(@elements [$($elements,)* $crate::json!($($element)+),] () $($rest)*)
+    };
+    (@elements [$($elements:expr,)*] ($($element:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::json!(@elements [$($elements,)*] ($($element)* $next) $($rest)*)
+    };
+    (@members [$($members:expr,)*]) => { ::std::vec![$($members),*] };
+    (@members [$($members:expr,)*] $key:ident : $($rest:tt)*) => {
+        $crate::json!(@member [$($members,)*] (stringify!($key)) () $($rest)*)
+    };
+    (@members [$($members:expr,)*] $key:literal : $($rest:tt)*) => {
+        $crate::json!(@member [$($members,)*] ($key) () $($rest)*)
+    };
+    (@member [$($members:expr,)*] ($key:expr) ($($value:tt)+)) => {
+        $crate::json!(@members [$($members,)* ($key.to_string(), $crate::json!($($value)+)),])
+    };
+    (@member [$($members:expr,)*] ($key:expr) ($($value:tt)+) , $($rest:tt)*) => {
+        $crate::json!(@members [$($members,)* ($key.to_string(), $crate::json!($($value)+)),] $($rest)*)
+    };
+    (@member [$($members:expr,)*] ($key:expr) ($($value:tt)*) $next:tt $($rest:tt)*) => {
+        $crate::json!(@member [$($members,)*] ($key) ($($value)* $next) $($rest)*)
+    };
+    (null) => { $crate::json::document::Json::Null };
+    (true) => { $crate::json::document::Json::Boolean(true) };
+    (false) => { $crate::json::document::Json::Boolean(false) };
+    ([$($elements:tt)*]) => { $crate::json::document::Json::Array($crate::json!(@elements [] () $($elements)*)) };
+    ({$($members:tt)*}) => { $crate::json::document::Json::Object($crate::json!(@members [] $($members)*)) };
+    (- $number:literal) => { $crate::json::document::Json::from(-$number) };
+    ($scalar:literal) => { $crate::json::document::Json::from($scalar) };
+}
+
+/// Builds a [crate::value::record::Record] of the structure in a variable, from its [json!] document.
+///
+/// Returns a [crate::value::error::TypeResult], with the errors of [crate::json::mapping::json_to_raw]
+/// for missing, unknown or mistyped fields.
+///
+/// ```
+/// use roussillon_type_system::{record, structure};
+/// use roussillon_type_system::value::concept::DataValue;
+///
+/// let point = structure! { Point { x: integer, tags: [byte; 2] } };
+///
+/// let origin = record!(point { x: 0, tags: [1, 2] }).unwrap();
+/// assert_eq!(origin.raw(), [0, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+/// assert!(record!(point { x: 0 }).is_err());
+/// ```
+#[macro_export]
+macro_rules! record {
+    ($structure:ident { $($fields:tt)* }) => {{
+        let structure: &::std::rc::Rc<$crate::types::typedef::Structure> = &$structure;
+        $crate::json::mapping::json_to_raw(&(structure.clone() as $crate::types::concept::Type), &$crate::json!({ $($fields)* }))
+            .and_then(|raw| $crate::value::record::Record::from(structure.clone(), &raw))
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::json::document::Json;
+
+    #[test]
+    fn test_json_scalars() {
+        assert_eq!(json!(1_000), Json::integer(1000));
+        assert_eq!(json!(5u8), Json::integer(5));
+        assert_eq!(json!(-5i8), Json::integer(-5));
+        assert_eq!(json!(18_446_744_073_709_551_615u64).as_u64(), Some(u64::MAX));
+        assert_eq!(json!(-2.5).to_string(), "-2.5");
+        assert_eq!(json!(0.1f32).to_string(), "0.1");
+        assert_eq!(json!(1e300).as_f64(), Some(1e300));
+        assert_eq!(json!("\u{41}\n"), Json::string("A\n"));
+        assert_eq!(json!('\u{e9}'), Json::string("\u{e9}"));
+        assert_eq!(json!(r"C:\"), Json::string("C:\\"));
+        assert_eq!(json!([true, null, { "a b": [] }]).to_string(), r#"[true,null,{"a b":[]}]"#);
+    }
+
+    #[test]
+    fn test_labels() {
+        let point = structure! { Point { x: integer, y: integer } };
+        assert_eq!(point.labels.label(1).unwrap().as_str(), "y");
+        let bit = enumeration! { Bit { Zero: (), One: () } };
+        assert_eq!(bit.variant_index("One").unwrap(), 1);
+    }
+}
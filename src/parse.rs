@@ -1,7 +1,7 @@
 
 pub type Parsed<'a, T> = (Option<T>, &'a [u8]);
 
-pub fn parse_slice(input: &[u8], len: usize) -> Parsed<&[u8]> {
+pub fn parse_slice(input: &[u8], len: usize) -> Parsed<'_, &[u8]> {
     if input.len() < len {
         (None, input)
     } else {
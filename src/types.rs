@@ -16,6 +16,8 @@
 //! - [typedef::Enumeration]
 //! - [functional::FunctionType]
 //!
+//! [kind::TypeKind] is a view of any type, with its components, walked and rewritten with [visit].
+//!
 //! ## Example
 //!
 //! Create a "MyStruct" [typedef::Structure] with 3 fields :
@@ -42,12 +44,16 @@
 //!         space: "",
 //!         name: "MyStruct"
 //!     }, 
-//!     labels: LabelBank({
-//!         Label("field_a"): 0,
-//!         Label("field_b"): 1,
-//!         Label("field_c"): 2}
-//!     ),
-//!     product_type: ProductType([<integer>, <integer>, <float>])
+//!     labels: LabelBank {
+//!         indexes: {
+//!             Label("field_a"): 0,
+//!             Label("field_b"): 1,
+//!             Label("field_c"): 2
+//!         },
+//!         annotations: {}
+//!     },
+//!     product_type: ProductType([<integer>, <integer>, <float>]),
+//!     annotations: Annotations { doc: None, deprecated: None, rename: None, skip: false, attributes: [] }
 //! }
 //! ```
 //!
@@ -58,6 +64,9 @@ pub mod sequence;
 pub mod algebraic;
 pub mod typedef;
 pub mod functional;
+pub mod registry;
+pub mod kind;
+pub mod visit;
 #[cfg(feature = "experiments")]
 pub mod dynamic;
 #[cfg(feature = "experiments")]
@@ -1,6 +1,7 @@
 //! This module provides ways to manage Algebraic Data Types.
 //!
 //! - The [SumType] struct describes a type which has a value of one of its variant.
+//!   Its values are encoded with a [TAG_SIZE] bytes tag followed by the payload, padded to the largest variant.
 //! - The [ProductType] struct describes a type which has a value for each one of its composing type.
 
 use std::cmp::max;
@@ -11,9 +12,22 @@ use crate::types::concept::{DataType, Type};
 
 use crate::types::sequence;
 use crate::types::sequence::Tuple;
+use crate::value::canonical::CanonicalForm;
 use crate::value::concept::ValueCell;
+use crate::value::decode::Decoder;
 use crate::value::error::TypeResult;
+use crate::value::path::Segment;
+use crate::value::record::ProductValue;
 use crate::value::union::SumValue;
+use crate::value::validation::{Validator, ViolationKind};
+
+/// The size of the tag which prefixes the values of a [SumType].
+pub const TAG_SIZE: usize = 8;
+
+/// Reads the tag of a raw [SumType] value.
+pub fn read_tag(raw: &[u8]) -> Option<u64> {
+    Some(u64::from_be_bytes(raw.get(..TAG_SIZE)?.try_into().ok()?))
+}
 
 /// This struct describes a type which can have a value of one of its variant.
 #[derive(Clone, Debug)]
@@ -22,13 +36,25 @@ pub struct SumType(Tuple);
 impl SumType {
     pub fn new(types: &[Type]) -> Self { Self(types.to_vec()) }
     pub fn to_tuple(&self) -> Tuple { self.0.to_vec() }
+    pub fn variants(&self) -> &Tuple { &self.0 }
     pub fn variant(&self, tag: usize) -> Option<Type> { self.0.get(tag).cloned() }
+    pub fn len(&self) -> usize { self.0.len() }
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
     pub fn to_rc(self) -> Rc<Self> { Rc::new(self) }
+
+    /// The raw encoding of the variant `tag` with its default payload, zeroed if there is no such variant.
+    pub fn variant_default_raw(&self, tag: usize) -> Vec<u8> {
+        let Some(variant) = self.variant(tag) else { return vec![0; self.size()]; };
+        let mut raw = (tag as u64).to_be_bytes().to_vec();
+        raw.extend(variant.default_raw());
+        raw.resize(self.size(), 0);
+        raw
+    }
 }
 
 impl DataType for SumType {
     fn size(&self) -> usize {
-        sequence::fn_size(&self.0, max)
+        TAG_SIZE + sequence::fn_size(&self.0, max)
     }
 
     fn typename(&self) -> String {
@@ -38,6 +64,37 @@ impl DataType for SumType {
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell> {
         Ok(SumValue::from(self.clone().to_rc(), raw)?.to_cell())
     }
+
+    /// The first variant with its default payload.
+    fn default_raw(&self) -> Vec<u8> { self.variant_default_raw(0) }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        Ok(SumValue::decode(self.clone().to_rc(), raw, decoder)?.to_cell())
+    }
+
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        if !validator.expect_size(self.size(), raw) {
+            return;
+        }
+        let tag = read_tag(raw).unwrap_or_default();
+        match usize::try_from(tag).ok().and_then(|tag| Some((tag, self.variant(tag)?))) {
+            Some((tag, variant)) => {
+                let end = TAG_SIZE + variant.size();
+                validator.visit(Segment::Tag(tag), TAG_SIZE, variant.as_ref(), &raw[TAG_SIZE..end]);
+                if validator.canonical_form().is_some() && raw[end..].iter().any(|byte| *byte != 0) {
+                    validator.report(end, ViolationKind::NonZeroPadding);
+                }
+            }
+            None => validator.report(0, ViolationKind::InvalidTag { tag, variants: self.0.len() }),
+        }
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        let Some(variant) = read_tag(raw).and_then(|tag| self.variant(usize::try_from(tag).ok()?)) else { return; };
+        let end = TAG_SIZE + variant.size();
+        variant.canonicalize_raw(&mut raw[TAG_SIZE..end], form);
+        raw[end..].fill(0);
+    }
 }
 
 impl Add<Type> for SumType {
@@ -58,6 +115,7 @@ impl ProductType {
     pub const fn unit_type() -> Self { Self(Vec::new()) }
     pub fn new(types: &[Type]) -> Self { Self(types.to_vec()) }
     pub fn to_tuple(&self) -> Tuple { self.0.to_vec() }
+    pub fn fields(&self) -> &Tuple { &self.0 }
     pub fn is_unit_type(&self) -> bool { self.0.is_empty() }
     pub fn field(&self, index: usize) -> Option<Type> { self.0.get(index).cloned() }
     pub fn to_rc(self) -> Rc<Self> { Rc::new(self) }
@@ -79,7 +137,21 @@ impl DataType for ProductType {
     fn typename(&self) -> String { format!("<{}>", sequence::join(&self.0, "&")) }
 
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell> {
-        self.0.construct_from_raw(raw)
+        Ok(ProductValue::from(self.clone().to_rc(), raw)?.to_cell())
+    }
+
+    fn default_raw(&self) -> Vec<u8> { self.0.default_raw() }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        Ok(ProductValue::decode(self.clone().to_rc(), raw, decoder)?.to_cell())
+    }
+
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        self.0.validate_raw(raw, validator)
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        self.0.canonicalize_raw(raw, form)
     }
 }
 
@@ -4,14 +4,32 @@
 //!
 //! Any `struct` describing a Roussillon data type *MUST* implement this trait.
 
+use std::any::Any;
 use std::fmt::{Debug, Display, Formatter};
 use std::rc::Rc;
 
+use crate::value::canonical::CanonicalForm;
 use crate::value::concept::ValueCell;
-use crate::value::error::TypeResult;
+use crate::value::decode::Decoder;
+use crate::value::error::{CanTypeError, TypeError, TypeResult};
+use crate::value::path::Path;
+use crate::value::validation::{Validator, Violation, ViolationKind};
+
+/// A trait to get a value as [Any], to downcast it to its concrete type.
+pub trait AsAny {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any { self }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+}
 
 /// A trait for structs that represent a data type.
-pub trait DataType {
+pub trait DataType: AsAny {
     /// The allocation size of an element for this [DataType] at compile time.
     fn size(&self) -> usize;
 
@@ -20,11 +38,67 @@ pub trait DataType {
     
     /// Construct a new ValueCell.
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell>;
+
+    /// Construct a new ValueCell with every byte set to zero.
+    fn zeroed(&self) -> TypeResult<ValueCell> {
+        self.construct_from_raw(&vec![0; self.size()])
+    }
+
+    /// The raw encoding of the default value of this type.
+    ///
+    /// By default, every byte is zero.
+    fn default_raw(&self) -> Vec<u8> { vec![0; self.size()] }
+
+    /// Construct the default value of this type.
+    fn default_value(&self) -> TypeResult<ValueCell> {
+        self.construct_from_raw(&self.default_raw())
+    }
+
+    /// Construct a new ValueCell within the limits of a [Decoder].
+    ///
+    /// By default, `raw` is decoded as a scalar value of [DataType::size] bytes.
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        decoder.leaf(raw)?;
+        expect_size(self.size(), raw)?;
+        self.construct_from_raw(raw)
+    }
+
+    /// Checks that `raw` encodes a value of this type without constructing it.
+    ///
+    /// By default, only the size of `raw` is checked.
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        validator.expect_size(self.size(), raw);
+    }
+
+    /// Rewrites a valid `raw` value of this type in its canonical encoding.
+    ///
+    /// By default, any encoding is canonical.
+    fn canonicalize_raw(&self, _raw: &mut [u8], _form: &CanonicalForm) {}
+}
+
+/// Returns a [TypeError::InvalidRaw] error if `raw` is not `expected` bytes long.
+pub fn expect_size(expected: usize, raw: &[u8]) -> CanTypeError {
+    if raw.len() == expected {
+        Ok(())
+    } else {
+        Err(TypeError::InvalidRaw(Violation {
+            offset: 0,
+            path: Path::root(),
+            kind: ViolationKind::LengthMismatch { expected, provided: raw.len() },
+        }))
+    }
 }
 
 /// A reference-counted dynamic [DataType].
 pub type Type = Rc<dyn DataType>;
 
+impl dyn DataType {
+    /// Returns this type as a `T` if it is one.
+    pub fn downcast_ref<T: DataType + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+}
+
 impl Display for dyn DataType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.typename())
@@ -1,6 +1,7 @@
 use std::rc::Rc;
 use crate::types::concept::{DataType, Type};
 use crate::value::concept::ValueCell;
+use crate::value::decode::Decoder;
 use crate::value::error::{TypeError, TypeResult};
 
 #[derive(Clone)]
@@ -76,6 +77,13 @@ impl DataType for Dynamic {
             Dynamic::Defined(t) => t.construct_from_raw(raw),
         }
     }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        match self {
+            Dynamic::TypeVar(TypeVariable::Type(t)) | Dynamic::Defined(t) => t.decode(raw, decoder),
+            _ => self.construct_from_raw(raw),
+        }
+    }
 }
 
 pub fn top_type() -> Type { Rc::new(Dynamic::Any) }
\ No newline at end of file
@@ -1,10 +1,11 @@
 use std::rc::Rc;
 
+use crate::annotation::Annotations;
 use crate::identity::Identifier;
 use crate::types::concept::{DataType, Type};
 use crate::types::sequence::Tuple;
 use crate::value::concept::ValueCell;
-use crate::value::error::TypeResult;
+use crate::value::error::{TypeError, TypeResult};
 
 #[derive(Clone, Debug)]
 pub struct FunctionType {
@@ -25,7 +26,7 @@ impl DataType for FunctionType {
     }
 
     fn construct_from_raw(&self, _raw: &[u8]) -> TypeResult<ValueCell> {
-        todo!()
+        Err(TypeError::Message("Cannot construct a function from raw data.".to_string()))
     }
 }
 
@@ -33,10 +34,16 @@ impl DataType for FunctionType {
 pub struct FunctionDeclaration {
     pub identifier: Identifier,
     pub signature: FunctionType,
+    pub annotations: Annotations,
 }
 
 impl FunctionDeclaration {
     pub fn new(identifier: Identifier, signature: FunctionType) -> Self {
-        Self { identifier, signature }
+        Self { identifier, signature, annotations: Annotations::new() }
+    }
+
+    pub fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = annotations;
+        self
     }
 }
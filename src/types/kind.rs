@@ -0,0 +1,219 @@
+//! The [TypeKind] view of a [Type], to walk any type without knowing its concrete struct.
+//!
+//! `kind()` is implemented on `dyn DataType`, so it is available on any [Type].
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::structure;
+//! use roussillon_type_system::types::concept::Type;
+//! use roussillon_type_system::types::kind::TypeKind;
+//!
+//! let point: Type = structure! { Geometry/Point { x: float, tags: [byte; 4] } };
+//!
+//! let TypeKind::Structure { identifier, fields, .. } = point.kind() else { panic!() };
+//! assert_eq!(identifier.to_string(), "Geometry/Point");
+//! assert!(matches!(fields[1].kind(), TypeKind::List { len: 4, .. }));
+//! ```
+
+use crate::identity::{Identified, Identifier, LabelBank};
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::{DataType, Type};
+use crate::types::functional::FunctionType;
+use crate::types::primitive::Primitive;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+
+/// What a [Type] is, with its components.
+#[derive(Clone, Debug)]
+pub enum TypeKind<'a> {
+    Boolean,
+    Byte,
+    /// [Primitive::Bytes] of `size` bytes.
+    Bytes(usize),
+    Integer,
+    Float,
+    Reference {
+        to_type: &'a Type,
+    },
+    List {
+        of_type: &'a Type,
+        len: usize,
+    },
+    Tuple {
+        members: &'a Tuple,
+    },
+    Product {
+        fields: &'a Tuple,
+    },
+    Sum {
+        variants: &'a Tuple,
+    },
+    Structure {
+        identifier: Identifier,
+        labels: &'a LabelBank,
+        fields: &'a Tuple,
+    },
+    Enumeration {
+        identifier: Identifier,
+        labels: &'a LabelBank,
+        variants: &'a Tuple,
+    },
+    Function {
+        arguments: &'a Tuple,
+        return_type: &'a Type,
+    },
+    /// Any other type, like the experimental types.
+    Other,
+}
+
+impl dyn DataType {
+    /// Returns what this type is, with its components.
+    pub fn kind(&self) -> TypeKind<'_> {
+        if let Some(primitive) = self.downcast_ref::<Primitive>() {
+            return match primitive {
+                Primitive::Boolean => TypeKind::Boolean,
+                Primitive::Byte => TypeKind::Byte,
+                Primitive::Bytes(size) => TypeKind::Bytes(*size),
+                Primitive::Integer => TypeKind::Integer,
+                Primitive::Float => TypeKind::Float,
+                Primitive::Reference(to_type) => TypeKind::Reference { to_type },
+                Primitive::List((of_type, len)) => TypeKind::List { of_type, len: *len },
+            };
+        }
+        if let Some(members) = self.downcast_ref::<Tuple>() {
+            return TypeKind::Tuple { members };
+        }
+        if let Some(product) = self.downcast_ref::<ProductType>() {
+            return TypeKind::Product { fields: product.fields() };
+        }
+        if let Some(sum) = self.downcast_ref::<SumType>() {
+            return TypeKind::Sum { variants: sum.variants() };
+        }
+        if let Some(structure) = self.downcast_ref::<Structure>() {
+            return TypeKind::Structure {
+                identifier: structure.identifier(),
+                labels: &structure.labels,
+                fields: structure.product_type.fields(),
+            };
+        }
+        if let Some(enumeration) = self.downcast_ref::<Enumeration>() {
+            return TypeKind::Enumeration {
+                identifier: enumeration.identifier(),
+                labels: enumeration.labels(),
+                variants: enumeration.sum_type.variants(),
+            };
+        }
+        if let Some(function) = self.downcast_ref::<FunctionType>() {
+            return TypeKind::Function { arguments: &function.arguments, return_type: &function.return_type };
+        }
+        TypeKind::Other
+    }
+}
+
+impl TypeKind<'_> {
+    /// The types this type is composed of, in order.
+    pub fn components(&self) -> Vec<&Type> {
+        match self {
+            TypeKind::Reference { to_type } => vec![to_type],
+            TypeKind::List { of_type, .. } => vec![of_type],
+            TypeKind::Tuple { members } => members.iter().collect(),
+            TypeKind::Product { fields } | TypeKind::Structure { fields, .. } => fields.iter().collect(),
+            TypeKind::Sum { variants } | TypeKind::Enumeration { variants, .. } => variants.iter().collect(),
+            TypeKind::Function { arguments, return_type } => arguments.iter().chain([*return_type]).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether this type is a [Primitive] without component.
+    pub fn is_scalar(&self) -> bool {
+        matches!(self, TypeKind::Boolean | TypeKind::Byte | TypeKind::Bytes(_) | TypeKind::Integer | TypeKind::Float)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::types::algebraic::SumType;
+    use crate::types::concept::{DataType, Type};
+    use crate::types::functional::FunctionType;
+    use crate::types::kind::TypeKind;
+    use crate::types::sequence::Tuple;
+    use crate::value::concept::ValueCell;
+    use crate::value::error::{TypeError, TypeResult};
+    use crate::{data_type, enumeration, structure};
+
+    struct Opaque;
+
+    impl DataType for Opaque {
+        fn size(&self) -> usize { 0 }
+
+        fn typename(&self) -> String { "opaque".to_string() }
+
+        fn construct_from_raw(&self, _: &[u8]) -> TypeResult<ValueCell> { Err(TypeError::Message("opaque".to_string())) }
+    }
+
+    fn typenames(components: Vec<&Type>) -> Vec<String> {
+        components.iter().map(|component| component.typename()).collect()
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert!(matches!(data_type!(boolean).kind(), TypeKind::Boolean));
+        assert!(matches!(data_type!(byte).kind(), TypeKind::Byte));
+        assert!(matches!(data_type!(bytes<3>).kind(), TypeKind::Bytes(3)));
+        assert!(matches!(data_type!(integer).kind(), TypeKind::Integer));
+        assert!(matches!(data_type!(float).kind(), TypeKind::Float));
+        for scalar in [data_type!(boolean), data_type!(byte), data_type!(wide), data_type!(integer), data_type!(float)] {
+            assert!(scalar.kind().is_scalar());
+            assert!(scalar.kind().components().is_empty());
+        }
+
+        let reference = data_type!(&float);
+        assert!(!reference.kind().is_scalar());
+        assert_eq!(typenames(reference.kind().components()), ["float"]);
+        let list = data_type!([integer; 4]);
+        assert!(matches!(list.kind(), TypeKind::List { len: 4, .. }));
+        assert_eq!(typenames(list.kind().components()), ["integer"]);
+    }
+
+    #[test]
+    fn test_composites() {
+        let tuple: Type = Rc::new(vec![data_type!(boolean), data_type!(byte)] as Tuple);
+        assert!(matches!(tuple.kind(), TypeKind::Tuple { .. }));
+        assert_eq!(typenames(tuple.kind().components()), ["boolean", "byte"]);
+
+        let product = data_type!((integer, float));
+        assert!(matches!(product.kind(), TypeKind::Product { .. }));
+        assert_eq!(typenames(product.kind().components()), ["integer", "float"]);
+
+        let sum: Type = SumType::new(&[data_type!(()), data_type!(integer)]).to_rc();
+        assert!(matches!(sum.kind(), TypeKind::Sum { .. }));
+        assert_eq!(typenames(sum.kind().components()), ["<>", "integer"]);
+
+        let point: Type = structure! { Geometry/Point { x: float, y: float } };
+        let TypeKind::Structure { identifier, labels, .. } = point.kind() else { panic!("a structure") };
+        assert_eq!(identifier.to_string(), "Geometry/Point");
+        assert_eq!(labels.label(1).unwrap().as_str(), "y");
+        assert_eq!(typenames(point.kind().components()), ["float", "float"]);
+
+        let option: Type = enumeration! { Option { None: (), Some: point } };
+        let TypeKind::Enumeration { identifier, labels, .. } = option.kind() else { panic!("an enumeration") };
+        assert_eq!(identifier.to_string(), "Option");
+        assert_eq!(labels.label(0).unwrap().as_str(), "None");
+        assert_eq!(typenames(option.kind().components()), ["<>", "Geometry/Point"]);
+
+        let function: Type = FunctionType::new(vec![data_type!(integer), data_type!(byte)], data_type!(boolean)).to_rc();
+        assert!(matches!(function.kind(), TypeKind::Function { .. }));
+        assert_eq!(typenames(function.kind().components()), ["integer", "byte", "boolean"]);
+    }
+
+    #[test]
+    fn test_other() {
+        let opaque: Type = Rc::new(Opaque);
+        assert!(matches!(opaque.kind(), TypeKind::Other));
+        assert!(!opaque.kind().is_scalar());
+        assert!(opaque.kind().components().is_empty());
+    }
+}
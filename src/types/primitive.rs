@@ -9,14 +9,18 @@
 
 use std::mem::size_of;
 use std::rc::Rc;
-use crate::types::concept::{DataType, Type};
+use crate::types::concept::{expect_size, DataType, Type};
 use crate::value::boolean::Boolean;
+use crate::value::canonical::CanonicalForm;
 use crate::value::byte::Bytes;
 use crate::value::concept::{GetDataValue, ValueCell};
+use crate::value::decode::Decoder;
 use crate::value::error::{TypeError, TypeResult};
 use crate::value::number::{Float, Integer};
 use crate::value::reference::Reference;
 use crate::value::list::List as ListValue;
+use crate::value::path::Segment;
+use crate::value::validation::{Validator, ViolationKind};
 
 pub type List = (Type, usize);
 
@@ -125,7 +129,67 @@ impl DataType for Primitive {
             Primitive::Float => Ok(Float::from(raw).to_cell()),
             Primitive::Integer => Ok(Integer::from(raw).to_cell()),
             Primitive::Reference(t) => Ok(Reference::from(Rc::clone(t), raw).to_cell()),
-            Primitive::List(t) => Ok(ListValue::from(Rc::clone(&t.0), t.1, raw).to_cell())
+            Primitive::List(t) => Ok(ListValue::from(Rc::clone(&t.0), t.1, raw)?.to_cell())
         }
     }
-}
\ No newline at end of file
+
+    fn default_raw(&self) -> Vec<u8> {
+        match self {
+            Primitive::List((of_type, len)) => of_type.default_raw().repeat(*len),
+            _ => vec![0; self.size()],
+        }
+    }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        match self {
+            Primitive::List((of_type, len)) => Ok(ListValue::decode(Rc::clone(of_type), *len, raw, decoder)?.to_cell()),
+            _ => {
+                decoder.leaf(raw)?;
+                expect_size(self.size(), raw)?;
+                self.construct_from_raw(raw)
+            }
+        }
+    }
+
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        if !validator.expect_size(self.size(), raw) {
+            return;
+        }
+        match self {
+            Primitive::Boolean if raw[0] > 1 => validator.report(0, ViolationKind::InvalidBoolean(raw[0])),
+            Primitive::Float => if let Some(form) = validator.canonical_form() {
+                let bits = u64::from_be_bytes(raw.try_into().unwrap());
+                if form.float(bits) != bits {
+                    validator.report(0, ViolationKind::NonCanonicalFloat(bits));
+                }
+            },
+            Primitive::List((of_type, len)) => {
+                let size = of_type.size();
+                for index in 0..*len {
+                    let start = index * size;
+                    validator.visit(Segment::Index(index), start, of_type.as_ref(), &raw[start..start + size]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        match self {
+            Primitive::Boolean => raw[0] = form.boolean(raw[0]),
+            Primitive::Float => {
+                let bits = u64::from_be_bytes((&*raw).try_into().unwrap());
+                raw.copy_from_slice(&form.float(bits).to_be_bytes());
+            }
+            Primitive::List((of_type, _)) => {
+                let size = of_type.size();
+                if size > 0 {
+                    for element in raw.chunks_exact_mut(size) {
+                        of_type.canonicalize_raw(element, form);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! A [Registry] of named definitions.
+//!
+//! A registry keeps [Definition]s in registration order and finds them by their [Identifier].
+//! Importers produce registries, and generators walk them.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::annotation::Annotations;
+use crate::identity::{Identified, Identifier};
+use crate::types::concept::Type;
+use crate::types::functional::FunctionDeclaration;
+use crate::types::typedef::{Enumeration, Structure};
+
+/// A named definition.
+#[derive(Clone, Debug)]
+pub enum Definition {
+    Structure(Rc<Structure>),
+    Enumeration(Rc<Enumeration>),
+    Function(FunctionDeclaration),
+}
+
+impl Definition {
+    /// The type of this definition, for [Definition::Structure] and [Definition::Enumeration].
+    pub fn to_type(&self) -> Option<Type> {
+        match self {
+            Definition::Structure(structure) => Some(structure.clone()),
+            Definition::Enumeration(enumeration) => Some(enumeration.clone()),
+            Definition::Function(_) => None,
+        }
+    }
+
+    pub fn annotations(&self) -> &Annotations {
+        match self {
+            Definition::Structure(structure) => &structure.annotations,
+            Definition::Enumeration(enumeration) => &enumeration.annotations,
+            Definition::Function(function) => &function.annotations,
+        }
+    }
+}
+
+impl Identified for Definition {
+    fn identifier(&self) -> Identifier {
+        match self {
+            Definition::Structure(structure) => structure.identifier(),
+            Definition::Enumeration(enumeration) => enumeration.identifier(),
+            Definition::Function(function) => function.identifier.clone(),
+        }
+    }
+}
+
+/// A collection of [Definition]s, indexed by their [Identifier].
+#[derive(Clone, Debug, Default)]
+pub struct Registry {
+    definitions: Vec<Definition>,
+    index: HashMap<String, usize>,
+}
+
+impl Registry {
+    pub fn new() -> Self { Self::default() }
+
+    /// Registers a definition.
+    ///
+    /// Returns the previous definition with the same identifier, which is replaced in place.
+    pub fn register(&mut self, definition: Definition) -> Option<Definition> {
+        let name = definition.identifier().to_string();
+        match self.index.get(&name) {
+            Some(index) => Some(std::mem::replace(&mut self.definitions[*index], definition)),
+            None => {
+                self.index.insert(name, self.definitions.len());
+                self.definitions.push(definition);
+                None
+            }
+        }
+    }
+
+    pub fn add_structure(&mut self, structure: Rc<Structure>) -> Option<Definition> {
+        self.register(Definition::Structure(structure))
+    }
+
+    pub fn add_enumeration(&mut self, enumeration: Rc<Enumeration>) -> Option<Definition> {
+        self.register(Definition::Enumeration(enumeration))
+    }
+
+    pub fn add_function(&mut self, function: FunctionDeclaration) -> Option<Definition> {
+        self.register(Definition::Function(function))
+    }
+
+    /// Finds a definition by its identifier, written `space/name`.
+    pub fn get(&self, identifier: &str) -> Option<&Definition> {
+        self.definitions.get(*self.index.get(identifier)?)
+    }
+
+    pub fn structure(&self, identifier: &str) -> Option<Rc<Structure>> {
+        match self.get(identifier)? {
+            Definition::Structure(structure) => Some(structure.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn enumeration(&self, identifier: &str) -> Option<Rc<Enumeration>> {
+        match self.get(identifier)? {
+            Definition::Enumeration(enumeration) => Some(enumeration.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn function(&self, identifier: &str) -> Option<&FunctionDeclaration> {
+        match self.get(identifier)? {
+            Definition::Function(function) => Some(function),
+            _ => None,
+        }
+    }
+
+    /// All definitions, in registration order.
+    pub fn definitions(&self) -> &[Definition] { &self.definitions }
+
+    /// The namespaces of the definitions, in order of first appearance.
+    pub fn namespaces(&self) -> Vec<String> {
+        let mut namespaces: Vec<String> = Vec::new();
+        for definition in &self.definitions {
+            let space = definition.space();
+            if !namespaces.contains(&space) {
+                namespaces.push(space);
+            }
+        }
+        namespaces
+    }
+
+    /// The definitions of a namespace, in registration order.
+    pub fn in_namespace(&self, space: &str) -> Vec<&Definition> {
+        self.definitions.iter().filter(|definition| definition.space() == space).collect()
+    }
+
+    pub fn len(&self) -> usize { self.definitions.len() }
+
+    pub fn is_empty(&self) -> bool { self.definitions.is_empty() }
+}
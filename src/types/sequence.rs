@@ -3,9 +3,13 @@
 //! This type is used in any "type collection" like [crate::types::algebraic::SumType] and [crate::types::algebraic::ProductType].
 
 use crate::types::concept::{DataType, Type};
+use crate::value::canonical::CanonicalForm;
 use crate::value::concept::ValueCell;
+use crate::value::decode::Decoder;
 use crate::value::error::TypeResult;
+use crate::value::path::Segment;
 use crate::value::sequence::Sequence;
+use crate::value::validation::Validator;
 
 pub type Tuple = Vec<Type>;
 
@@ -40,4 +44,33 @@ impl DataType for Tuple {
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell> {
         Ok(Sequence::from(self.clone(), raw)?.to_cell())
     }
+
+    fn default_raw(&self) -> Vec<u8> {
+        self.iter().flat_map(|member| member.default_raw()).collect()
+    }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        Ok(Sequence::decode(self.clone(), raw, decoder)?.to_cell())
+    }
+
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        if !validator.expect_size(self.size(), raw) {
+            return;
+        }
+        let mut start = 0;
+        for (position, member) in self.iter().enumerate() {
+            let end = start + member.size();
+            validator.visit(Segment::Position(position), start, member.as_ref(), &raw[start..end]);
+            start = end;
+        }
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        let mut start = 0;
+        for member in self.iter() {
+            let end = start + member.size();
+            member.canonicalize_raw(&mut raw[start..end], form);
+            start = end;
+        }
+    }
 }
@@ -3,14 +3,109 @@
 //! It lets the user identify algebraic types :
 //! - [Structure] is an [Identified] : [ProductType],
 //! - [Enumeration] is an [Identified] : [SumType],
+//!
+//! The fields of a [Structure] have a [FieldAccess] : a [Visibility] checked against the [Identifier] of the accessor,
+//! and a read-only flag. The checked accessors of [Record] enforce them.
+//!
+//! ```
+//! use roussillon_type_system::identity::{Identifier, LabelBank};
+//! use roussillon_type_system::types::algebraic::ProductType;
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::types::typedef::{FieldAccess, Structure, Visibility};
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::value::record::Record;
+//!
+//! let account = Structure::new("Bank/Account", LabelBank::from(&["id", "balance"]), ProductType::new(&[
+//!     Primitive::Integer.to_rc(),
+//!     Primitive::Integer.to_rc(),
+//! ]))
+//!     .with_access("id", FieldAccess::new(Visibility::Public).read_only()).unwrap()
+//!     .with_access("balance", FieldAccess::new(Visibility::Namespace)).unwrap()
+//!     .to_rc();
+//! let mut record = Record::new(account, &[Integer::new(1).to_cell(), Integer::new(100).to_cell()]).unwrap();
+//!
+//! let bank = Identifier::new("Bank/transfer");
+//! let outside = Identifier::new("Shop/checkout");
+//! assert!(record.field_from_name_as("balance", &bank).is_ok());
+//! assert!(record.field_from_name_as("balance", &outside).is_err());
+//! assert!(record.set_field_from_name("id", &Integer::new(2).to_cell(), &bank).is_err());
+//! ```
+//!
+//! The fields of a [Structure] may have a default value, and an [Enumeration] has a default variant.
+//! They give the [DataType::default_value] of the type, and let [Record::from_required] leave out the fields with a default :
+//!
+//! ```
+//! use roussillon_type_system::identity::LabelBank;
+//! use roussillon_type_system::types::algebraic::ProductType;
+//! use roussillon_type_system::types::concept::DataType;
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::types::typedef::Structure;
+//! use roussillon_type_system::value::concept::DataValue;
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::value::record::Record;
+//!
+//! let settings = Structure::new("App/Settings", LabelBank::from(&["width", "height"]), ProductType::new(&[
+//!     Primitive::Integer.to_rc(),
+//!     Primitive::Integer.to_rc(),
+//! ])).with_default("height", &Integer::new(600).to_cell()).unwrap().to_rc();
+//!
+//! assert_eq!(settings.default_value().unwrap().borrow().raw()[8..], 600i64.to_be_bytes());
+//! let record = Record::from_required(settings, &[("width", Integer::new(800).to_cell())]).unwrap();
+//! assert_eq!(record.raw()[..8], 800i64.to_be_bytes());
+//! ```
 
+use std::collections::HashMap;
 use std::rc::Rc;
+use crate::annotation::Annotations;
 use crate::identity::{Identified, Identifier, Label, LabelBank, Labelled};
 use crate::types::algebraic::{ProductType, SumType};
 use crate::types::concept::{DataType, Type};
+use crate::value::canonical::CanonicalForm;
 use crate::value::concept::ValueCell;
-use crate::value::error::TypeResult;
+use crate::value::decode::Decoder;
+use crate::value::error::{AccessError, CanTypeError, LabelError, TypeResult};
+use crate::value::path::Segment;
 use crate::value::record::Record;
+use crate::value::union::Union;
+use crate::value::validation::Validator;
+
+/// Where a field of a [Structure] can be accessed from.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Visibility {
+    #[default]
+    Public,
+    /// Accessible from the namespace of the structure.
+    Namespace,
+    /// Accessible from the structure itself.
+    Private,
+}
+
+impl Visibility {
+    /// Returns `true` if a field of `owner` with this visibility can be accessed from `accessor`.
+    pub fn allows(&self, owner: &Identifier, accessor: &Identifier) -> bool {
+        match self {
+            Visibility::Public => true,
+            Visibility::Namespace => owner.space == accessor.space,
+            Visibility::Private => owner == accessor,
+        }
+    }
+}
+
+/// The access rules of a field of a [Structure]. Fields are public and writable by default.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldAccess {
+    pub visibility: Visibility,
+    pub read_only: bool,
+}
+
+impl FieldAccess {
+    pub fn new(visibility: Visibility) -> Self { Self { visibility, read_only: false } }
+
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+}
 
 /// A [Structure] is an identified [ProductType].
 #[derive(Clone, Debug)]
@@ -18,6 +113,9 @@ pub struct Structure {
     identifier: Identifier,
     pub labels: LabelBank,
     pub product_type: ProductType,
+    pub annotations: Annotations,
+    access: HashMap<usize, FieldAccess>,
+    defaults: HashMap<usize, Vec<u8>>,
 }
 
 impl Structure {
@@ -26,7 +124,86 @@ impl Structure {
             identifier: Identifier::new(identifier),
             labels,
             product_type: fields,
+            annotations: Annotations::new(),
+            access: HashMap::new(),
+            defaults: HashMap::new(),
+        }
+    }
+
+    pub fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// Sets the access rules of the field labelled `label`.
+    pub fn with_access(mut self, label: &str, access: FieldAccess) -> TypeResult<Self> {
+        let index = self.field_index(label)?;
+        self.access.insert(index, access);
+        Ok(self)
+    }
+
+    /// The access rules of the field at `index`.
+    pub fn access(&self, index: usize) -> FieldAccess { self.access.get(&index).copied().unwrap_or_default() }
+
+    /// Checks that the field at `index` exists and is visible from `accessor`.
+    pub fn check_read(&self, index: usize, accessor: &Identifier) -> CanTypeError {
+        if self.product_type.field(index).is_none() {
+            return Err(AccessError::NoSuchField { structure: self.identifier.to_string(), field: index.to_string() }.promote());
+        }
+        let visibility = self.access(index).visibility;
+        if !visibility.allows(&self.identifier, accessor) {
+            return Err(AccessError::Hidden { structure: self.identifier.to_string(), field: self.field_name(index), visibility, accessor: accessor.to_string() }.promote());
+        }
+        Ok(())
+    }
+
+    /// Checks that the field at `index` is visible from `accessor` and is not read-only.
+    pub fn check_write(&self, index: usize, accessor: &Identifier) -> CanTypeError {
+        self.check_read(index, accessor)?;
+        if self.access(index).read_only {
+            return Err(AccessError::ReadOnly { structure: self.identifier.to_string(), field: self.field_name(index) }.promote());
         }
+        Ok(())
+    }
+
+    /// Sets the default value of the field labelled `label`.
+    pub fn with_default(mut self, label: &str, value: &ValueCell) -> TypeResult<Self> {
+        let index = self.field_index(label)?;
+        value.borrow().validate_type(&self.product_type.field(index).unwrap())?;
+        self.defaults.insert(index, value.borrow().raw());
+        Ok(self)
+    }
+
+    /// Returns `true` if the field at `index` has a default value set with [Structure::with_default].
+    pub fn has_default(&self, index: usize) -> bool { self.defaults.contains_key(&index) }
+
+    /// The raw default value of the field at `index` : its own default, or the default of its type.
+    pub fn field_default_raw(&self, index: usize) -> Option<Vec<u8>> {
+        match self.defaults.get(&index) {
+            Some(raw) => Some(raw.clone()),
+            None => Some(self.product_type.field(index)?.default_raw()),
+        }
+    }
+
+    /// Replaces the types of the fields, and drops the default values of the fields whose type changes.
+    pub fn with_fields(mut self, fields: ProductType) -> Self {
+        let previous = std::mem::replace(&mut self.product_type, fields);
+        let product_type = &self.product_type;
+        self.defaults.retain(|index, _| {
+            matches!((previous.field(*index), product_type.field(*index)), (Some(before), Some(after)) if before.typename() == after.typename())
+        });
+        self
+    }
+
+    /// The index of the field labelled `label`.
+    pub fn field_index(&self, label: &str) -> TypeResult<usize> {
+        self.labels.labelled(&Label::new(label))
+            .ok_or_else(|| LabelError::Unknown { typename: self.typename(), label: label.to_string() }.promote())
+    }
+
+    /// The label of the field at `index`, or its index.
+    pub fn field_name(&self, index: usize) -> String {
+        self.labels.label(index).map(Label::to_string).unwrap_or_else(|| index.to_string())
     }
 
     pub fn to_rc(self) -> Rc<Self> { Rc::new(self) }
@@ -40,6 +217,33 @@ impl DataType for Structure {
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell> {
         Ok(Record::from(self.clone().to_rc(), raw)?.to_cell())
     }
+
+    /// The default values of the fields.
+    fn default_raw(&self) -> Vec<u8> {
+        (0..self.product_type.to_tuple().len()).flat_map(|index| self.field_default_raw(index).unwrap()).collect()
+    }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        Ok(Record::decode(self.clone().to_rc(), raw, decoder)?.to_cell())
+    }
+
+    /// Validates the fields like a [ProductType], at labelled [Segment::Field]s.
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        if !validator.expect_size(self.size(), raw) {
+            return;
+        }
+        let mut start = 0;
+        for (position, field) in self.product_type.fields().iter().enumerate() {
+            let end = start + field.size();
+            let segment = self.labels.label(position).cloned().map(Segment::Field).unwrap_or(Segment::Position(position));
+            validator.visit(segment, start, field.as_ref(), &raw[start..end]);
+            start = end;
+        }
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        self.product_type.canonicalize_raw(raw, form)
+    }
 }
 
 impl Identified for Structure {
@@ -58,6 +262,8 @@ pub struct Enumeration {
     identifier: Identifier,
     labels: LabelBank,
     pub sum_type: SumType,
+    pub annotations: Annotations,
+    default_variant: usize,
 }
 
 impl Enumeration {
@@ -66,10 +272,35 @@ impl Enumeration {
             identifier: Identifier::new(identifier),
             labels,
             sum_type,
+            annotations: Annotations::new(),
+            default_variant: 0,
         }
     }
+
+    pub fn with_annotations(mut self, annotations: Annotations) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
     pub fn variant(&self, tag: usize) -> Option<Type> { self.sum_type.variant(tag) }
 
+    /// Sets the variant of the default value to the variant labelled `label`.
+    pub fn with_default_variant(mut self, label: &str) -> TypeResult<Self> {
+        self.default_variant = self.variant_index(label)?;
+        Ok(self)
+    }
+
+    /// The tag of the variant labelled `label`.
+    pub fn variant_index(&self, label: &str) -> TypeResult<usize> {
+        self.labels.labelled(&Label::new(label))
+            .ok_or_else(|| LabelError::Unknown { typename: self.typename(), label: label.to_string() }.promote())
+    }
+
+    /// The tag of the variant of the default value, the first variant unless set with [Enumeration::with_default_variant].
+    pub fn default_variant(&self) -> usize { self.default_variant }
+
+    pub fn labels(&self) -> &LabelBank { &self.labels }
+
     pub fn to_rc(self) -> Rc<Self> { Rc::new(self) }
 }
 
@@ -79,7 +310,22 @@ impl DataType for Enumeration {
     fn typename(&self) -> String { self.identifier.to_string() }
 
     fn construct_from_raw(&self, raw: &[u8]) -> TypeResult<ValueCell> {
-        self.sum_type.construct_from_raw(raw)
+        Ok(Union::from(self.clone().to_rc(), raw)?.to_cell())
+    }
+
+    /// The default variant with its default payload.
+    fn default_raw(&self) -> Vec<u8> { self.sum_type.variant_default_raw(self.default_variant) }
+
+    fn decode(&self, raw: &[u8], decoder: &mut Decoder) -> TypeResult<ValueCell> {
+        Ok(Union::decode(self.clone().to_rc(), raw, decoder)?.to_cell())
+    }
+
+    fn validate_raw(&self, raw: &[u8], validator: &mut Validator) {
+        self.sum_type.validate_raw(raw, validator)
+    }
+
+    fn canonicalize_raw(&self, raw: &mut [u8], form: &CanonicalForm) {
+        self.sum_type.canonicalize_raw(raw, form)
     }
 }
 
@@ -91,4 +337,82 @@ impl Identified for Enumeration {
 
 impl Labelled<Type> for Enumeration {
     fn labelled(&self, label: &Label) -> Option<Type> { self.variant(self.labels.labelled(label)?) }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod test {
+    use crate::identity::LabelBank;
+    use crate::types::algebraic::{ProductType, SumType};
+    use crate::types::concept::DataType;
+    use crate::types::primitive::Primitive;
+    use crate::types::typedef::{Enumeration, Structure};
+    use crate::value::concept::DataValue;
+    use crate::value::error::{LabelError, TypeError};
+    use crate::value::number::{Float, Integer};
+    use crate::value::record::{Record, RecordBuilder};
+
+    fn settings() -> Structure {
+        Structure::new("App/Settings", LabelBank::from(&["width", "height"]), ProductType::new(&[
+            Primitive::Integer.to_rc(),
+            Primitive::Integer.to_rc(),
+        ]))
+    }
+
+    fn status() -> Enumeration {
+        Enumeration::new("App/Status", LabelBank::from(&["Idle", "Busy"]), SumType::new(&[
+            Primitive::Byte.to_rc(),
+            settings().with_default("height", &Integer::new(600).to_cell()).unwrap().to_rc(),
+        ]))
+    }
+
+    #[test]
+    fn test_field_defaults() {
+        let Err(TypeError::LabelError(LabelError::Unknown { label, .. })) = settings().with_default("depth", &Integer::new(1).to_cell()) else {
+            panic!("an unknown field has no default");
+        };
+        assert_eq!(label, "depth");
+        assert!(matches!(settings().with_default("height", &Float::new(1.0).to_cell()), Err(TypeError::InvalidType { .. })));
+        let settings = settings().with_default("height", &Integer::new(600).to_cell()).unwrap();
+        assert!(!settings.has_default(0) && settings.has_default(1));
+        assert_eq!(settings.field_default_raw(1).unwrap(), 600i64.to_be_bytes());
+        assert_eq!(settings.field_default_raw(0).unwrap(), 0i64.to_be_bytes());
+        assert_eq!(settings.field_default_raw(2), None);
+        assert_eq!(settings.zeroed().unwrap().borrow().raw(), [0; 16]);
+        assert_eq!(settings.default_value().unwrap().borrow().raw()[8..], 600i64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let settings = settings().with_default("width", &Integer::new(800).to_cell()).unwrap()
+            .with_default("height", &Integer::new(600).to_cell()).unwrap()
+            .with_fields(ProductType::new(&[Primitive::Integer.to_rc(), Primitive::Float.to_rc()]));
+        assert!(settings.has_default(0));
+        assert!(!settings.has_default(1));
+    }
+
+    #[test]
+    fn test_default_variant() {
+        let Err(TypeError::LabelError(LabelError::Unknown { typename, label })) = status().with_default_variant("Done") else {
+            panic!("an unknown variant cannot be the default");
+        };
+        assert_eq!((typename.as_str(), label.as_str()), ("App/Status", "Done"));
+        assert_eq!(status().default_variant(), 0);
+        assert_eq!(status().default_raw(), [0; 24]);
+        let status = status().with_default_variant("Busy").unwrap();
+        assert_eq!(status.default_variant(), 1);
+        assert_eq!(status.default_raw(), [1u64.to_be_bytes(), 0i64.to_be_bytes(), 600i64.to_be_bytes()].concat());
+        assert_eq!(status.zeroed().unwrap().borrow().raw(), [0; 24]);
+    }
+
+    #[test]
+    fn test_required_fields() {
+        let settings = settings().with_default("height", &Integer::new(600).to_cell()).unwrap().to_rc();
+        let Err(TypeError::LabelError(LabelError::Missing { typename, label })) = Record::from_required(settings.clone(), &[]) else {
+            panic!("a field without default is required");
+        };
+        assert_eq!((typename.as_str(), label.as_str()), ("App/Settings", "width"));
+        let duplicate = RecordBuilder::new(&settings).set("width", Integer::new(1).to_cell()).unwrap().set("width", Integer::new(2).to_cell());
+        assert!(matches!(duplicate, Err(TypeError::LabelError(LabelError::Duplicate { label, .. })) if label == "width"));
+        let record = Record::from_required(settings, &[("width", Integer::new(800).to_cell())]).unwrap();
+        assert_eq!(record.raw(), [800i64.to_be_bytes(), 600i64.to_be_bytes()].concat());
+    }
+}
@@ -0,0 +1,191 @@
+//! Traversals of the components of a [Type], following its [TypeKind].
+//!
+//! - [walk_type] calls a [TypeVisitor] on a type and its components, depth first,
+//! - [fold_type] rewrites a type with a [TypeFolder], bottom-up :
+//!   a type is rebuilt only if one of its components is rewritten.
+//!
+//! ## Example
+//!
+//! Replace every `integer` by a `float` :
+//! ```
+//! use roussillon_type_system::data_type;
+//! use roussillon_type_system::types::concept::Type;
+//! use roussillon_type_system::types::kind::TypeKind;
+//! use roussillon_type_system::types::visit::{fold_type, TypeFolder};
+//! use roussillon_type_system::value::error::TypeResult;
+//!
+//! struct IntegerToFloat;
+//!
+//! impl TypeFolder for IntegerToFloat {
+//!     fn fold(&mut self, data_type: Type) -> TypeResult<Type> {
+//!         Ok(if matches!(data_type.kind(), TypeKind::Integer) { data_type!(float) } else { data_type })
+//!     }
+//! }
+//!
+//! let folded = fold_type(&data_type!((boolean, [integer; 2])), &mut IntegerToFloat).unwrap();
+//! assert_eq!(folded.typename(), "<boolean&[float;2]>");
+//! ```
+
+use std::rc::Rc;
+
+use crate::types::algebraic::{ProductType, SumType};
+use crate::types::concept::Type;
+use crate::types::functional::FunctionType;
+use crate::types::kind::TypeKind;
+use crate::types::primitive::Primitive;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::error::TypeResult;
+
+/// Visits a type and its components with [walk_type].
+pub trait TypeVisitor {
+    /// Called before the components of `data_type`, which are skipped if it returns `false`.
+    fn enter(&mut self, data_type: &Type) -> bool;
+
+    /// Called after the components of `data_type`.
+    fn leave(&mut self, _data_type: &Type) {}
+}
+
+/// Calls `visitor` on `data_type` and its [TypeKind::components], depth first.
+pub fn walk_type(data_type: &Type, visitor: &mut impl TypeVisitor) {
+    if visitor.enter(data_type) {
+        for component in data_type.kind().components() {
+            walk_type(component, visitor);
+        }
+    }
+    visitor.leave(data_type);
+}
+
+/// Rewrites a type and its components with [fold_type].
+pub trait TypeFolder {
+    /// Replaces `data_type` before its components are folded, `None` to fold them.
+    ///
+    /// A replacement is not folded.
+    fn replace(&mut self, _data_type: &Type) -> TypeResult<Option<Type>> { Ok(None) }
+
+    /// Rewrites `data_type`, once its components are folded.
+    fn fold(&mut self, data_type: Type) -> TypeResult<Type> { Ok(data_type) }
+}
+
+/// Rewrites `data_type` with `folder`, bottom-up.
+///
+/// A type with rewritten components is rebuilt with the same kind. A rebuilt [Structure] or [Enumeration] keeps its
+/// identifier, labels and annotations, and drops the default values of the fields whose type changes.
+pub fn fold_type(data_type: &Type, folder: &mut impl TypeFolder) -> TypeResult<Type> {
+    if let Some(replacement) = folder.replace(data_type)? {
+        return Ok(replacement);
+    }
+    let kind = data_type.kind();
+    let components = kind.components();
+    let mut folded = Vec::with_capacity(components.len());
+    for component in components.iter() {
+        folded.push(fold_type(component, folder)?);
+    }
+    let unchanged = components.iter().zip(folded.iter()).all(|(component, folded)| Rc::ptr_eq(component, folded));
+    let rebuilt = if unchanged { data_type.clone() } else { rebuild(data_type, &kind, folded) };
+    folder.fold(rebuilt)
+}
+
+/// Rebuilds `data_type` of `kind` with new `components`.
+fn rebuild(data_type: &Type, kind: &TypeKind, mut components: Tuple) -> Type {
+    match kind {
+        TypeKind::Reference { .. } => Primitive::Reference(components.remove(0)).to_rc(),
+        TypeKind::List { len, .. } => Primitive::list(components.remove(0), *len).to_rc(),
+        TypeKind::Tuple { .. } => Rc::new(components),
+        TypeKind::Product { .. } => ProductType::new(&components).to_rc(),
+        TypeKind::Sum { .. } => SumType::new(&components).to_rc(),
+        TypeKind::Structure { .. } => {
+            let structure = data_type.downcast_ref::<Structure>().unwrap().clone();
+            structure.with_fields(ProductType::new(&components)).to_rc()
+        }
+        TypeKind::Enumeration { .. } => {
+            let mut enumeration = data_type.downcast_ref::<Enumeration>().unwrap().clone();
+            enumeration.sum_type = SumType::new(&components);
+            enumeration.to_rc()
+        }
+        TypeKind::Function { .. } => {
+            let return_type = components.pop().unwrap();
+            FunctionType::new(components, return_type).to_rc()
+        }
+        _ => data_type.clone(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::types::concept::Type;
+    use crate::types::kind::TypeKind;
+    use crate::types::typedef::Structure;
+    use crate::types::visit::{fold_type, walk_type, TypeFolder, TypeVisitor};
+    use crate::value::error::{TypeError, TypeResult};
+    use crate::{data_type, structure};
+
+    /// Records the entered and left typenames, without entering lists.
+    struct Trace(Vec<String>);
+
+    impl TypeVisitor for Trace {
+        fn enter(&mut self, data_type: &Type) -> bool {
+            self.0.push(format!("+{}", data_type.typename()));
+            !matches!(data_type.kind(), TypeKind::List { .. })
+        }
+
+        fn leave(&mut self, data_type: &Type) { self.0.push(format!("-{}", data_type.typename())) }
+    }
+
+    struct IntegerToFloat;
+
+    impl TypeFolder for IntegerToFloat {
+        fn fold(&mut self, data_type: Type) -> TypeResult<Type> {
+            Ok(if matches!(data_type.kind(), TypeKind::Integer) { data_type!(float) } else { data_type })
+        }
+    }
+
+    /// Replaces references by 3 bytes, and rejects booleans.
+    struct Strict;
+
+    impl TypeFolder for Strict {
+        fn replace(&mut self, data_type: &Type) -> TypeResult<Option<Type>> {
+            Ok(matches!(data_type.kind(), TypeKind::Reference { .. }).then(|| data_type!(bytes<3>)))
+        }
+
+        fn fold(&mut self, data_type: Type) -> TypeResult<Type> {
+            match data_type.kind() {
+                TypeKind::Boolean => Err(TypeError::Message("no boolean".to_string())),
+                TypeKind::Integer => Ok(data_type!(float)),
+                _ => Ok(data_type),
+            }
+        }
+    }
+
+    #[test]
+    fn test_walk() {
+        let mut trace = Trace(Vec::new());
+        walk_type(&data_type!((byte, [integer; 2])), &mut trace);
+        assert_eq!(trace.0, ["+<byte&[integer;2]>", "+byte", "-byte", "+[integer;2]", "-[integer;2]", "-<byte&[integer;2]>"]);
+    }
+
+    #[test]
+    fn test_fold() {
+        let unchanged = data_type!((byte, [boolean; 2]));
+        assert!(Rc::ptr_eq(&fold_type(&unchanged, &mut IntegerToFloat).unwrap(), &unchanged));
+
+        let point: Type = structure! { Geometry/Point { x: integer, y: byte } };
+        let folded = fold_type(&point, &mut IntegerToFloat).unwrap();
+        let structure = folded.downcast_ref::<Structure>().unwrap();
+        assert_eq!(folded.typename(), "Geometry/Point");
+        assert_eq!(structure.labels.label(0).unwrap().as_str(), "x");
+        assert_eq!(structure.product_type.fields()[0].typename(), "float");
+        assert_eq!(point.downcast_ref::<Structure>().unwrap().product_type.fields()[0].typename(), "integer");
+    }
+
+    #[test]
+    fn test_replace() {
+        let folded = fold_type(&data_type!((&integer, integer)), &mut Strict).unwrap();
+        assert_eq!(folded.typename(), "<bytes<3>&float>");
+        let folded = fold_type(&data_type!((&boolean, [integer; 2])), &mut Strict).unwrap();
+        assert_eq!(folded.typename(), "<bytes<3>&[float;2]>");
+        assert!(matches!(fold_type(&data_type!((byte, [boolean; 2])), &mut Strict), Err(TypeError::Message(_))));
+    }
+}
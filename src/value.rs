@@ -15,6 +15,9 @@
 //! - [record::Record]
 //! - [union::SumValue]
 //! - [union::Union]
+//!
+//! [convert] converts Rust values to and from these values, [visit] walks and rewrites nested values,
+//! [diff] compares them, [copy] copies them and [graph] detects their shared cells and cycles.
 
 
 pub mod concept;
@@ -28,5 +31,14 @@ pub mod list;
 pub mod union;
 pub mod record;
 pub mod function;
+pub mod path;
+pub mod validation;
+pub mod canonical;
+pub mod decode;
+pub mod convert;
+pub mod visit;
+pub mod diff;
+pub mod copy;
+pub mod graph;
 #[cfg(feature = "experiments")]
 pub mod value_type;
\ No newline at end of file
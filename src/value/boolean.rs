@@ -26,7 +26,7 @@ impl Boolean {
         }
     }
 
-    pub fn parse(input: &[u8]) -> Parsed<Self> {
+    pub fn parse(input: &[u8]) -> Parsed<'_, Self> {
         let (Some(raw), rest) = parse_slice(input, 1) else { return (None, input); };
         (Some(Self::from(raw)), rest)
     }
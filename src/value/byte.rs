@@ -1,6 +1,5 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::{u64, usize};
 use crate::parse::{parse_slice, Parsed};
 
 use crate::types::concept::Type;
@@ -116,7 +115,7 @@ pub enum Bytes {
 pub struct CannotCreateArchWithGivenSize(pub usize);
 
 impl Bytes {
-    pub fn parse(input: &[u8], size: usize) -> Parsed<Self> {
+    pub fn parse(input: &[u8], size: usize) -> Parsed<'_, Self> {
         let (Some(raw), rest) = parse_slice(input, size) else { return (None, input); };
         (Some(Self::from_raw(raw)), rest)
     }
@@ -0,0 +1,163 @@
+//! Canonical encoding of raw values.
+//!
+//! Several byte strings may decode to the same value :
+//! - a [crate::value::boolean::Boolean] is `true` for any non-zero byte,
+//! - a [crate::value::number::Float] accepts any NaN payload and a negative zero,
+//! - the padding of a [crate::value::union::SumValue] after a short variant is never read.
+//!
+//! The canonical encoding keeps only one of them, so raw values can be hashed or compared :
+//! - [canonicalize] rewrites a buffer in its canonical encoding,
+//! - [decode_strict] rejects any buffer which is not in its canonical encoding.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::value::canonical::{canonicalize, decode_strict, CanonicalForm};
+//!
+//! let flags = Primitive::list(Primitive::Boolean.to_rc(), 2).to_rc();
+//! let form = CanonicalForm::default();
+//!
+//! assert!(decode_strict(&flags, &[0, 7], &form).is_err());
+//! assert_eq!(canonicalize(&flags, &[0, 7], &form).unwrap(), vec![0, 1]);
+//! assert!(decode_strict(&flags, &[0, 1], &form).is_ok());
+//! ```
+
+use crate::types::concept::Type;
+use crate::value::concept::ValueCell;
+use crate::value::error::{TypeError, TypeResult};
+use crate::value::validation::{validate_all, Validator, ViolationKind};
+
+/// The bits of the canonical NaN : a quiet NaN without payload.
+pub const CANONICAL_NAN: u64 = 0x7ff8_0000_0000_0000;
+
+/// What to do with the negative zero of a [crate::value::number::Float].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum NegativeZero {
+    /// `-0.0` is canonical and distinct from `0.0`.
+    Preserve,
+    /// `-0.0` is encoded as `0.0`.
+    #[default]
+    Positive,
+}
+
+/// The policy of the canonical encoding.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalForm {
+    pub negative_zero: NegativeZero,
+}
+
+impl CanonicalForm {
+    pub fn new(negative_zero: NegativeZero) -> Self { Self { negative_zero } }
+
+    /// Returns the canonical bits of a float.
+    pub fn float(&self, bits: u64) -> u64 {
+        let value = f64::from_bits(bits);
+        if value.is_nan() {
+            CANONICAL_NAN
+        } else if value == 0.0 && self.negative_zero == NegativeZero::Positive {
+            0
+        } else {
+            bits
+        }
+    }
+
+    /// Returns the canonical byte of a boolean.
+    pub fn boolean(&self, byte: u8) -> u8 { u8::from(byte != 0) }
+}
+
+/// Returns the canonical encoding of `raw` as a value of `data_type`.
+///
+/// Any boolean byte is accepted, but `raw` must have the right size and valid sum tags.
+pub fn canonicalize(data_type: &Type, raw: &[u8], form: &CanonicalForm) -> TypeResult<Vec<u8>> {
+    let malformed = validate_all(data_type, raw).into_iter()
+        .find(|violation| !matches!(violation.kind, ViolationKind::InvalidBoolean(_)));
+    if let Some(violation) = malformed {
+        return Err(TypeError::InvalidRaw(violation));
+    }
+    let mut canonical = raw.to_vec();
+    data_type.canonicalize_raw(&mut canonical, form);
+    Ok(canonical)
+}
+
+/// Constructs a value of `data_type` from `raw` only if it is valid and canonical.
+pub fn decode_strict(data_type: &Type, raw: &[u8], form: &CanonicalForm) -> TypeResult<ValueCell> {
+    let mut validator = Validator::strict(*form);
+    data_type.validate_raw(raw, &mut validator);
+    if let Some(violation) = validator.into_violations().into_iter().next() {
+        return Err(TypeError::InvalidRaw(violation));
+    }
+    data_type.construct_from_raw(raw)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::algebraic::SumType;
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::value::canonical::{canonicalize, decode_strict, CanonicalForm, NegativeZero, CANONICAL_NAN};
+    use crate::value::error::TypeError;
+    use crate::value::validation::ViolationKind;
+
+    fn rejection(data_type: &Type, raw: &[u8], form: &CanonicalForm) -> (usize, ViolationKind) {
+        match decode_strict(data_type, raw, form) {
+            Err(TypeError::InvalidRaw(violation)) => (violation.offset, violation.kind),
+            other => panic!("expected a violation, got {:?}", other.map(|value| value.borrow().raw())),
+        }
+    }
+
+    fn maybe_byte() -> Type {
+        SumType::new(&[Primitive::Byte.to_rc(), Primitive::Integer.to_rc()]).to_rc()
+    }
+
+    #[test]
+    fn test_strict_booleans() {
+        let flags = Primitive::list(Primitive::Boolean.to_rc(), 3).to_rc();
+        let form = CanonicalForm::default();
+        assert!(matches!(rejection(&flags, &[1, 0, 2], &form), (2, ViolationKind::InvalidBoolean(2))));
+        assert_eq!(canonicalize(&flags, &[1, 0, 2], &form).unwrap(), vec![1, 0, 1]);
+        assert!(decode_strict(&flags, &[1, 0, 1], &form).is_ok());
+    }
+
+    #[test]
+    fn test_strict_floats() {
+        let float = Primitive::Float.to_rc();
+        let positive = CanonicalForm::default();
+        let preserve = CanonicalForm::new(NegativeZero::Preserve);
+
+        let payload = 0x7ff8_0000_0000_0001u64;
+        assert!(matches!(rejection(&float, &payload.to_be_bytes(), &positive), (0, ViolationKind::NonCanonicalFloat(bits)) if bits == payload));
+        assert_eq!(canonicalize(&float, &payload.to_be_bytes(), &positive).unwrap(), CANONICAL_NAN.to_be_bytes());
+        assert!(decode_strict(&float, &CANONICAL_NAN.to_be_bytes(), &positive).is_ok());
+
+        let negative_zero = (-0.0f64).to_bits().to_be_bytes();
+        assert!(matches!(rejection(&float, &negative_zero, &positive), (0, ViolationKind::NonCanonicalFloat(_))));
+        assert_eq!(canonicalize(&float, &negative_zero, &positive).unwrap(), vec![0; 8]);
+        assert!(decode_strict(&float, &negative_zero, &preserve).is_ok());
+        assert_eq!(canonicalize(&float, &negative_zero, &preserve).unwrap(), negative_zero);
+    }
+
+    #[test]
+    fn test_strict_padding() {
+        let maybe = maybe_byte();
+        let form = CanonicalForm::default();
+        let mut raw = 0u64.to_be_bytes().to_vec();
+        raw.extend([7, 0, 0, 0, 0, 0, 0, 9]);
+        assert!(matches!(rejection(&maybe, &raw, &form), (_, ViolationKind::NonZeroPadding)));
+
+        let canonical = canonicalize(&maybe, &raw, &form).unwrap();
+        assert_eq!(canonical[8..], [7, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(decode_strict(&maybe, &canonical, &form).is_ok());
+    }
+
+    #[test]
+    fn test_malformed_is_not_canonicalized() {
+        let maybe = maybe_byte();
+        let form = CanonicalForm::default();
+        let mut raw = 2u64.to_be_bytes().to_vec();
+        raw.extend([0; 8]);
+        assert!(matches!(canonicalize(&maybe, &raw, &form), Err(TypeError::InvalidRaw(violation)) if matches!(violation.kind, ViolationKind::InvalidTag { tag: 2, variants: 2 })));
+        assert!(matches!(rejection(&maybe, &raw, &form), (0, ViolationKind::InvalidTag { .. })));
+        assert!(matches!(canonicalize(&maybe, &raw[..9], &form), Err(TypeError::InvalidRaw(violation)) if matches!(violation.kind, ViolationKind::LengthMismatch { expected: 16, provided: 9 })));
+    }
+}
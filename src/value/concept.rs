@@ -1,11 +1,55 @@
-use std::cell::RefCell;
+//! Defines the [DataValue] trait and the [ValueCell] alias (to `Rc<RefCell<dyn DataValue>>`).
+//!
+//! A `dyn DataValue` is downcast to its concrete value with [DataValue::downcast_ref] and [DataValue::downcast_mut],
+//! and a [ValueCell] is borrowed as a concrete value with the [Downcast] helpers.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::value::concept::Downcast;
+//! use roussillon_type_system::value::list::List;
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::types::primitive::Primitive;
+//!
+//! let mut list = List::empty(Primitive::Integer.to_rc());
+//! list.push(Integer::new(40).to_cell()).unwrap();
+//! let cell = list.to_cell();
+//!
+//! let first = cell.as_list().unwrap().item(0).unwrap();
+//! *first.as_integer_mut().unwrap() = Integer::new(42);
+//! assert_eq!(first.as_integer().unwrap().value(), 42);
+//! assert!(cell.as_record().is_none());
+//! ```
+
+use std::cell::{Ref, RefCell, RefMut};
 use std::fmt::{Debug, Formatter};
 use std::rc::Rc;
 
-use crate::types::concept::Type;
+use crate::types::concept::{AsAny, Type};
+use crate::value::boolean::Boolean;
+use crate::value::byte::Bytes;
 use crate::value::error::{CanTypeError, TypeError};
+use crate::value::list::List;
+use crate::value::number::{Float, Integer};
+use crate::value::record::Record;
+use crate::value::sequence::Sequence;
+use crate::value::union::Union;
+
+/// A trait to copy a value into a new [ValueCell], sharing the cells of its members.
+///
+/// It is implemented for any [Clone] value, see [crate::value::copy] for deep copies.
+pub trait CloneValue {
+    fn clone_value(&self) -> ValueCell;
+}
 
-pub trait DataValue {
+impl<T: DataValue + Clone + 'static> CloneValue for T {
+    fn clone_value(&self) -> ValueCell { Rc::new(RefCell::new(self.clone())) }
+}
+
+/// A value of a [Type], with its raw data.
+///
+/// [AsAny] and [CloneValue] are implemented for any `'static` [Clone] type, so a value is `Clone + 'static`.
+pub trait DataValue: AsAny + CloneValue {
     fn data_type(&self) -> Type;
 
     fn raw(&self) -> Vec<u8>;
@@ -21,6 +65,20 @@ pub trait DataValue {
     }
 }
 
+impl dyn DataValue {
+    /// Returns this value as a `T` if it is one.
+    pub fn downcast_ref<T: DataValue + 'static>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Returns this value as a mutable `T` if it is one.
+    pub fn downcast_mut<T: DataValue + 'static>(&mut self) -> Option<&mut T> {
+        self.as_any_mut().downcast_mut::<T>()
+    }
+
+    pub fn is<T: DataValue + 'static>(&self) -> bool { self.as_any().is::<T>() }
+}
+
 impl Debug for dyn DataValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}{:x?}", self.data_type(), self.raw())
@@ -29,7 +87,116 @@ impl Debug for dyn DataValue {
 
 pub type ValueCell = Rc<RefCell<dyn DataValue>>;
 
+/// Borrows a [ValueCell] as its concrete value.
+///
+/// Each helper returns [None] if the value is not of the requested struct, and panics like [RefCell::borrow]
+/// or [RefCell::borrow_mut] if the cell is already mutably borrowed.
+pub trait Downcast {
+    fn downcast<T: DataValue + 'static>(&self) -> Option<Ref<'_, T>>;
+
+    fn downcast_mut<T: DataValue + 'static>(&self) -> Option<RefMut<'_, T>>;
+
+    fn as_record(&self) -> Option<Ref<'_, Record>> { self.downcast() }
+
+    fn as_record_mut(&self) -> Option<RefMut<'_, Record>> { self.downcast_mut() }
+
+    fn as_union(&self) -> Option<Ref<'_, Union>> { self.downcast() }
+
+    fn as_union_mut(&self) -> Option<RefMut<'_, Union>> { self.downcast_mut() }
+
+    fn as_list(&self) -> Option<Ref<'_, List>> { self.downcast() }
+
+    fn as_list_mut(&self) -> Option<RefMut<'_, List>> { self.downcast_mut() }
+
+    fn as_sequence(&self) -> Option<Ref<'_, Sequence>> { self.downcast() }
+
+    fn as_sequence_mut(&self) -> Option<RefMut<'_, Sequence>> { self.downcast_mut() }
+
+    fn as_integer(&self) -> Option<Ref<'_, Integer>> { self.downcast() }
+
+    fn as_integer_mut(&self) -> Option<RefMut<'_, Integer>> { self.downcast_mut() }
+
+    fn as_float(&self) -> Option<Ref<'_, Float>> { self.downcast() }
+
+    fn as_float_mut(&self) -> Option<RefMut<'_, Float>> { self.downcast_mut() }
+
+    fn as_boolean(&self) -> Option<Ref<'_, Boolean>> { self.downcast() }
+
+    fn as_boolean_mut(&self) -> Option<RefMut<'_, Boolean>> { self.downcast_mut() }
+
+    fn as_bytes(&self) -> Option<Ref<'_, Bytes>> { self.downcast() }
+
+    fn as_bytes_mut(&self) -> Option<RefMut<'_, Bytes>> { self.downcast_mut() }
+}
+
+impl Downcast for ValueCell {
+    fn downcast<T: DataValue + 'static>(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.borrow(), |value| value.downcast_ref::<T>()).ok()
+    }
+
+    fn downcast_mut<T: DataValue + 'static>(&self) -> Option<RefMut<'_, T>> {
+        RefMut::filter_map(self.borrow_mut(), |value| value.downcast_mut::<T>()).ok()
+    }
+}
+
 pub trait GetDataValue<T>: DataValue {
     fn get(&self) -> T;
     fn from_raw(raw: &[u8]) -> Self;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::facade::create_struct;
+    use crate::identity::LabelBank;
+    use crate::types::primitive::Primitive;
+    use crate::value::boolean::Boolean;
+    use crate::value::byte::Bytes;
+    use crate::value::concept::Downcast;
+    use crate::value::convert::IntoValue;
+    use crate::value::list::List;
+    use crate::value::number::{Float, Integer};
+    use crate::value::record::Record;
+
+    #[test]
+    fn test_downcast_ref() {
+        let cell = Integer::new(40).to_cell();
+        assert!(cell.borrow().is::<Integer>());
+        assert!(!cell.borrow().is::<Float>());
+        assert_eq!(cell.borrow().downcast_ref::<Integer>().unwrap().value(), 40);
+        assert!(cell.borrow().downcast_ref::<Float>().is_none());
+        *cell.borrow_mut().downcast_mut::<Integer>().unwrap() = Integer::new(42);
+        assert_eq!(cell.borrow().raw(), 42i64.to_be_bytes());
+        assert!(cell.borrow_mut().downcast_mut::<Boolean>().is_none());
+    }
+
+    #[test]
+    fn test_helpers() {
+        let integer = Integer::new(1).to_cell();
+        assert!(integer.as_integer().is_some());
+        assert!(integer.as_float().is_none() && integer.as_record().is_none() && integer.as_list_mut().is_none());
+        assert_eq!(2.5f64.into_value().as_float().unwrap().value(), 2.5);
+        assert!(true.into_value().as_boolean().unwrap().get());
+        assert!(matches!(*7u8.into_value().as_bytes().unwrap(), Bytes::Byte(_)));
+        assert_eq!(Some(3i64).into_value().as_union().unwrap().tag(), 1);
+        assert_eq!((1u8, false).into_value().as_sequence().unwrap().values().len(), 2);
+
+        let integer_type = Primitive::Integer.to_rc();
+        let point = create_struct("Point", LabelBank::from(&["x", "y"]), &[integer_type.clone(), integer_type.clone()]);
+        let record = Record::new(point, &[Integer::new(1).to_cell(), Integer::new(2).to_cell()]).unwrap().to_cell();
+        let y = record.as_record().unwrap().get_field(1).unwrap();
+        *y.as_integer_mut().unwrap() = Integer::new(5);
+        assert_eq!(&record.borrow().raw()[8..], 5i64.to_be_bytes());
+
+        let list = List::empty(integer_type).to_cell();
+        list.as_list_mut().unwrap().push(Integer::new(9).to_cell()).unwrap();
+        assert_eq!(list.as_list().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_borrowed_cell() {
+        let cell = Integer::new(1).to_cell();
+        let _borrow = cell.as_integer_mut();
+        cell.as_integer();
+    }
+}
@@ -0,0 +1,453 @@
+//! Conversions between Rust values and [ValueCell]s.
+//!
+//! - [RoussillonType] gives the [Type] of a Rust type,
+//! - [IntoValue] converts a Rust value to a [ValueCell],
+//! - [FromValue] reads a Rust value back from a [ValueCell] or a raw buffer,
+//!   or fails with a [ConversionError] naming the Rust type.
+//!
+//! | Rust type                            | Type                                               | Value                    |
+//! |--------------------------------------|----------------------------------------------------|--------------------------|
+//! | `bool`                               | [Primitive::Boolean]                               | [Boolean]                |
+//! | `i64`                                | [Primitive::Integer]                               | [Integer]                |
+//! | `f64`                                | [Primitive::Float]                                 | [Float]                  |
+//! | `u8`                                 | [Primitive::Byte]                                  | [Bytes::Byte]            |
+//! | `u16`, `u32`, `u64`, `u128`, `usize` | [Primitive::Bytes]                                 | the other [Bytes]        |
+//! | `[T; N]`                             | [Primitive::List]                                  | [List]                   |
+//! | `Vec<T>`                             | a [Primitive::List] of its length                  | [List]                   |
+//! | `()`                                 | the unit [ProductType]                             | [ProductValue]           |
+//! | `(A,)` to `(A, B, ..., L)`           | [Tuple]                                            | [Sequence]               |
+//! | `Option<T>`                          | a `Core/Option<T>` [Enumeration], `None` or `Some` | [Union]                  |
+//!
+//! A `Vec<T>` has no [RoussillonType], its type depends on its length.
+//!
+//! With the `derive` feature, `#[derive(RoussillonType)]` implements them for structs and enums.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::types::concept::DataType;
+//! use roussillon_type_system::value::convert::{FromValue, IntoValue, RoussillonType};
+//! use roussillon_type_system::value::error::{ConversionError, TypeError};
+//!
+//! assert_eq!(<Option<[i64; 2]>>::data_type().typename(), "Core/Option<[integer;2]>");
+//! assert_eq!(<(bool, u16)>::data_type().typename(), "(boolean,word)");
+//!
+//! let value = Some([4i64, 2]).into_value();
+//! assert_eq!(<Option<[i64; 2]>>::from_value(&value).unwrap(), Some([4, 2]));
+//! assert!(matches!(bool::from_value(&value), Err(TypeError::ConversionError(ConversionError::Mismatch { into: "bool", .. }))));
+//!
+//! let value = vec![(1u8, 2.5f64), (3, -1.0)].into_value();
+//! assert_eq!(<Vec<(u8, f64)>>::from_value(&value).unwrap(), [(1, 2.5), (3, -1.0)]);
+//! ```
+
+use std::any::type_name;
+use std::rc::Rc;
+
+use crate::identity::LabelBank;
+use crate::types::algebraic::{read_tag, ProductType, SumType, TAG_SIZE};
+use crate::types::concept::{DataType, Type};
+use crate::types::primitive::Primitive;
+use crate::types::sequence::Tuple;
+use crate::types::typedef::{Enumeration, Structure};
+use crate::value::boolean::Boolean;
+use crate::value::byte::{Arch, Byte, Bytes, Long, Quad, Wide, Word};
+use crate::value::concept::ValueCell;
+use crate::value::error::{CanTypeError, ConversionError, TypeResult};
+use crate::value::list::List;
+use crate::value::number::{Float, Integer};
+use crate::value::record::ProductValue;
+use crate::value::sequence::Sequence;
+use crate::value::union::Union;
+
+/// A Rust type which has a Roussillon [Type].
+pub trait RoussillonType {
+    fn data_type() -> Type;
+}
+
+/// Converts a Rust value to a [ValueCell].
+pub trait IntoValue {
+    fn into_value(self) -> ValueCell;
+}
+
+/// Reads a Rust value from a value.
+pub trait FromValue: Sized {
+    /// Reads a raw value.
+    fn from_raw(raw: &[u8]) -> TypeResult<Self>;
+
+    /// Whether values of `data_type` can be read.
+    fn accepts(data_type: &Type) -> bool;
+
+    /// Reads a value, after checking its type with [FromValue::accepts].
+    fn from_value(value: &ValueCell) -> TypeResult<Self> {
+        let value = value.borrow();
+        let data_type = value.data_type();
+        if !Self::accepts(&data_type) {
+            return Err(ConversionError::Mismatch { into: type_name::<Self>(), provided: data_type }.promote());
+        }
+        Self::from_raw(&value.raw())
+    }
+}
+
+/// Whether `data_type` is the [RoussillonType::data_type] of `T`, see [same_definition].
+/// It is the [FromValue::accepts] of most types.
+pub fn has_type<T: RoussillonType>(data_type: &Type) -> bool {
+    same_definition(data_type, &T::data_type())
+}
+
+/// Whether two types have the same typename and, for the [Structure]s and [Enumeration]s they contain,
+/// the same labels and the same field or variant types.
+///
+/// Two definitions with the same identifier but different fields have the same typename : their values
+/// cannot be read as one another.
+pub fn same_definition(a: &Type, b: &Type) -> bool {
+    if a.typename() != b.typename() {
+        return false;
+    }
+    let same_labels = |a: &LabelBank, b: &LabelBank, count: usize| (0..count).all(|index| a.label(index) == b.label(index));
+    let same_members = |a: &[Type], b: &[Type]| a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_definition(a, b));
+    if let (Some(a), Some(b)) = (a.downcast_ref::<Structure>(), b.downcast_ref::<Structure>()) {
+        let (fields, other_fields) = (a.product_type.to_tuple(), b.product_type.to_tuple());
+        return same_labels(&a.labels, &b.labels, fields.len()) && same_members(&fields, &other_fields);
+    }
+    if let (Some(a), Some(b)) = (a.downcast_ref::<Enumeration>(), b.downcast_ref::<Enumeration>()) {
+        let (variants, other_variants) = (a.sum_type.to_tuple(), b.sum_type.to_tuple());
+        return same_labels(a.labels(), b.labels(), variants.len()) && same_members(&variants, &other_variants);
+    }
+    match (a.downcast_ref::<Primitive>(), b.downcast_ref::<Primitive>()) {
+        (Some(Primitive::List((a, _))), Some(Primitive::List((b, _)))) => return same_definition(a, b),
+        (Some(Primitive::Reference(a)), Some(Primitive::Reference(b))) => return same_definition(a, b),
+        _ => {}
+    }
+    let members = |data_type: &Type| data_type.downcast_ref::<Tuple>().cloned()
+        .or_else(|| data_type.downcast_ref::<ProductType>().map(ProductType::to_tuple))
+        .or_else(|| data_type.downcast_ref::<SumType>().map(SumType::to_tuple));
+    match (members(a), members(b)) {
+        (Some(a), Some(b)) => same_members(&a, &b),
+        _ => true,
+    }
+}
+
+/// Returns a [ConversionError::Size] error if `raw` is not `expected` bytes long.
+fn expect_raw<T>(expected: usize, raw: &[u8]) -> CanTypeError {
+    if raw.len() == expected {
+        Ok(())
+    } else {
+        Err(ConversionError::Size { into: type_name::<T>(), expected, provided: raw.len() }.promote())
+    }
+}
+
+/// Splits the raw value of a [ProductType] or a [Tuple] in the raw values of its `members`, to read a `T`.
+pub fn members_raw<'a, T>(raw: &'a [u8], members: &[Type]) -> TypeResult<Vec<&'a [u8]>> {
+    expect_raw::<T>(members.iter().map(|member| member.size()).sum(), raw)?;
+    let mut start = 0;
+    Ok(members.iter().map(|member| {
+        let end = start + member.size();
+        let member_raw = &raw[start..end];
+        start = end;
+        member_raw
+    }).collect())
+}
+
+/// Splits the raw value of an [Enumeration] in its tag and the raw value of its payload, to read a `T`.
+pub fn variant_raw<'a, T>(raw: &'a [u8], enumeration: &Rc<Enumeration>) -> TypeResult<(usize, &'a [u8])> {
+    expect_raw::<T>(enumeration.size(), raw)?;
+    let tag = read_tag(raw).unwrap_or(u64::MAX);
+    let variant = enumeration.variant(tag as usize)
+        .ok_or_else(|| ConversionError::Variant { into: type_name::<T>(), tag }.promote())?;
+    Ok((tag as usize, &raw[TAG_SIZE..TAG_SIZE + variant.size()]))
+}
+
+/// Reads `count` elements of `T` from the raw value of a [List].
+fn elements_raw<T: RoussillonType + FromValue>(raw: &[u8], count: usize) -> TypeResult<Vec<T>> {
+    let size = T::data_type().size();
+    expect_raw::<T>(size * count, raw)?;
+    (0..count).map(|index| T::from_raw(&raw[index * size..(index + 1) * size])).collect()
+}
+
+/// Implements the conversions of a number read from its big-endian raw value.
+macro_rules! number {
+    ($rust:ty, $data_type:expr, |$value:ident| $into:expr) => {
+        impl RoussillonType for $rust {
+            fn data_type() -> Type { $data_type.to_rc() }
+        }
+
+        impl IntoValue for $rust {
+            fn into_value(self) -> ValueCell {
+                let $value = self;
+                $into.to_cell()
+            }
+        }
+
+        impl FromValue for $rust {
+            fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+                expect_raw::<Self>(size_of::<$rust>(), raw)?;
+                Ok(<$rust>::from_be_bytes(raw.try_into().unwrap()))
+            }
+
+            fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+        }
+    };
+}
+
+number!(i64, Primitive::Integer, |value| Integer::new(value));
+number!(f64, Primitive::Float, |value| Float::new(value));
+number!(u8, Primitive::Byte, |value| Bytes::Byte(Byte::new(value)));
+number!(u16, Primitive::Bytes(2), |value| Bytes::Word(Word::new(value)));
+number!(u32, Primitive::Bytes(4), |value| Bytes::Quad(Quad::new(value)));
+number!(u64, Primitive::Bytes(8), |value| Bytes::Long(Long::new(value)));
+number!(u128, Primitive::Bytes(16), |value| Bytes::Wide(Wide::new(value)));
+number!(usize, Primitive::Bytes(Arch::size_of()), |value| Bytes::Arch(Arch::new(value)));
+
+impl RoussillonType for bool {
+    fn data_type() -> Type { Primitive::Boolean.to_rc() }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> ValueCell {
+        if self { Boolean::create_true() } else { Boolean::create_false() }.to_cell()
+    }
+}
+
+impl FromValue for bool {
+    fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+        expect_raw::<Self>(1, raw)?;
+        Ok(raw[0] != 0)
+    }
+
+    fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+}
+
+impl RoussillonType for () {
+    fn data_type() -> Type { ProductType::unit_type().to_rc() }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> ValueCell { ProductValue::new(ProductType::unit_type().to_rc(), &[]).unwrap().to_cell() }
+}
+
+impl FromValue for () {
+    fn from_raw(raw: &[u8]) -> TypeResult<Self> { expect_raw::<Self>(0, raw) }
+
+    fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+}
+
+impl<T: RoussillonType, const N: usize> RoussillonType for [T; N] {
+    fn data_type() -> Type { Primitive::list(T::data_type(), N).to_rc() }
+}
+
+impl<T: RoussillonType + IntoValue, const N: usize> IntoValue for [T; N] {
+    fn into_value(self) -> ValueCell { Vec::from(self).into_value() }
+}
+
+impl<T: RoussillonType + FromValue, const N: usize> FromValue for [T; N] {
+    fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+        Ok(elements_raw::<T>(raw, N)?.try_into().unwrap_or_else(|_| unreachable!("the list has N elements")))
+    }
+
+    fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+}
+
+impl<T: RoussillonType + IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> ValueCell {
+        let mut list = List::empty(T::data_type());
+        for element in self {
+            list.push(element.into_value()).expect("an element has the type of the list");
+        }
+        list.to_cell()
+    }
+}
+
+/// A `Vec<T>` reads a [List] of `T` of any length.
+impl<T: RoussillonType + FromValue> FromValue for Vec<T> {
+    /// Reads as many elements as `raw` holds, none if `T` has no size.
+    fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+        let size = T::data_type().size();
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        if !raw.len().is_multiple_of(size) {
+            return Err(ConversionError::Size { into: type_name::<Self>(), expected: raw.len() / size * size, provided: raw.len() }.promote());
+        }
+        elements_raw(raw, raw.len() / size)
+    }
+
+    fn accepts(data_type: &Type) -> bool {
+        matches!(data_type.downcast_ref::<Primitive>(), Some(Primitive::List((of_type, _))) if has_type::<T>(of_type))
+    }
+
+    /// Reads as many elements as the length of the list.
+    fn from_value(value: &ValueCell) -> TypeResult<Self> {
+        let value = value.borrow();
+        let data_type = value.data_type();
+        match data_type.downcast_ref::<Primitive>() {
+            Some(Primitive::List((of_type, len))) if has_type::<T>(of_type) => elements_raw(&value.raw(), *len),
+            _ => Err(ConversionError::Mismatch { into: type_name::<Self>(), provided: data_type.clone() }.promote()),
+        }
+    }
+}
+
+/// Implements the conversions of a Rust tuple, a [Sequence] of a [Tuple].
+macro_rules! tuple {
+    ($($member:ident $position:tt),+) => {
+        impl<$($member: RoussillonType),+> RoussillonType for ($($member,)+) {
+            fn data_type() -> Type { Rc::new(vec![$($member::data_type()),+] as Tuple) }
+        }
+
+        impl<$($member: RoussillonType + IntoValue),+> IntoValue for ($($member,)+) {
+            fn into_value(self) -> ValueCell {
+                Sequence::new(vec![$($member::data_type()),+], &[$(self.$position.into_value()),+])
+                    .expect("a member has its declared type")
+                    .to_cell()
+            }
+        }
+
+        impl<$($member: RoussillonType + FromValue),+> FromValue for ($($member,)+) {
+            fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+                let members = members_raw::<Self>(raw, &[$($member::data_type()),+])?;
+                Ok(($($member::from_raw(members[$position])?,)+))
+            }
+
+            fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+        }
+    };
+}
+
+tuple!(A 0);
+tuple!(A 0, B 1);
+tuple!(A 0, B 1, C 2);
+tuple!(A 0, B 1, C 2, D 3);
+tuple!(A 0, B 1, C 2, D 3, E 4);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+/// The `Core/Option<T>` enumeration of `Option<T>`.
+fn option_type<T: RoussillonType>() -> Rc<Enumeration> {
+    Enumeration::new(
+        &format!("Core/Option<{}>", T::data_type().typename()),
+        LabelBank::from(&["None", "Some"]),
+        SumType::new(&[<()>::data_type(), T::data_type()]),
+    ).to_rc()
+}
+
+impl<T: RoussillonType> RoussillonType for Option<T> {
+    fn data_type() -> Type { option_type::<T>() }
+}
+
+impl<T: RoussillonType + IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> ValueCell {
+        let union = match self {
+            None => Union::new(option_type::<T>(), 0, ().into_value()),
+            Some(value) => Union::new(option_type::<T>(), 1, value.into_value()),
+        };
+        union.expect("a variant has its declared type").to_cell()
+    }
+}
+
+impl<T: RoussillonType + FromValue> FromValue for Option<T> {
+    fn from_raw(raw: &[u8]) -> TypeResult<Self> {
+        match variant_raw::<Self>(raw, &option_type::<T>())? {
+            (0, _) => Ok(None),
+            (_, payload) => Ok(Some(T::from_raw(payload)?)),
+        }
+    }
+
+    fn accepts(data_type: &Type) -> bool { has_type::<Self>(data_type) }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::facade::create_struct;
+    use crate::identity::LabelBank;
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::types::sequence::Tuple;
+    use crate::value::convert::{has_type, same_definition, FromValue, IntoValue, RoussillonType};
+    use crate::value::error::{ConversionError, TypeError, TypeResult};
+
+    #[test]
+    fn test_same_definition() {
+        let float = Primitive::Float.to_rc();
+        let point = |labels: &[&str], members: &[Type]| -> Type { create_struct("Point", LabelBank::from(labels), members) };
+        let xy = point(&["x", "y"], &[float.clone(), float.clone()]);
+        let xz = point(&["x", "z"], &[float.clone(), float.clone()]);
+        let integer = point(&["x", "y"], &[float.clone(), Primitive::Integer.to_rc()]);
+        assert_eq!(xy.typename(), xz.typename());
+        assert!(same_definition(&xy, &point(&["x", "y"], &[float.clone(), float.clone()])));
+        assert!(!same_definition(&xy, &xz));
+        assert!(!same_definition(&xy, &integer));
+
+        let list = |of_type: &Type| Primitive::List((of_type.clone(), 2)).to_rc();
+        assert!(same_definition(&list(&xy), &list(&xy)));
+        assert!(!same_definition(&list(&xy), &list(&xz)));
+        let reference = |of_type: &Type| Primitive::Reference(of_type.clone()).to_rc();
+        assert!(!same_definition(&reference(&xy), &reference(&integer)));
+        let tuple = |member: &Type| -> Type { Rc::new(vec![float.clone(), member.clone()] as Tuple) };
+        assert!(same_definition(&tuple(&xy), &tuple(&xy)));
+        assert!(!same_definition(&tuple(&xy), &tuple(&xz)));
+
+        assert!(has_type::<f64>(&float));
+        assert!(!has_type::<(f64, f64)>(&xy));
+    }
+
+    fn round_trip<T: IntoValue + FromValue + RoussillonType + Clone + PartialEq + std::fmt::Debug>(value: T) {
+        let cell = value.clone().into_value();
+        assert_eq!(cell.borrow().data_type().typename(), T::data_type().typename());
+        assert_eq!(T::from_value(&cell).unwrap(), value);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        round_trip(true);
+        round_trip(-40i64);
+        round_trip(2.5f64);
+        round_trip(0xABu8);
+        round_trip(0xABCDu16);
+        round_trip(0xABCD_EF01u32);
+        round_trip(u64::MAX);
+        round_trip(u128::MAX - 1);
+        round_trip(usize::MAX);
+        round_trip(());
+        round_trip([1i64, 2, 3]);
+        round_trip((true, 4u8, -1.5f64));
+        round_trip((1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8, 12u8));
+        round_trip(Some([0u16; 2]));
+        round_trip(None::<i64>);
+        assert_eq!(Vec::<u32>::from_value(&vec![1u32, 2, 3].into_value()).unwrap(), vec![1, 2, 3]);
+        assert_eq!(Vec::<()>::from_raw(&[]).unwrap(), Vec::<()>::new());
+    }
+
+    #[test]
+    fn test_conversion_errors() {
+        let size = |result: TypeResult<()>| match result {
+            Err(TypeError::ConversionError(ConversionError::Size { expected, provided, .. })) => (expected, provided),
+            other => panic!("expected a size error, got {other:?}"),
+        };
+        assert_eq!(size(i64::from_raw(&[0; 4]).map(drop)), (8, 4));
+        assert_eq!(size(bool::from_raw(&[]).map(drop)), (1, 0));
+        assert_eq!(size(<[u16; 2]>::from_raw(&[0; 3]).map(drop)), (4, 3));
+        assert_eq!(size(Vec::<u32>::from_raw(&[0; 6]).map(drop)), (4, 6));
+        assert_eq!(size(Option::<u8>::from_raw(&[0; 8]).map(drop)), (9, 8));
+
+        let mut raw = [0u8; 9];
+        raw[7] = 5;
+        assert!(matches!(
+            Option::<u8>::from_raw(&raw),
+            Err(TypeError::ConversionError(ConversionError::Variant { tag: 5, .. }))
+        ));
+
+        let mismatch = |result: TypeResult<()>| matches!(result, Err(TypeError::ConversionError(ConversionError::Mismatch { .. })));
+        assert!(mismatch(i64::from_value(&2.0f64.into_value()).map(drop)));
+        assert!(mismatch(u32::from_value(&2u16.into_value()).map(drop)));
+        assert!(mismatch(<[u8; 2]>::from_value(&[1u8, 2, 3].into_value()).map(drop)));
+        assert!(mismatch(Vec::<u8>::from_value(&vec![1u16].into_value()).map(drop)));
+        assert!(mismatch(<(u8, bool)>::from_value(&(true, 1u8).into_value()).map(drop)));
+        assert!(mismatch(Option::<i64>::from_value(&Some(1u8).into_value()).map(drop)));
+    }
+}
@@ -0,0 +1,129 @@
+//! Copies of values, to implement the value semantics of a language.
+//!
+//! - [CloneCell::shallow_clone] copies a value into a new cell, sharing the cells of its members,
+//! - [CloneCell::deep_clone] copies a value and all its members into new cells,
+//!   without decoding raw data, so it also copies references and functions.
+//!   It returns the [crate::value::error::GraphError] of a value which contains itself or a borrowed cell.
+//!
+//! A [List] in copy-on-write mode is not copied by [CloneCell::deep_clone] :
+//! its copy shares the elements until one of the lists is written, see [List::with_copy_on_write].
+//!
+//! ## Example
+//!
+//! ```
+//! use std::rc::Rc;
+//! use roussillon_type_system::{record, structure};
+//! use roussillon_type_system::value::concept::Downcast;
+//! use roussillon_type_system::value::copy::CloneCell;
+//! use roussillon_type_system::value::list::List;
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::types::primitive::Primitive;
+//!
+//! let point = structure! { Point { x: integer, y: integer } };
+//! let origin = record!(point { x: 0, y: 0 }).unwrap().to_cell();
+//!
+//! let shallow = origin.shallow_clone();
+//! let deep = origin.deep_clone().unwrap();
+//! let x = origin.as_record().unwrap().field_from_name("x").unwrap();
+//! *x.as_integer_mut().unwrap() = Integer::new(1);
+//! assert_eq!(shallow.as_record().unwrap().field_from_name("x").unwrap().as_integer().unwrap().value(), 1);
+//! assert_eq!(deep.as_record().unwrap().field_from_name("x").unwrap().as_integer().unwrap().value(), 0);
+//!
+//! let mut list = List::empty(Primitive::Integer.to_rc()).with_copy_on_write();
+//! list.push(Integer::new(40).to_cell()).unwrap();
+//! let mut copy = list.clone();
+//! assert!(Rc::ptr_eq(&list[0], &copy[0]));
+//!
+//! *copy.item_mut(0).unwrap().as_integer_mut().unwrap() = Integer::new(42);
+//! assert_eq!(list[0].as_integer().unwrap().value(), 40);
+//! assert_eq!(copy[0].as_integer().unwrap().value(), 42);
+//! ```
+
+use crate::value::concept::ValueCell;
+use crate::value::error::TypeResult;
+use crate::value::graph::walk_graph;
+use crate::value::list::List;
+use crate::value::path::Path;
+use crate::value::visit::{members, rebuild, ValueVisitor};
+
+/// Copies a [ValueCell] into a new cell.
+pub trait CloneCell {
+    /// Copies the value into a new cell, sharing the cells of its members.
+    fn shallow_clone(&self) -> ValueCell;
+
+    /// Copies the value and all its members into new cells, once [walk_graph] found no cycle nor borrowed cell.
+    fn deep_clone(&self) -> TypeResult<ValueCell>;
+}
+
+/// Enters every member, to check a whole graph with [walk_graph].
+struct Everything;
+
+impl ValueVisitor for Everything {
+    fn enter(&mut self, _path: &Path, _value: &ValueCell) -> bool { true }
+}
+
+impl CloneCell for ValueCell {
+    fn shallow_clone(&self) -> ValueCell { self.borrow().clone_value() }
+
+    fn deep_clone(&self) -> TypeResult<ValueCell> {
+        walk_graph(self, &mut Everything)?;
+        copy(self)
+    }
+}
+
+/// The deep copy of a value of an acyclic graph.
+fn copy(value: &ValueCell) -> TypeResult<ValueCell> {
+    let copy_on_write = value.borrow().downcast_ref::<List>().is_some_and(List::is_copy_on_write);
+    let members = members(value);
+    if copy_on_write || members.is_empty() {
+        return Ok(value.shallow_clone());
+    }
+    let copies = members.iter().map(|(_, member)| copy(member)).collect::<TypeResult<_>>()?;
+    rebuild(value, copies)
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::enumeration;
+    use crate::value::concept::Downcast;
+    use crate::value::convert::{FromValue, IntoValue};
+    use crate::value::copy::CloneCell;
+    use crate::value::error::{GraphError, TypeError};
+    use crate::value::number::Integer;
+    use crate::value::union::Union;
+
+    #[test]
+    fn test_deep_clone() {
+        let nested = (1i64, [2i64, 3], Some(4u8)).into_value();
+        let copy = nested.deep_clone().unwrap();
+        let first = nested.as_sequence().unwrap().values()[0].clone();
+        *first.as_integer_mut().unwrap() = Integer::new(10);
+        assert_eq!(<(i64, [i64; 2], Option<u8>)>::from_value(&copy).unwrap(), (1, [2, 3], Some(4)));
+        assert!(!Rc::ptr_eq(&copy.as_sequence().unwrap().values()[1], &nested.as_sequence().unwrap().values()[1]));
+
+        let shallow = nested.shallow_clone();
+        assert!(Rc::ptr_eq(&shallow.as_sequence().unwrap().values()[1], &nested.as_sequence().unwrap().values()[1]));
+    }
+
+    #[test]
+    fn test_graph_errors() {
+        // The payload of `Next` is named `Link`, so a `Link` union can hold any `Link` union.
+        let end = enumeration! { Link { End: () } };
+        let link = enumeration! { Link { End: (), Next: end } };
+        let first = Union::new(link.clone(), 0, ().into_value()).unwrap().to_cell();
+        let second = Union::new(link, 0, ().into_value()).unwrap().to_cell();
+        first.as_union_mut().unwrap().set_cell(1, second.clone()).unwrap();
+        second.as_union_mut().unwrap().set_cell(1, first.clone()).unwrap();
+        assert!(matches!(first.deep_clone(), Err(TypeError::GraphError(GraphError::Cycle { .. }))));
+        second.as_union_mut().unwrap().set_cell(0, ().into_value()).unwrap();
+        assert!(first.deep_clone().is_ok());
+
+        let pair = (1u8, 2u8).into_value();
+        let member = pair.as_sequence().unwrap().values()[1].clone();
+        let borrow = member.borrow_mut();
+        assert!(matches!(pair.deep_clone(), Err(TypeError::GraphError(GraphError::Borrowed { path })) if path == "1"));
+        drop(borrow);
+    }
+}
@@ -0,0 +1,208 @@
+//! Decoding of raw data within resource limits.
+//!
+//! Raw data may come from untrusted sources. A [Decoder] enforces [DecodeLimits] while values are constructed :
+//! - the nesting depth of composite values,
+//! - the count of elements in composite values,
+//! - the estimated total allocation,
+//! - the length of the decoded buffers.
+//!
+//! Any [crate::types::concept::DataType::construct_from_raw] call uses the [DecodeLimits::default] limits.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::value::decode::{decode, DecodeLimits};
+//! use roussillon_type_system::value::error::{LimitError, TypeError};
+//!
+//! let big_list = Primitive::list(Primitive::Byte.to_rc(), 1024).to_rc();
+//! let limits = DecodeLimits { max_elements: 256, ..DecodeLimits::default() };
+//!
+//! let result = decode(&big_list, &[0; 1024], &limits);
+//! assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Elements { maximum: 256 }))));
+//! ```
+
+use std::mem::size_of;
+
+use crate::types::concept::Type;
+use crate::value::concept::ValueCell;
+use crate::value::error::{CanTypeError, LimitError, TypeResult};
+
+/// The limits applied by a [Decoder].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// The maximum nesting depth of composite values.
+    pub max_depth: usize,
+    /// The maximum count of elements in all composite values.
+    pub max_elements: usize,
+    /// The maximum estimated allocation in bytes.
+    pub max_allocation: usize,
+    /// The maximum length in bytes of a decoded buffer.
+    pub max_length: usize,
+}
+
+impl DecodeLimits {
+    /// Limits which never fail.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+            max_allocation: usize::MAX,
+            max_length: usize::MAX,
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_elements: 1 << 20,
+            max_allocation: 1 << 26,
+            max_length: 1 << 26,
+        }
+    }
+}
+
+/// Keeps track of the resources used while decoding a value.
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    limits: DecodeLimits,
+    depth: usize,
+    elements: usize,
+    allocation: usize,
+}
+
+impl Decoder {
+    pub fn new(limits: DecodeLimits) -> Self {
+        Self { limits, depth: 0, elements: 0, allocation: 0 }
+    }
+
+    pub fn limits(&self) -> &DecodeLimits { &self.limits }
+
+    /// The estimated allocation so far, in bytes.
+    pub fn allocation(&self) -> usize { self.allocation }
+
+    /// Checks the length of a buffer about to be decoded.
+    pub fn check_length(&self, raw: &[u8]) -> CanTypeError {
+        if raw.len() > self.limits.max_length {
+            return Err(LimitError::Length { maximum: self.limits.max_length, provided: raw.len() }.promote());
+        }
+        Ok(())
+    }
+
+    /// Enters a composite value of `count` elements encoded in `raw`.
+    ///
+    /// Must be followed by [Decoder::leave] once its elements are decoded.
+    pub fn enter(&mut self, raw: &[u8], count: usize) -> CanTypeError {
+        self.check_length(raw)?;
+        if self.depth >= self.limits.max_depth {
+            return Err(LimitError::Depth { maximum: self.limits.max_depth }.promote());
+        }
+        self.elements = self.elements.saturating_add(count);
+        if self.elements > self.limits.max_elements {
+            return Err(LimitError::Elements { maximum: self.limits.max_elements }.promote());
+        }
+        self.allocate(count.saturating_mul(size_of::<ValueCell>()))?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Leaves the current composite value.
+    pub fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Accounts for a scalar value encoded in `raw`.
+    pub fn leaf(&mut self, raw: &[u8]) -> CanTypeError {
+        self.check_length(raw)?;
+        self.allocate(raw.len())
+    }
+
+    fn allocate(&mut self, bytes: usize) -> CanTypeError {
+        self.allocation = self.allocation.saturating_add(bytes);
+        if self.allocation > self.limits.max_allocation {
+            return Err(LimitError::Allocation { maximum: self.limits.max_allocation }.promote());
+        }
+        Ok(())
+    }
+}
+
+impl Default for Decoder {
+    fn default() -> Self { Self::new(DecodeLimits::default()) }
+}
+
+/// Constructs a value of `data_type` from `raw` within the `limits`.
+pub fn decode(data_type: &Type, raw: &[u8], limits: &DecodeLimits) -> TypeResult<ValueCell> {
+    data_type.decode(raw, &mut Decoder::new(*limits))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::algebraic::ProductType;
+    use crate::types::concept::Type;
+    use crate::types::primitive::Primitive;
+    use crate::value::decode::{decode, DecodeLimits, Decoder};
+    use crate::value::error::{LimitError, TypeError};
+    use crate::value::list::List;
+    use crate::value::sequence::Sequence;
+
+    fn nested_list(depth: usize) -> Type {
+        (0..depth).fold(Primitive::Byte.to_rc(), |of_type, _| Primitive::list(of_type, 1).to_rc())
+    }
+
+    #[test]
+    fn test_depth() {
+        let limits = DecodeLimits { max_depth: 2, ..DecodeLimits::default() };
+        assert!(decode(&nested_list(2), &[0], &limits).is_ok());
+        let result = decode(&nested_list(3), &[0], &limits);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Depth { maximum: 2 }))));
+    }
+
+    #[test]
+    fn test_elements() {
+        let limits = DecodeLimits { max_elements: 4, ..DecodeLimits::default() };
+        let bytes = Primitive::list(Primitive::Byte.to_rc(), 4).to_rc();
+        assert!(decode(&bytes, &[0; 4], &limits).is_ok());
+        let nested = Primitive::list(bytes, 2).to_rc();
+        let result = decode(&nested, &[0; 8], &limits);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Elements { maximum: 4 }))));
+    }
+
+    #[test]
+    fn test_allocation() {
+        let limits = DecodeLimits { max_allocation: 64, ..DecodeLimits::default() };
+        assert!(decode(&Primitive::Integer.to_rc(), &[0; 8], &limits).is_ok());
+        let integers = Primitive::list(Primitive::Integer.to_rc(), 8).to_rc();
+        let result = decode(&integers, &[0; 64], &limits);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Allocation { maximum: 64 }))));
+    }
+
+    #[test]
+    fn test_allocation_overflow() {
+        let mut decoder = Decoder::new(DecodeLimits::unlimited());
+        let result = List::decode(ProductType::unit_type().to_rc(), usize::MAX / 4, &[], &mut decoder);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Allocation { maximum: usize::MAX }))));
+    }
+
+    #[test]
+    fn test_length() {
+        let limits = DecodeLimits { max_length: 4, ..DecodeLimits::default() };
+        assert!(decode(&Primitive::Bytes(4).to_rc(), &[0; 4], &limits).is_ok());
+        let result = decode(&Primitive::Integer.to_rc(), &[0; 8], &limits);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Length { maximum: 4, provided: 8 }))));
+    }
+
+    #[test]
+    fn test_parse_within_limits() {
+        let pair = vec![Primitive::Byte.to_rc(), Primitive::Byte.to_rc()];
+        let (sequence, rest) = Sequence::parse(&[1, 2, 3], pair.clone(), &mut Decoder::default()).unwrap();
+        assert_eq!(sequence.unwrap().values().len(), 2);
+        assert_eq!(rest, [3]);
+        assert!(Sequence::parse(&[1], pair.clone(), &mut Decoder::default()).unwrap().0.is_none());
+
+        let mut decoder = Decoder::new(DecodeLimits { max_elements: 1, ..DecodeLimits::default() });
+        let result = Sequence::parse(&[1, 2], pair, &mut decoder);
+        assert!(matches!(result, Err(TypeError::LimitExceeded(LimitError::Elements { maximum: 1 }))));
+    }
+}
@@ -0,0 +1,229 @@
+//! Structural differences between two values of the same type, as a [Patch] of [Change]s located with [Path]s.
+//!
+//! - [diff] compares two values member by member : fields, positions, list indexes and sum payloads,
+//! - [apply_patch] replays a [Patch] onto a value, checking the type of each change,
+//! - a [Patch] is rendered with a line per [Change], the values in their [crate::json::mapping] :
+//!   `path: old -> new`, or `path: #old_tag old -> #new_tag new` for a variant switch.
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::{enumeration, record, structure};
+//! use roussillon_type_system::value::diff::{apply_patch, diff};
+//!
+//! let status = enumeration! { Status { Draft: (), Published: integer } };
+//! let post = structure! { Post { title: [byte; 2], status: status, views: integer } };
+//!
+//! let before = record!(post { title: [104, 105], status: { Draft: [] }, views: 3 }).unwrap().to_cell();
+//! let after = record!(post { title: [104, 111], status: { Published: 2024 }, views: 3 }).unwrap().to_cell();
+//!
+//! let patch = diff(&before, &after).unwrap();
+//! assert_eq!(patch.to_string(), "title[1]: 105 -> 111\nstatus: #0 {\"Draft\":[]} -> #1 {\"Published\":2024}");
+//!
+//! apply_patch(&before, &patch).unwrap();
+//! assert!(diff(&before, &after).unwrap().is_empty());
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use crate::facade::copy_value;
+use crate::json::mapping::{to_hex, to_json};
+use crate::value::concept::ValueCell;
+use crate::value::error::{CanTypeError, TypeError, TypeResult};
+use crate::value::path::{Navigate, Path, Segment};
+use crate::value::visit::members;
+
+/// A change of the value at a [Path].
+///
+/// The old and new values are copies, taken when the change is computed.
+#[derive(Clone, Debug)]
+pub enum Change {
+    /// A value without members changes.
+    Value {
+        path: Path,
+        old: ValueCell,
+        new: ValueCell,
+    },
+    /// A sum value switches from the variant `old_tag` to `new_tag`, `old` and `new` are the whole sum values.
+    Variant {
+        path: Path,
+        old_tag: usize,
+        new_tag: usize,
+        old: ValueCell,
+        new: ValueCell,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &Path {
+        match self {
+            Change::Value { path, .. } | Change::Variant { path, .. } => path,
+        }
+    }
+
+    /// The value after the change.
+    pub fn new_value(&self) -> &ValueCell {
+        match self {
+            Change::Value { new, .. } | Change::Variant { new, .. } => new,
+        }
+    }
+}
+
+/// The [Change]s from a value to another, in the order of their paths.
+#[derive(Clone, Debug, Default)]
+pub struct Patch(Vec<Change>);
+
+impl Patch {
+    pub fn changes(&self) -> &[Change] { &self.0 }
+
+    pub fn len(&self) -> usize { self.0.len() }
+
+    pub fn is_empty(&self) -> bool { self.0.is_empty() }
+}
+
+/// Returns the [Patch] from `old` to `new`, which must have the same type.
+pub fn diff(old: &ValueCell, new: &ValueCell) -> TypeResult<Patch> {
+    let (old_type, new_type) = (old.borrow().data_type(), new.borrow().data_type());
+    if old_type.typename() != new_type.typename() {
+        return Err(TypeError::InvalidType { expected: old_type, provided: new_type });
+    }
+    let mut changes = Vec::new();
+    diff_at(&mut Path::root(), old, new, &mut changes)?;
+    Ok(Patch(changes))
+}
+
+fn diff_at(path: &mut Path, old: &ValueCell, new: &ValueCell, changes: &mut Vec<Change>) -> CanTypeError {
+    let (old_members, new_members) = (members(old), members(new));
+    if let ([(Segment::Tag(old_tag), _)], [(Segment::Tag(new_tag), _)]) = (old_members.as_slice(), new_members.as_slice()) {
+        if old_tag != new_tag {
+            changes.push(Change::Variant { path: path.clone(), old_tag: *old_tag, new_tag: *new_tag, old: copy(old)?, new: copy(new)? });
+            return Ok(());
+        }
+    }
+    if old_members.is_empty() {
+        if old.borrow().raw() != new.borrow().raw() {
+            changes.push(Change::Value { path: path.clone(), old: copy(old)?, new: copy(new)? });
+        }
+        return Ok(());
+    }
+    for ((segment, old_member), (_, new_member)) in old_members.into_iter().zip(new_members) {
+        path.push(segment);
+        diff_at(path, &old_member, &new_member, changes)?;
+        path.pop();
+    }
+    Ok(())
+}
+
+fn copy(value: &ValueCell) -> TypeResult<ValueCell> {
+    let data_type = value.borrow().data_type();
+    copy_value(data_type, value)
+}
+
+/// Replays the `patch` onto `value` with [Navigate::set], which checks the type of each change and the access of
+/// the fields it writes.
+///
+/// The changes before a failing change are applied.
+pub fn apply_patch(value: &ValueCell, patch: &Patch) -> CanTypeError {
+    for change in patch.changes() {
+        value.set(change.path(), change.new_value())?;
+    }
+    Ok(())
+}
+
+/// The [crate::json::mapping] of a value, or its raw value in hexadecimal if it has none.
+fn render(value: &ValueCell) -> String {
+    to_json(value).map(|json| json.to_string()).unwrap_or_else(|_| format!("0x{}", to_hex(&value.borrow().raw())))
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Value { path, old, new } => write!(f, "{}: {} -> {}", path, render(old), render(new)),
+            Change::Variant { path, old_tag, new_tag, old, new } => {
+                write!(f, "{}: #{} {} -> #{} {}", path, old_tag, render(old), new_tag, render(new))
+            }
+        }
+    }
+}
+
+impl Display for Patch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(Change::to_string).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::value::concept::Downcast;
+    use crate::value::convert::{FromValue, IntoValue};
+    use crate::value::diff::{apply_patch, diff, Change};
+    use crate::value::error::{PathError, TypeError};
+    use crate::value::number::Integer;
+    use crate::value::path::{Navigate, Path};
+    use crate::{enumeration, record, structure};
+
+    type Nested = (i64, [u8; 3], Option<(bool, i64)>);
+
+    #[test]
+    fn test_round_trip() {
+        let pairs: [(Nested, Nested); 4] = [
+            ((1, [1, 2, 3], Some((true, 5))), (2, [1, 9, 3], Some((false, 5)))),
+            ((1, [1, 2, 3], Some((true, 5))), (1, [0, 0, 0], None)),
+            ((1, [1, 2, 3], None), (1, [1, 2, 3], Some((true, -1)))),
+            ((7, [0, 0, 0], None), (7, [0, 0, 0], None)),
+        ];
+        for (old, new) in pairs {
+            let (old, new) = (old.into_value(), new.into_value());
+            let patch = diff(&old, &new).unwrap();
+            apply_patch(&old, &patch).unwrap();
+            assert_eq!(Nested::from_value(&old).unwrap(), Nested::from_value(&new).unwrap());
+            assert!(diff(&old, &new).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let old = (1i64, [1u8, 2, 3], Some((true, 5i64))).into_value();
+        let new = (2i64, [1u8, 9, 3], None::<(bool, i64)>).into_value();
+        let patch = diff(&old, &new).unwrap();
+        let paths: Vec<String> = patch.changes().iter().map(|change| change.path().to_string()).collect();
+        assert_eq!(paths, ["0", "1[1]", "2"]);
+        assert!(matches!(patch.changes()[2], Change::Variant { old_tag: 1, new_tag: 0, .. }));
+        assert_eq!(patch.changes()[0].to_string(), "0: 1 -> 2");
+        assert_eq!(diff(&1i64.into_value(), &2i64.into_value()).unwrap().to_string(), ".: 1 -> 2");
+        assert_eq!(diff(&old, &old).unwrap().to_string(), "");
+
+        *new.as_sequence().unwrap().values()[0].as_integer_mut().unwrap() = Integer::new(40);
+        apply_patch(&old, &patch).unwrap();
+        assert_eq!(old.as_sequence().unwrap().values()[0].as_integer().unwrap().value(), 2);
+    }
+
+    #[test]
+    fn test_variant_text() {
+        let status = enumeration! { Status { Draft: (), Published: integer } };
+        let post = structure! { Post { status: status, views: integer } };
+        let before = record!(post { status: { Draft: [] }, views: 3 }).unwrap().to_cell();
+        let after = record!(post { status: { Published: 2024 }, views: 4 }).unwrap().to_cell();
+        assert_eq!(
+            diff(&before, &after).unwrap().to_string(),
+            "status: #0 {\"Draft\":[]} -> #1 {\"Published\":2024}\nviews: 3 -> 4"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(diff(&1i64.into_value(), &1.0f64.into_value()), Err(TypeError::InvalidType { .. })));
+        assert!(matches!(diff(&[1u8].into_value(), &[1u8, 2].into_value()), Err(TypeError::InvalidType { .. })));
+
+        let patch = diff(&(1i64, 2i64).into_value(), &(3i64, 4i64).into_value()).unwrap();
+        let narrower = (0i64,).into_value();
+        let result = apply_patch(&narrower, &patch);
+        assert!(matches!(result, Err(TypeError::PathError(PathError::NoMember { path, .. })) if path == "1"));
+        assert_eq!(<(i64,)>::from_value(&narrower).unwrap(), (3,));
+
+        let mistyped = (0i64, 0.0f64).into_value();
+        assert!(matches!(apply_patch(&mistyped, &patch), Err(TypeError::PathError(PathError::Mismatch { .. }))));
+        assert_eq!(mistyped.get(&Path::parse("0").unwrap()).unwrap().as_integer().unwrap().value(), 3);
+    }
+}
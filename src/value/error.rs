@@ -1,4 +1,6 @@
 use crate::types::concept::Type;
+use crate::types::typedef::{FieldAccess, Visibility};
+use crate::value::validation::Violation;
 
 #[derive(Clone, Debug)]
 pub enum SumTypeError {
@@ -20,6 +22,181 @@ pub enum SequenceError {
     },
 }
 
+#[derive(Clone, Debug)]
+pub enum LimitError {
+    Depth {
+        maximum: usize,
+    },
+    Elements {
+        maximum: usize,
+    },
+    Allocation {
+        maximum: usize,
+    },
+    Length {
+        maximum: usize,
+        provided: usize,
+    },
+}
+
+/// Errors of the [crate::json] module, located with a JSON pointer.
+#[derive(Clone, Debug)]
+pub enum JsonError {
+    Syntax {
+        position: usize,
+        message: String,
+    },
+    Mismatch {
+        pointer: String,
+        expected: Type,
+        found: String,
+    },
+    MissingKey {
+        pointer: String,
+        key: String,
+    },
+    UnknownKey {
+        pointer: String,
+        key: String,
+    },
+    Unsupported {
+        pointer: String,
+        data_type: Type,
+    },
+    /// Two different definitions share the identifier `name`, so they cannot both be keyed by it in `$defs`.
+    Conflict {
+        pointer: String,
+        name: String,
+    },
+}
+
+/// Errors of the [crate::codegen] module.
+#[derive(Clone, Debug)]
+pub enum CodegenError {
+    /// The type cannot be declared, `name` is the name it would have had.
+    Unsupported {
+        name: String,
+        data_type: Type,
+    },
+}
+
+/// Errors of the labels of fields and variants, `typename` is the name of the structure or the enumeration.
+#[derive(Clone, Debug)]
+pub enum LabelError {
+    Unknown {
+        typename: String,
+        label: String,
+    },
+    /// A field without default value is not assigned.
+    Missing {
+        typename: String,
+        label: String,
+    },
+    /// A field is assigned twice.
+    Duplicate {
+        typename: String,
+        label: String,
+    },
+}
+
+/// Errors of the checked accessors of the fields of a [crate::value::record::Record].
+///
+/// Structures and accessors are written `space/name`.
+#[derive(Clone, Debug)]
+pub enum AccessError {
+    NoSuchField {
+        structure: String,
+        field: String,
+    },
+    /// The field is not visible from `accessor`.
+    Hidden {
+        structure: String,
+        field: String,
+        visibility: Visibility,
+        accessor: String,
+    },
+    ReadOnly {
+        structure: String,
+        field: String,
+    },
+}
+
+/// Errors of the conversions of values to Rust values, in [crate::value::convert].
+///
+/// `into` is the name of the Rust type.
+#[derive(Clone, Debug)]
+pub enum ConversionError {
+    /// The value does not have a type the Rust type can be read from.
+    Mismatch {
+        into: &'static str,
+        provided: Type,
+    },
+    /// The raw value does not have the size of a value of the Rust type.
+    Size {
+        into: &'static str,
+        expected: usize,
+        provided: usize,
+    },
+    /// The tag is not a variant of the Rust type.
+    Variant {
+        into: &'static str,
+        tag: u64,
+    },
+}
+
+/// Errors of the [crate::value::path] navigation.
+///
+/// `path` is the path up to the segment which failed, included.
+#[derive(Clone, Debug)]
+pub enum PathError {
+    Syntax {
+        position: usize,
+        message: String,
+    },
+    /// The value of type `typename` has no member at the last segment of `path`.
+    NoMember {
+        path: String,
+        typename: String,
+    },
+    /// The sum value has the `active` tag, not the one of the last segment of `path`.
+    InactiveTag {
+        path: String,
+        active: usize,
+    },
+    /// The value set at `path` does not have its type.
+    Mismatch {
+        path: String,
+        expected: Type,
+        provided: Type,
+    },
+    /// The field at the last segment of `path` is not public, or is read-only, so it cannot be written.
+    Restricted {
+        path: String,
+        access: FieldAccess,
+    },
+}
+
+/// Errors of the graphs of cells, in [crate::value::graph].
+///
+/// `path` is the path of the cell which failed.
+#[derive(Clone, Debug)]
+pub enum GraphError {
+    /// The cell at `path` is the cell at `ancestor`, so it contains itself.
+    Cycle {
+        path: String,
+        ancestor: String,
+    },
+    /// The cell at `path` is also reached at `first`.
+    Alias {
+        path: String,
+        first: String,
+    },
+    /// The cell at `path` is mutably borrowed.
+    Borrowed {
+        path: String,
+    },
+}
+
 #[derive(Clone, Debug)]
 pub enum TypeError {
     SumTypeError(SumTypeError),
@@ -28,6 +205,15 @@ pub enum TypeError {
         expected: Type,
         provided: Type
     },
+    InvalidRaw(Violation),
+    LimitExceeded(LimitError),
+    JsonError(JsonError),
+    CodegenError(CodegenError),
+    AccessError(AccessError),
+    LabelError(LabelError),
+    ConversionError(ConversionError),
+    PathError(PathError),
+    GraphError(GraphError),
     Message(String),
 }
 
@@ -43,4 +229,44 @@ impl SequenceError {
     pub fn promote(self) -> TypeError {
         TypeError::ProductTypeError(self)
     }
-}
\ No newline at end of file
+}
+impl LimitError {
+    pub fn promote(self) -> TypeError {
+        TypeError::LimitExceeded(self)
+    }
+}
+impl JsonError {
+    pub fn promote(self) -> TypeError {
+        TypeError::JsonError(self)
+    }
+}
+impl CodegenError {
+    pub fn promote(self) -> TypeError {
+        TypeError::CodegenError(self)
+    }
+}
+impl AccessError {
+    pub fn promote(self) -> TypeError {
+        TypeError::AccessError(self)
+    }
+}
+impl LabelError {
+    pub fn promote(self) -> TypeError {
+        TypeError::LabelError(self)
+    }
+}
+impl ConversionError {
+    pub fn promote(self) -> TypeError {
+        TypeError::ConversionError(self)
+    }
+}
+impl PathError {
+    pub fn promote(self) -> TypeError {
+        TypeError::PathError(self)
+    }
+}
+impl GraphError {
+    pub fn promote(self) -> TypeError {
+        TypeError::GraphError(self)
+    }
+}
@@ -0,0 +1,254 @@
+//! Aliasing and cycles in the graph of the cells of a value.
+//!
+//! The members of a value are [ValueCell]s, so the same cell can be a member of two values (an alias),
+//! or a member of itself (a cycle), and [crate::value::concept::DataValue::raw] would then recurse forever.
+//!
+//! - [walk_graph] calls a [ValueVisitor] like [crate::value::visit::walk_value],
+//!   but returns a [GraphError] on a cycle or a mutably borrowed cell instead of overflowing or panicking,
+//! - [aliases] lists the cells reached at two paths, and [check_unaliased] rejects them,
+//! - [check_disjoint] rejects values which share cells, like a [crate::value::list::List] with
+//!   [crate::value::list::List::with_alias_check], or [crate::value::record::Record::new_unaliased].
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::structure;
+//! use roussillon_type_system::value::error::{GraphError, TypeError};
+//! use roussillon_type_system::value::graph::{aliases, check_unaliased};
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::value::record::Record;
+//!
+//! let point = structure! { Point { x: integer, y: integer } };
+//! let zero = Integer::new(0).to_cell();
+//! let origin = Record::new(point.clone(), &[zero.clone(), zero.clone()]).unwrap().to_cell();
+//!
+//! let found = aliases(&origin).unwrap();
+//! assert_eq!((found[0].0.to_string(), found[0].1.to_string()), ("x".to_string(), "y".to_string()));
+//! assert!(matches!(check_unaliased(&origin), Err(TypeError::GraphError(GraphError::Alias { .. }))));
+//! assert!(Record::new_unaliased(point, &[zero.clone(), zero]).is_err());
+//! ```
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::value::concept::ValueCell;
+use crate::value::error::{CanTypeError, GraphError, TypeResult};
+use crate::value::path::{Path, Segment};
+use crate::value::visit::{members, ValueVisitor};
+
+/// The identity of a cell, its address.
+fn identity(value: &ValueCell) -> *const () { Rc::as_ptr(value).cast() }
+
+/// Calls `visitor` on `value` and its members, depth first, from the root [Path].
+///
+/// A cell is visited each time it is reached, but a cell reached within itself is a [GraphError::Cycle],
+/// and a mutably borrowed cell is a [GraphError::Borrowed] : the walk stops at the first error.
+pub fn walk_graph(value: &ValueCell, visitor: &mut impl ValueVisitor) -> CanTypeError {
+    walk_at(&mut Path::root(), value, &mut Vec::new(), visitor)
+}
+
+fn walk_at(path: &mut Path, value: &ValueCell, ancestors: &mut Vec<(*const (), Path)>, visitor: &mut impl ValueVisitor) -> CanTypeError {
+    if let Some((_, ancestor)) = ancestors.iter().find(|(cell, _)| *cell == identity(value)) {
+        return Err(GraphError::Cycle { path: path.to_string(), ancestor: ancestor.to_string() }.promote());
+    }
+    if value.try_borrow().is_err() {
+        return Err(GraphError::Borrowed { path: path.to_string() }.promote());
+    }
+    if visitor.enter(path, value) {
+        ancestors.push((identity(value), path.clone()));
+        for (segment, member) in members(value) {
+            path.push(segment);
+            walk_at(path, &member, ancestors, visitor)?;
+            path.pop();
+        }
+        ancestors.pop();
+    }
+    visitor.leave(path, value);
+    Ok(())
+}
+
+/// Records the first path of each cell, and the paths at which a cell is reached again.
+#[derive(Default)]
+struct Aliases {
+    first: HashMap<*const (), Path>,
+    found: Vec<(Path, Path)>,
+}
+
+impl ValueVisitor for Aliases {
+    fn enter(&mut self, path: &Path, value: &ValueCell) -> bool {
+        match self.first.get(&identity(value)) {
+            Some(first) => {
+                self.found.push((first.clone(), path.clone()));
+                false
+            }
+            None => {
+                self.first.insert(identity(value), path.clone());
+                true
+            }
+        }
+    }
+}
+
+impl Aliases {
+    fn check(self) -> CanTypeError {
+        match self.found.into_iter().next() {
+            Some((first, path)) => Err(GraphError::Alias { path: path.to_string(), first: first.to_string() }.promote()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The cells reached at two paths in `value`, as pairs of the first path and a later one.
+///
+/// The members of a cell reached again are not listed.
+pub fn aliases(value: &ValueCell) -> TypeResult<Vec<(Path, Path)>> {
+    let mut aliases = Aliases::default();
+    walk_graph(value, &mut aliases)?;
+    Ok(aliases.found)
+}
+
+/// Checks that no cell of `value` is reached at two paths, or within itself.
+pub fn check_unaliased(value: &ValueCell) -> CanTypeError {
+    let mut aliases = Aliases::default();
+    walk_graph(value, &mut aliases)?;
+    aliases.check()
+}
+
+/// Checks that no cell is reached at two paths in `values` and their members, the `i`-th value being at `[i]`.
+pub fn check_disjoint<'a>(values: impl IntoIterator<Item = &'a ValueCell>) -> CanTypeError {
+    let mut aliases = Aliases::default();
+    for (index, value) in values.into_iter().enumerate() {
+        walk_at(&mut Path::from(&[Segment::Index(index)][..]), value, &mut Vec::new(), &mut aliases)?;
+    }
+    aliases.check()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::primitive::Primitive;
+    use crate::value::concept::{Downcast, ValueCell};
+    use crate::value::convert::{IntoValue, RoussillonType};
+    use crate::value::error::{GraphError, TypeError};
+    use crate::value::graph::{aliases, check_disjoint, check_unaliased, walk_graph};
+    use crate::value::list::List;
+    use crate::value::number::Integer;
+    use crate::value::path::Path;
+    use crate::value::record::Record;
+    use crate::value::sequence::Sequence;
+    use crate::value::union::Union;
+    use crate::value::visit::ValueVisitor;
+    use crate::{enumeration, structure};
+
+    struct Count(usize);
+
+    impl ValueVisitor for Count {
+        fn enter(&mut self, _path: &Path, _value: &ValueCell) -> bool {
+            self.0 += 1;
+            true
+        }
+    }
+
+    fn pairs(value: &ValueCell) -> Vec<(String, String)> {
+        aliases(value).unwrap().iter().map(|(first, path)| (first.to_string(), path.to_string())).collect()
+    }
+
+    fn integers(values: &[ValueCell]) -> List {
+        let mut list = List::empty(Primitive::Integer.to_rc());
+        for value in values {
+            list.push(value.clone()).unwrap();
+        }
+        list
+    }
+
+    #[test]
+    fn test_cycles() {
+        // The payload of `Next` is named `Link`, so a `Link` union can hold any `Link` union.
+        let end = enumeration! { Link { End: () } };
+        let link = enumeration! { Link { End: (), Next: end } };
+        let first = Union::new(link.clone(), 0, ().into_value()).unwrap().to_cell();
+        let second = Union::new(link, 0, ().into_value()).unwrap().to_cell();
+        first.as_union_mut().unwrap().set_cell(1, second.clone()).unwrap();
+        second.as_union_mut().unwrap().set_cell(1, first.clone()).unwrap();
+
+        let cycle = |result: Result<(), TypeError>| match result {
+            Err(TypeError::GraphError(GraphError::Cycle { path, ancestor })) => (path, ancestor),
+            other => panic!("expected a cycle, got {other:?}"),
+        };
+        assert_eq!(cycle(walk_graph(&first, &mut Count(0))), ("#1#1".to_string(), ".".to_string()));
+        assert_eq!(cycle(check_unaliased(&second)), ("#1#1".to_string(), ".".to_string()));
+        assert_eq!(cycle(aliases(&first).map(drop)), ("#1#1".to_string(), ".".to_string()));
+
+        let pair = (1u8, 2u8).into_value();
+        let mut count = Count(0);
+        walk_graph(&pair, &mut count).unwrap();
+        assert_eq!(count.0, 3);
+
+        second.as_union_mut().unwrap().set_cell(0, ().into_value()).unwrap();
+        assert!(check_unaliased(&first).is_ok());
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let element = Integer::new(1).to_cell();
+        let list = integers(&[Integer::new(0).to_cell(), element.clone()]).to_cell();
+        let borrow = element.borrow_mut();
+        assert!(matches!(
+            walk_graph(&list, &mut Count(0)),
+            Err(TypeError::GraphError(GraphError::Borrowed { path })) if path == "[1]"
+        ));
+        drop(borrow);
+        assert!(walk_graph(&list, &mut Count(0)).is_ok());
+    }
+
+    #[test]
+    fn test_aliases() {
+        let shared = Integer::new(1).to_cell();
+        let list = integers(&[shared.clone(), Integer::new(2).to_cell(), shared.clone()]).to_cell();
+        assert_eq!(pairs(&list), [("[0]".to_string(), "[2]".to_string())]);
+        assert!(matches!(
+            check_unaliased(&list),
+            Err(TypeError::GraphError(GraphError::Alias { path, first })) if path == "[2]" && first == "[0]"
+        ));
+
+        let pair = (1i64, 2i64).into_value();
+        let pair_type = <(i64, i64)>::data_type();
+        let twice = Sequence::new(vec![pair_type.clone(), pair_type], &[pair.clone(), pair]).unwrap().to_cell();
+        assert_eq!(pairs(&twice), [("0".to_string(), "1".to_string())]);
+
+        assert!(pairs(&(1i64, [2i64, 3]).into_value()).is_empty());
+        assert!(check_unaliased(&(1i64, [2i64, 3]).into_value()).is_ok());
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let shared = Integer::new(1).to_cell();
+        let first = integers(&[Integer::new(0).to_cell(), shared.clone()]).to_cell();
+        let second = integers(&[shared]).to_cell();
+        assert!(matches!(
+            check_disjoint(&[first.clone(), second.clone()]),
+            Err(TypeError::GraphError(GraphError::Alias { path, first })) if path == "[1][0]" && first == "[0][1]"
+        ));
+        assert!(check_disjoint(&[first.clone(), 1i64.into_value()]).is_ok());
+        assert!(check_disjoint(&[first.clone(), first]).is_err());
+    }
+
+    #[test]
+    fn test_alias_checks() {
+        let shared = Integer::new(1).to_cell();
+        let mut list = integers(std::slice::from_ref(&shared)).with_alias_check();
+        assert!(list.checks_aliases());
+        assert!(matches!(list.push(shared.clone()), Err(TypeError::GraphError(GraphError::Alias { .. }))));
+        assert_eq!(list.len(), 1);
+        list.push(Integer::new(2).to_cell()).unwrap();
+        assert_eq!(list.len(), 2);
+        assert!(!integers(&[]).checks_aliases());
+
+        let point = structure! { Point { x: integer, y: integer } };
+        assert!(Record::new(point.clone(), &[shared.clone(), shared.clone()]).is_ok());
+        assert!(matches!(
+            Record::new_unaliased(point.clone(), &[shared.clone(), shared.clone()]),
+            Err(TypeError::GraphError(GraphError::Alias { path, first })) if path == "y" && first == "x"
+        ));
+        assert!(Record::new_unaliased(point, &[shared, Integer::new(2).to_cell()]).is_ok());
+    }
+}
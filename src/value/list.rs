@@ -2,22 +2,54 @@ use std::cell::RefCell;
 use std::ops::Index;
 use std::rc::Rc;
 
-use crate::types::concept::Type;
+use crate::types::concept::{expect_size, Type};
 use crate::types::primitive::Primitive;
 use crate::value::concept::{DataValue, ValueCell};
-use crate::value::error::CanTypeError;
+use crate::value::copy::CloneCell;
+use crate::value::decode::Decoder;
+use crate::value::error::{CanTypeError, LimitError, TypeResult};
+use crate::value::graph::check_disjoint;
 use crate::value::sequence::values_to_raw;
 
+/// A list of values of the same type.
+///
+/// A clone shares the cells of its elements. In copy-on-write mode, a clone also shares the storage of the elements
+/// until one of the lists is written, which then copies the elements deeply : clones behave as deep copies,
+/// at the cost of a single copy on the first write.
+/// The elements are written through [List::item_mut], [DataValue::set], [crate::value::path::Navigate::get_mut] or
+/// [crate::value::path::Navigate::set] : [List::item], [Index] and [crate::value::path::Navigate::get] hand out the
+/// shared elements, to be read only.
+///
+/// With an alias check, [List::push] rejects a value which shares cells with the list.
 #[derive(Clone, Debug)]
 pub struct List {
     of_type: Type,
-    elements: Vec<ValueCell>,
+    elements: Rc<Vec<ValueCell>>,
+    copy_on_write: bool,
+    alias_check: bool,
 }
 
 impl List {
     pub fn empty(of_type: Type) -> Self {
-        Self { of_type, elements: Vec::new() }
+        Self { of_type, elements: Rc::new(Vec::new()), copy_on_write: false, alias_check: false }
     }
+
+    /// Turns on the copy-on-write mode of this list.
+    pub fn with_copy_on_write(mut self) -> Self {
+        self.copy_on_write = true;
+        self
+    }
+    pub fn is_copy_on_write(&self) -> bool { self.copy_on_write }
+
+    /// Turns on the alias check of this list : [List::push] walks the elements with
+    /// [crate::value::graph::check_disjoint], so it takes a time proportional to the size of the list.
+    pub fn with_alias_check(mut self) -> Self {
+        self.alias_check = true;
+        self
+    }
+    pub fn checks_aliases(&self) -> bool { self.alias_check }
+
+    pub fn of_type(&self) -> &Type { &self.of_type }
     pub fn len(&self) -> usize {
         self.elements.len()
     }
@@ -26,26 +58,59 @@ impl List {
     }
     pub fn push(&mut self, value: ValueCell) -> CanTypeError {
         value.borrow().validate_type(&self.of_type)?;
-        self.elements.push(value);
+        if self.alias_check {
+            check_disjoint(self.elements.iter().chain([&value]))?;
+        }
+        self.elements_mut().push(value);
         Ok(())
     }
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
     
+    /// Returns the element at `index` to be read.
+    ///
+    /// In copy-on-write mode, the element may be shared with the clones of this list : write it through
+    /// [List::item_mut] instead.
     pub fn item(&self, index: usize) -> Option<ValueCell> {
         self.elements.get(index).cloned()
     }
 
-    pub fn from(of_type: Type, size: usize, raw: &[u8]) -> Self {
+    /// Returns the element at `index` to be written, after copying the shared elements in copy-on-write mode.
+    pub fn item_mut(&mut self, index: usize) -> Option<ValueCell> {
+        self.elements_mut().get(index).cloned()
+    }
+
+    /// The elements owned by this list, copied deeply first if they are shared in copy-on-write mode.
+    ///
+    /// An element which cannot be copied deeply, as it contains itself or a borrowed cell, is copied shallowly.
+    fn elements_mut(&mut self) -> &mut Vec<ValueCell> {
+        if self.copy_on_write && Rc::strong_count(&self.elements) > 1 {
+            let copies = self.elements.iter().map(|element| element.deep_clone().unwrap_or_else(|_| element.shallow_clone()));
+            self.elements = Rc::new(copies.collect());
+        }
+        Rc::make_mut(&mut self.elements)
+    }
+
+    pub fn from(of_type: Type, size: usize, raw: &[u8]) -> TypeResult<Self> {
+        Self::decode(of_type, size, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [List] of `size` elements from raw data, within the limits of the `decoder`.
+    pub fn decode(of_type: Type, size: usize, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
+        decoder.enter(raw, size)?;
+        let element_size = of_type.size();
+        expect_size(element_size.saturating_mul(size), raw)?;
         let mut elements = Vec::new();
+        elements.try_reserve_exact(size).map_err(|_| LimitError::Allocation { maximum: decoder.limits().max_allocation }.promote())?;
         for i in 0..size {
-            let raw_element = &raw[i * of_type.size()..(i + 1) * of_type.size()];
-            let element = of_type.construct_from_raw(raw_element).unwrap();
-            elements.push(element);
+            let raw_element = &raw[i * element_size..(i + 1) * element_size];
+            elements.push(of_type.decode(raw_element, decoder)?);
         }
-        List { of_type, elements }
+        decoder.leave();
+        Ok(List { of_type, elements: Rc::new(elements), copy_on_write: false, alias_check: false })
     }
 }
 
+/// Reads the element at an index, like [List::item].
 impl Index<usize> for List {
     type Output = ValueCell;
 
@@ -63,11 +128,98 @@ impl DataValue for List {
 
     fn set(&mut self, raw: &[u8]) {
         let mut pointer: usize = 0;
-        let size = self.data_type().size();
-        for i in self.elements.iter_mut() {
+        let size = self.of_type.size();
+        for i in self.elements_mut().iter() {
             let pointer_end = pointer + size;
             i.borrow_mut().set(&raw[pointer..pointer_end]);
             pointer = pointer_end;
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::types::primitive::Primitive;
+    use crate::value::concept::{Downcast, ValueCell};
+    use crate::value::convert::{FromValue, IntoValue, RoussillonType};
+    use crate::value::list::List;
+    use crate::value::number::Integer;
+    use crate::value::path::{Navigate, Path};
+
+    /// A copy-on-write list of integers, and a clone sharing its elements.
+    fn shared(values: &[i64]) -> (ValueCell, ValueCell) {
+        let mut list = List::empty(Primitive::Integer.to_rc()).with_copy_on_write();
+        for value in values {
+            list.push(value.into_value()).unwrap();
+        }
+        let clone = list.clone();
+        (list.to_cell(), clone.to_cell())
+    }
+
+    fn values(list: &ValueCell) -> Vec<i64> { Vec::<i64>::from_value(list).unwrap() }
+
+    fn write(cell: &ValueCell, value: i64) { *cell.as_integer_mut().unwrap() = Integer::new(value) }
+
+    #[test]
+    fn test_copy_on_write() {
+        let (list, clone) = shared(&[1, 2]);
+        let element = clone.as_list_mut().unwrap().item_mut(0).unwrap();
+        write(&element, 10);
+        assert_eq!((values(&list), values(&clone)), (vec![1, 2], vec![10, 2]));
+
+        let (list, clone) = shared(&[1, 2]);
+        write(&clone.get_mut(&Path::parse("[1]").unwrap()).unwrap(), 20);
+        assert_eq!((values(&list), values(&clone)), (vec![1, 2], vec![1, 20]));
+
+        let (list, clone) = shared(&[1, 2]);
+        clone.set(&Path::parse("[0]").unwrap(), &30i64.into_value()).unwrap();
+        assert_eq!((values(&list), values(&clone)), (vec![1, 2], vec![30, 2]));
+
+        let (list, clone) = shared(&[1, 2]);
+        clone.borrow_mut().set(&[3i64.to_be_bytes(), 4i64.to_be_bytes()].concat());
+        assert_eq!((values(&list), values(&clone)), (vec![1, 2], vec![3, 4]));
+
+        let (list, clone) = shared(&[1, 2]);
+        clone.as_list_mut().unwrap().push(5i64.into_value()).unwrap();
+        write(&clone.as_list_mut().unwrap().item_mut(0).unwrap(), 6);
+        assert_eq!((values(&list), values(&clone)), (vec![1, 2], vec![6, 2, 5]));
+
+        let (list, clone) = shared(&[1, 2]);
+        write(&list.get_mut(&Path::parse("[0]").unwrap()).unwrap(), 7);
+        assert_eq!((values(&list), values(&clone)), (vec![7, 2], vec![1, 2]));
+    }
+
+    #[test]
+    fn test_nested_copy_on_write() {
+        let mut rows = List::empty(<[i64; 2]>::data_type()).with_copy_on_write();
+        rows.push([1i64, 2].into_value()).unwrap();
+        let clone = rows.clone().to_cell();
+        let rows = rows.to_cell();
+        write(&clone.get_mut(&Path::parse("[0][1]").unwrap()).unwrap(), 9);
+        assert_eq!(Vec::<[i64; 2]>::from_value(&rows).unwrap(), [[1, 2]]);
+        assert_eq!(Vec::<[i64; 2]>::from_value(&clone).unwrap(), [[1, 9]]);
+    }
+
+    #[test]
+    fn test_shared_reads() {
+        let (list, clone) = shared(&[1, 2]);
+        let borrowed = clone.as_list().unwrap();
+        let read = clone.get(&Path::parse("[0]").unwrap()).unwrap();
+        assert!(Rc::ptr_eq(&read, &borrowed[0]) && Rc::ptr_eq(&read, &list.as_list().unwrap()[0]));
+        drop(borrowed);
+
+        assert!(Rc::ptr_eq(&list.as_list().unwrap().item(0).unwrap(), &clone.as_list().unwrap()[0]));
+        let element = clone.as_list_mut().unwrap().item_mut(0).unwrap();
+        assert!(!Rc::ptr_eq(&list.as_list().unwrap()[0], &element));
+        assert!(Rc::ptr_eq(&clone.as_list().unwrap().item(0).unwrap(), &element));
+
+        let mut plain = List::empty(Primitive::Integer.to_rc());
+        plain.push(1i64.into_value()).unwrap();
+        let plain_clone = plain.clone().to_cell();
+        let plain = plain.to_cell();
+        write(&plain_clone.get_mut(&Path::parse("[0]").unwrap()).unwrap(), 8);
+        assert_eq!(values(&plain), [8]);
+    }
+}
@@ -19,7 +19,7 @@ impl Float {
 
     pub fn from(raw: &[u8]) -> Self { Self::new(f64::from_be_bytes(raw.try_into().unwrap_or_default())) }
 
-    pub fn parse_float(input: &[u8]) -> Parsed<Self> {
+    pub fn parse_float(input: &[u8]) -> Parsed<'_, Self> {
         let (Some(raw), rest) = parse_slice(input, 8) else { return (None, input); };
         (Some(Self::from(raw)), rest)
     }
@@ -52,7 +52,7 @@ impl Integer {
 
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
     pub fn from(raw: &[u8]) -> Self { Self::new(i64::from_be_bytes(raw.try_into().unwrap_or_default())) }
-    pub fn parse(input: &[u8]) -> Parsed<Self> {
+    pub fn parse(input: &[u8]) -> Parsed<'_, Self> {
         let (Some(raw), rest) = parse_slice(input, 8) else { return (None, input); };
         (Some(Self::from(raw)), rest)
     }
@@ -0,0 +1,457 @@
+//! A [Path] locates a value nested in another value.
+//!
+//! Paths are rendered as `field.items[3].0#1` where :
+//! - `.field` is a labelled field of a [crate::value::record::Record],
+//! - `.0` is a member of a [crate::value::sequence::Sequence] or a [crate::value::record::ProductValue],
+//! - `[3]` is an element of a [crate::value::list::List],
+//! - `#1` is the payload of a [crate::value::union::SumValue] with the tag `1`.
+//!
+//! A [Path] is parsed from this syntax with [Path::parse], and [Navigate] gets, sets and lists the nested values
+//! of any [ValueCell]. Like the raw data of a value, paths read every field of a record, but [Navigate::set] only
+//! writes through the public and writable fields, following their [crate::types::typedef::FieldAccess].
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::{record, structure};
+//! use roussillon_type_system::value::concept::Downcast;
+//! use roussillon_type_system::value::error::{PathError, TypeError};
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::value::path::{Navigate, Path};
+//!
+//! let point = structure! { Point { x: integer, tags: [integer; 2] } };
+//! let shape = structure! { Shape { origin: point, closed: boolean } };
+//! let square = record!(shape { origin: { x: 0, tags: [1, 2] }, closed: true }).unwrap().to_cell();
+//!
+//! let tag = Path::parse("origin.tags[1]").unwrap();
+//! assert_eq!(square.get(&tag).unwrap().as_integer().unwrap().value(), 2);
+//!
+//! square.set(&tag, &Integer::new(4).to_cell()).unwrap();
+//! assert_eq!(square.get(&tag).unwrap().as_integer().unwrap().value(), 4);
+//!
+//! let missing = square.get(&Path::parse("origin.tags[2]").unwrap());
+//! assert!(matches!(missing, Err(TypeError::PathError(PathError::NoMember { path, .. })) if path == "origin.tags[2]"));
+//!
+//! let paths: Vec<String> = square.paths().iter().map(Path::to_string).collect();
+//! assert_eq!(paths, ["origin", "origin.x", "origin.tags", "origin.tags[0]", "origin.tags[1]", "closed"]);
+//! ```
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::identity::{Label, Labelled};
+use crate::value::concept::ValueCell;
+use crate::types::typedef::Visibility;
+use crate::value::error::{CanTypeError, PathError, TypeError, TypeResult};
+use crate::value::list::List;
+use crate::value::record::{ProductValue, Record};
+use crate::value::sequence::Sequence;
+use crate::value::union::{SumValue, Union};
+use crate::value::visit::{walk_value, ValueVisitor};
+
+/// One step of a [Path].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Segment {
+    /// A labelled field : `.name`.
+    Field(Label),
+    /// A member of a tuple or product at a position : `.0`.
+    Position(usize),
+    /// An element of a list : `[3]`.
+    Index(usize),
+    /// The payload of a sum value for a tag : `#1`.
+    Tag(usize),
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Segment::Field(label) => write!(f, ".{}", label),
+            Segment::Position(position) => write!(f, ".{}", position),
+            Segment::Index(index) => write!(f, "[{}]", index),
+            Segment::Tag(tag) => write!(f, "#{}", tag),
+        }
+    }
+}
+
+/// A sequence of [Segment] from a root value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Path(Vec<Segment>);
+
+impl Path {
+    /// The path of the root value itself.
+    pub fn root() -> Self { Self(Vec::new()) }
+
+    pub fn is_root(&self) -> bool { self.0.is_empty() }
+
+    pub fn segments(&self) -> &[Segment] { &self.0 }
+
+    pub fn push(&mut self, segment: Segment) { self.0.push(segment) }
+
+    pub fn pop(&mut self) -> Option<Segment> { self.0.pop() }
+
+    /// Returns a new [Path] with the `segment` appended.
+    pub fn join(&self, segment: Segment) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+}
+
+impl From<&[Segment]> for Path {
+    fn from(segments: &[Segment]) -> Self { Self(segments.to_vec()) }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_root() {
+            return write!(f, ".");
+        }
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                Segment::Field(label) if index == 0 => write!(f, "{}", label)?,
+                Segment::Position(position) if index == 0 => write!(f, "{}", position)?,
+                _ => write!(f, "{}", segment)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Path {
+    /// Parses a path written like `field.items[3].0#1`, or `.` for the root.
+    ///
+    /// The first field or position may be written without its leading dot.
+    pub fn parse(input: &str) -> TypeResult<Self> {
+        let mut path = Self::root();
+        if input == "." {
+            return Ok(path);
+        }
+        let bytes = input.as_bytes();
+        let mut position = 0;
+        while position < bytes.len() {
+            let start = position;
+            let segment = match bytes[position] {
+                b'.' => {
+                    let name = word(input, position + 1);
+                    position += 1 + name.len();
+                    member_segment(name, start)?
+                }
+                b'[' => {
+                    let index = word(input, position + 1);
+                    position += 1 + index.len();
+                    if bytes.get(position) != Some(&b']') {
+                        return Err(syntax(position, "expected `]`"));
+                    }
+                    position += 1;
+                    Segment::Index(number(index, start)?)
+                }
+                b'#' => {
+                    let tag = word(input, position + 1);
+                    position += 1 + tag.len();
+                    Segment::Tag(number(tag, start)?)
+                }
+                _ if start == 0 => {
+                    let name = word(input, 0);
+                    position += name.len();
+                    member_segment(name, start)?
+                }
+                _ => return Err(syntax(position, "expected `.`, `[` or `#`")),
+            };
+            path.push(segment);
+        }
+        Ok(path)
+    }
+}
+
+impl FromStr for Path {
+    type Err = TypeError;
+
+    fn from_str(input: &str) -> TypeResult<Self> { Self::parse(input) }
+}
+
+/// The identifier characters of `input` from `start`.
+fn word(input: &str, start: usize) -> &str {
+    let rest = &input[start..];
+    let end = rest.find(|character: char| !(character.is_alphanumeric() || character == '_')).unwrap_or(rest.len());
+    &rest[..end]
+}
+
+fn syntax(position: usize, message: &str) -> TypeError {
+    PathError::Syntax { position, message: message.to_string() }.promote()
+}
+
+fn number(digits: &str, position: usize) -> TypeResult<usize> {
+    digits.parse().map_err(|_| syntax(position, "expected a number"))
+}
+
+/// A [Segment::Position] if `name` is a number, a [Segment::Field] otherwise.
+fn member_segment(name: &str, position: usize) -> TypeResult<Segment> {
+    if name.is_empty() {
+        Err(syntax(position, "expected a field or a position"))
+    } else if name.bytes().all(|byte| byte.is_ascii_digit()) {
+        Ok(Segment::Position(number(name, position)?))
+    } else {
+        Ok(Segment::Field(Label::new(name)))
+    }
+}
+
+/// Navigates the nested values of a [ValueCell] with [Path]s.
+pub trait Navigate {
+    /// Returns the value at `path`, to be read : the element of a copy-on-write list may be shared with its clones.
+    fn get(&self, path: &Path) -> TypeResult<ValueCell>;
+
+    /// Returns the value at `path`, to be written.
+    ///
+    /// A list element is taken with [List::item_mut], so that the element of a copy-on-write list is not shared with
+    /// its clones. Each field of a record on the way must be public and writable, see [PathError::Restricted].
+    fn get_mut(&self, path: &Path) -> TypeResult<ValueCell>;
+
+    /// Sets the value at `path` to a copy of `value`, which must have the type of the value at `path`,
+    /// through [Navigate::get_mut].
+    fn set(&self, path: &Path, value: &ValueCell) -> TypeResult<()>;
+
+    /// The paths of all the nested values, depth first, without the root.
+    fn paths(&self) -> Vec<Path>;
+}
+
+impl Navigate for ValueCell {
+    fn get(&self, path: &Path) -> TypeResult<ValueCell> {
+        let mut current = self.clone();
+        for (depth, segment) in path.segments().iter().enumerate() {
+            current = member(&current, segment, &Path::from(&path.segments()[..=depth]))?;
+        }
+        Ok(current)
+    }
+
+    fn get_mut(&self, path: &Path) -> TypeResult<ValueCell> {
+        let mut current = self.clone();
+        for (depth, segment) in path.segments().iter().enumerate() {
+            let prefix = Path::from(&path.segments()[..=depth]);
+            check_writable(&current, segment, &prefix)?;
+            current = member_mut(&current, segment, &prefix)?;
+        }
+        Ok(current)
+    }
+
+    fn set(&self, path: &Path, value: &ValueCell) -> TypeResult<()> {
+        let target = self.get_mut(path)?;
+        let expected = target.borrow().data_type();
+        let provided = value.borrow().data_type();
+        if expected.typename() != provided.typename() {
+            return Err(PathError::Mismatch { path: path.to_string(), expected, provided }.promote());
+        }
+        let raw = value.borrow().raw();
+        target.borrow_mut().set(&raw);
+        Ok(())
+    }
+
+    fn paths(&self) -> Vec<Path> {
+        struct Paths(Vec<Path>);
+
+        impl ValueVisitor for Paths {
+            fn enter(&mut self, path: &Path, _value: &ValueCell) -> bool {
+                if !path.is_root() {
+                    self.0.push(path.clone());
+                }
+                true
+            }
+        }
+
+        let mut paths = Paths(Vec::new());
+        walk_value(self, &mut paths);
+        paths.0
+    }
+}
+
+/// Checks that the field of a record at the last `segment` of `path` is public and writable.
+fn check_writable(value: &ValueCell, segment: &Segment, path: &Path) -> CanTypeError {
+    let borrowed = value.borrow();
+    let Some(record) = borrowed.downcast_ref::<Record>() else { return Ok(()) };
+    let structure = record.structure();
+    let index = match segment {
+        Segment::Field(label) => structure.labels.labelled(label),
+        Segment::Position(position) => Some(*position),
+        _ => None,
+    };
+    match index.map(|index| structure.access(index)) {
+        Some(access) if access.visibility != Visibility::Public || access.read_only => {
+            Err(PathError::Restricted { path: path.to_string(), access }.promote())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The member of `value` at the last `segment` of `path`, to be written : a list element is taken with
+/// [List::item_mut], so that a copy-on-write list does not write the elements it shares.
+fn member_mut(value: &ValueCell, segment: &Segment, path: &Path) -> TypeResult<ValueCell> {
+    if let Segment::Index(index) = segment {
+        let element = value.borrow_mut().downcast_mut::<List>().and_then(|list| list.item_mut(*index));
+        if let Some(element) = element {
+            return Ok(element);
+        }
+    }
+    member(value, segment, path)
+}
+
+/// The member of `value` at the last `segment` of `path`.
+fn member(value: &ValueCell, segment: &Segment, path: &Path) -> TypeResult<ValueCell> {
+    let borrowed = value.borrow();
+    let no_member = || PathError::NoMember { path: path.to_string(), typename: borrowed.data_type().typename() }.promote();
+    let found = match segment {
+        Segment::Field(label) => borrowed.downcast_ref::<Record>().and_then(|record| {
+            record.as_sequence().values().get(record.structure().labels.labelled(label)?).cloned()
+        }),
+        Segment::Position(position) => {
+            if let Some(record) = borrowed.downcast_ref::<Record>() {
+                record.as_sequence().values().get(*position).cloned()
+            } else if let Some(product) = borrowed.downcast_ref::<ProductValue>() {
+                product.as_sequence().values().get(*position).cloned()
+            } else {
+                borrowed.downcast_ref::<Sequence>().and_then(|sequence| sequence.values().get(*position).cloned())
+            }
+        }
+        Segment::Index(index) => borrowed.downcast_ref::<List>().and_then(|list| list.item(*index)),
+        Segment::Tag(tag) => {
+            let active = if let Some(union) = borrowed.downcast_ref::<Union>() {
+                Some((union.tag(), union.current_value().clone()))
+            } else {
+                borrowed.downcast_ref::<SumValue>().map(|sum| (sum.tag(), sum.current_value().clone()))
+            };
+            match active {
+                Some((active, payload)) if active == *tag => Some(payload),
+                Some((active, _)) => return Err(PathError::InactiveTag { path: path.to_string(), active }.promote()),
+                None => None,
+            }
+        }
+    };
+    found.ok_or_else(no_member)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::identity::Label;
+    use crate::types::typedef::{FieldAccess, Visibility};
+    use crate::value::concept::{Downcast, ValueCell};
+    use crate::value::convert::{FromValue, IntoValue};
+    use crate::value::error::{PathError, TypeError};
+    use crate::value::path::{Navigate, Path, Segment};
+    use crate::{record, structure};
+
+    fn syntax(input: &str) -> (usize, String) {
+        match Path::parse(input) {
+            Err(TypeError::PathError(PathError::Syntax { position, message })) => (position, message),
+            other => panic!("expected a syntax error in {input:?}, got {other:?}"),
+        }
+    }
+
+    fn path(input: &str) -> Path { input.parse().unwrap() }
+
+    fn shape() -> ValueCell {
+        let point = structure! { Point { x: integer, tags: [integer; 2] } };
+        let shape = structure! { Shape { origin: point, closed: boolean } };
+        record!(shape { origin: { x: 0, tags: [1, 2] }, closed: true }).unwrap().to_cell()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert!(path(".").is_root());
+        assert_eq!(path(".").to_string(), ".");
+        for input in ["origin.tags[3].0#1", "0.1", "items[0][1]", "value#0.x"] {
+            assert_eq!(path(input).to_string(), input);
+        }
+        assert_eq!(path(".x"), path("x"));
+        assert_eq!(
+            path("a.1[2]#3").segments(),
+            [Segment::Field(Label::new("a")), Segment::Position(1), Segment::Index(2), Segment::Tag(3)]
+        );
+
+        assert_eq!(syntax("a..b"), (1, "expected a field or a position".to_string()));
+        assert_eq!(syntax("a[1"), (3, "expected `]`".to_string()));
+        assert_eq!(syntax("a[x]"), (1, "expected a number".to_string()));
+        assert_eq!(syntax("a#"), (1, "expected a number".to_string()));
+        assert_eq!(syntax("a b"), (1, "expected `.`, `[` or `#`".to_string()));
+        assert_eq!(syntax("-"), (0, "expected a field or a position".to_string()));
+    }
+
+    #[test]
+    fn test_get() {
+        let shape = shape();
+        assert_eq!(shape.get(&path("origin.tags[1]")).unwrap().as_integer().unwrap().value(), 2);
+        assert_eq!(shape.get(&path("0.1[0]")).unwrap().as_integer().unwrap().value(), 1);
+        assert!(shape.get(&path(".")).unwrap().as_record().is_some());
+
+        let no_member = |cell: &ValueCell, input: &str| match cell.get(&path(input)) {
+            Err(TypeError::PathError(PathError::NoMember { path, typename })) => (path, typename),
+            other => panic!("expected no member at {input:?}, got {other:?}"),
+        };
+        assert_eq!(no_member(&shape, "origin.y"), ("origin.y".to_string(), "Point".to_string()));
+        assert_eq!(no_member(&shape, "origin.x.y"), ("origin.x.y".to_string(), "integer".to_string()));
+        assert_eq!(no_member(&shape, "origin[0]"), ("origin[0]".to_string(), "Point".to_string()));
+        assert_eq!(no_member(&shape, "2").0, "2");
+        assert_eq!(no_member(&shape, "closed#0").1, "boolean");
+
+        let option = (Some(3u8), None::<u8>).into_value();
+        assert_eq!(option.get(&path("0#1")).unwrap().borrow().raw(), [3]);
+        assert!(matches!(
+            option.get(&path("1#1.x")),
+            Err(TypeError::PathError(PathError::InactiveTag { path, active: 0 })) if path == "1#1"
+        ));
+    }
+
+    #[test]
+    fn test_set() {
+        let shape = shape();
+        shape.set(&path("origin.x"), &7i64.into_value()).unwrap();
+        shape.set(&path("origin.tags"), &[8i64, 9].into_value()).unwrap();
+        assert_eq!(<[i64; 2]>::from_value(&shape.get(&path("origin.tags")).unwrap()).unwrap(), [8, 9]);
+        assert_eq!(shape.get(&path("origin.x")).unwrap().as_integer().unwrap().value(), 7);
+
+        let mismatch = |input: &str, value: &ValueCell| match shape.set(&path(input), value) {
+            Err(TypeError::PathError(PathError::Mismatch { path, expected, provided })) => (path, expected.typename(), provided.typename()),
+            other => panic!("expected a mismatch at {input:?}, got {other:?}"),
+        };
+        assert_eq!(mismatch("origin.x", &1.5f64.into_value()), ("origin.x".to_string(), "integer".to_string(), "float".to_string()));
+        assert_eq!(mismatch("origin.tags", &[1i64].into_value()).1, "[integer;2]");
+        assert_eq!(mismatch("origin.tags[0]", &true.into_value()).2, "boolean");
+        assert_eq!(mismatch(".", &1i64.into_value()).1, "Shape");
+        assert!(matches!(shape.set(&path("origin.z"), &1i64.into_value()), Err(TypeError::PathError(PathError::NoMember { .. }))));
+        assert_eq!(shape.get(&path("origin.x")).unwrap().as_integer().unwrap().value(), 7);
+
+        let option = Some(3u8).into_value();
+        option.set(&path("#1"), &4u8.into_value()).unwrap();
+        assert_eq!(Option::<u8>::from_value(&option).unwrap(), Some(4));
+        assert!(matches!(option.set(&path("#0"), &().into_value()), Err(TypeError::PathError(PathError::InactiveTag { active: 1, .. }))));
+    }
+
+    #[test]
+    fn test_restricted_set() {
+        let point = structure! { Point { x: integer, y: integer, z: integer } }.as_ref().clone()
+            .with_access("x", FieldAccess::new(Visibility::Public).read_only()).unwrap()
+            .with_access("y", FieldAccess::new(Visibility::Private)).unwrap()
+            .to_rc();
+        let shape = structure! { Shape { origin: point, closed: boolean } }.as_ref().clone()
+            .with_access("closed", FieldAccess::new(Visibility::Public).read_only()).unwrap()
+            .to_rc();
+        let square = record!(shape { origin: { x: 1, y: 2, z: 3 }, closed: true }).unwrap().to_cell();
+
+        let restricted = |input: &str| match square.set(&path(input), &9i64.into_value()) {
+            Err(TypeError::PathError(PathError::Restricted { path, access })) => (path, access),
+            other => panic!("expected a restricted field at {input:?}, got {other:?}"),
+        };
+        assert_eq!(restricted("origin.x"), ("origin.x".to_string(), FieldAccess::new(Visibility::Public).read_only()));
+        assert_eq!(restricted("origin.1"), ("origin.1".to_string(), FieldAccess::new(Visibility::Private)));
+        assert_eq!(restricted("closed").0, "closed");
+        assert_eq!(square.get(&path("origin.x")).unwrap().as_integer().unwrap().value(), 1);
+        assert_eq!(square.get(&path("origin.y")).unwrap().as_integer().unwrap().value(), 2);
+
+        square.set(&path("origin.z"), &9i64.into_value()).unwrap();
+        assert_eq!(square.get(&path("origin.z")).unwrap().as_integer().unwrap().value(), 9);
+    }
+
+    #[test]
+    fn test_paths() {
+        let paths: Vec<String> = (1u8, Some([2u8])).into_value().paths().iter().map(Path::to_string).collect();
+        assert_eq!(paths, ["0", "1", "1#1", "1#1[0]"]);
+        assert!(1i64.into_value().paths().is_empty());
+    }
+}
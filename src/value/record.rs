@@ -1,13 +1,17 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::identity::{Label, Labelled};
+use crate::facade::copy_value;
+use crate::identity::{Identifier, Label, Labelled};
 
-use crate::types::concept::Type;
+use crate::types::concept::{DataType, Type};
 use crate::types::algebraic::ProductType;
-use crate::types::typedef::Structure;
+use crate::types::typedef::{Structure, Visibility};
 
 use crate::value::concept::{DataValue, ValueCell};
-use crate::value::error::TypeResult;
+use crate::value::copy::CloneCell;
+use crate::value::decode::Decoder;
+use crate::value::error::{AccessError, CanTypeError, LabelError, TypeResult};
+use crate::value::graph::check_unaliased;
 use crate::value::sequence::Sequence;
 
 #[derive(Clone, Debug)]
@@ -22,25 +26,25 @@ impl ProductValue {
         Ok(ProductValue { product, value: Sequence::new(sequence, values)? })
     }
 
+    pub fn product(&self) -> &Rc<ProductType> { &self.product }
+
     pub fn to_sequence(self) -> Sequence { self.value }
 
     pub fn clone_sequence(&self) -> Sequence { self.value.clone() }
 
     pub fn as_sequence(&self) -> &Sequence { &self.value }
 
-    pub fn get_element(&self, nth: usize) -> Option<ValueCell> {
-        let fields = self.value.get();
-        if nth > fields.len() {
-            None
-        } else {
-            Some(fields[nth].clone())
-        }
-    }
+    pub fn get_element(&self, nth: usize) -> Option<ValueCell> { self.value.get().get(nth).cloned() }
 
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
 
     pub fn from(product: Rc<ProductType>, raw: &[u8]) -> TypeResult<Self> {
-        Ok(ProductValue { product: product.clone(), value: Sequence::from(product.to_tuple(), raw)? })
+        Self::decode(product, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [ProductValue] from raw data, within the limits of the `decoder`.
+    pub fn decode(product: Rc<ProductType>, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
+        Ok(ProductValue { product: product.clone(), value: Sequence::decode(product.to_tuple(), raw, decoder)? })
     }
 }
 
@@ -68,34 +72,190 @@ impl Record {
         })
     }
 
+    /// Returns a new [Record] like [Record::new], if no cell is reached twice in its fields,
+    /// see [crate::value::graph::check_unaliased].
+    pub fn new_unaliased(structure_type: Rc<Structure>, values: &[ValueCell]) -> TypeResult<Self> {
+        let record = Self::new(structure_type, values)?;
+        check_unaliased(&record.clone().to_cell())?;
+        Ok(record)
+    }
+
+    /// Returns a new [Record] from the values of some fields, by label.
+    ///
+    /// The fields left out take their default value, and must have one set with [Structure::with_default].
+    pub fn from_required(structure_type: Rc<Structure>, values: &[(&str, ValueCell)]) -> TypeResult<Self> {
+        let mut builder = RecordBuilder::new(&structure_type);
+        for (label, value) in values {
+            builder = builder.set(label, value.clone())?;
+        }
+        builder.build()
+    }
+
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
 
-    pub fn to_sequence(self) -> Sequence { self.value.to_sequence() }
+    pub fn structure(&self) -> &Rc<Structure> { &self.of_type }
 
-    pub fn clone_sequence(&self) -> Sequence { self.value.clone().to_sequence() }
+    /// Returns the fields as a [Sequence], see [Record::clone_sequence].
+    pub fn to_sequence(self) -> Sequence { self.clone_sequence() }
+
+    /// Returns the fields as a [Sequence] : the public writable fields are shared,
+    /// the read-only and non-public ones are copies, so they cannot be mutated through the result.
+    pub fn clone_sequence(&self) -> Sequence {
+        let values = (0..self.value.as_sequence().values().len())
+            .map(|field| self.guarded_field(field))
+            .collect::<Vec<_>>();
+        Sequence::new(self.value.as_sequence().definition().clone(), &values)
+            .expect("the copies of the fields have the types of the fields")
+    }
 
-    pub fn as_sequence(&self) -> &Sequence { self.value.as_sequence() }
+    /// The live cells of the fields, whatever their access : for the traversals of the crate.
+    pub(crate) fn as_sequence(&self) -> &Sequence { self.value.as_sequence() }
 
-    pub fn get_field(&self, field: usize) -> Option<ValueCell> { self.value.get_element(field) }
+    /// The field at `field`, or a copy of it if it is read-only or not public.
+    fn guarded_field(&self, field: usize) -> ValueCell {
+        let value = self.value.get_element(field).unwrap();
+        let access = self.of_type.access(field);
+        if access.visibility == Visibility::Public && !access.read_only {
+            return value;
+        }
+        copy_value(self.of_type.product_type.field(field).unwrap(), &value).unwrap_or_else(|_| value.shallow_clone())
+    }
 
+    /// Returns the field at `field` if it is public, see [Record::get_field_as] for the other fields.
+    ///
+    /// A read-only field is returned as a copy, so it cannot be mutated through the result.
+    pub fn get_field(&self, field: usize) -> Option<ValueCell> {
+        if self.of_type.access(field).visibility != Visibility::Public {
+            return None;
+        }
+        self.readable_field(field).ok()
+    }
+
+    /// Returns the field labelled `field` if it is public, see [Record::get_field].
     pub fn field_from_name(&self, field: &str) -> Option<ValueCell> { self.labelled(&Label::new(field)) }
 
+    /// Returns the field at `field` if it is visible from `accessor`.
+    ///
+    /// A read-only field is returned as a copy, so it cannot be mutated through the result.
+    pub fn get_field_as(&self, field: usize, accessor: &Identifier) -> TypeResult<ValueCell> {
+        self.of_type.check_read(field, accessor)?;
+        self.readable_field(field)
+    }
+
+    /// The field at `field`, or a copy of it if it is read-only.
+    fn readable_field(&self, field: usize) -> TypeResult<ValueCell> {
+        let value = self.value.get_element(field)
+            .ok_or_else(|| AccessError::NoSuchField { structure: self.of_type.typename(), field: field.to_string() }.promote())?;
+        if self.of_type.access(field).read_only {
+            copy_value(self.of_type.product_type.field(field).unwrap(), &value)
+        } else {
+            Ok(value)
+        }
+    }
+
+    /// Returns the field labelled `field` if it is visible from `accessor`, see [Record::get_field_as].
+    pub fn field_from_name_as(&self, field: &str, accessor: &Identifier) -> TypeResult<ValueCell> {
+        self.get_field_as(self.of_type.field_index(field)?, accessor)
+    }
+
+    /// Sets the field at `field` to `value` if it is visible from `accessor` and not read-only.
+    pub fn set_field(&mut self, field: usize, value: &ValueCell, accessor: &Identifier) -> CanTypeError {
+        self.of_type.check_write(field, accessor)?;
+        value.borrow().validate_type(&self.of_type.product_type.field(field).unwrap())?;
+        let raw = value.borrow().raw();
+        self.value.get_element(field).unwrap().borrow_mut().set(&raw);
+        Ok(())
+    }
+
+    /// Sets the field labelled `field`, see [Record::set_field].
+    pub fn set_field_from_name(&mut self, field: &str, value: &ValueCell, accessor: &Identifier) -> CanTypeError {
+        self.set_field(self.of_type.field_index(field)?, value, accessor)
+    }
+
     pub fn from(structure_type: Rc<Structure>, raw: &[u8]) -> TypeResult<Self> {
+        Self::decode(structure_type, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [Record] from raw data, within the limits of the `decoder`.
+    pub fn decode(structure_type: Rc<Structure>, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
         let product = structure_type.product_type.clone().to_rc();
         Ok(
             Self {
                 of_type: structure_type,
-                value: ProductValue::from(product, raw)?, 
+                value: ProductValue::decode(product, raw, decoder)?,
             }
         )
     }
 }
 
+/// Builds a [Record] field by field, by label.
+///
+/// The fields which are not set take their default value, if they have one set with [Structure::with_default].
+///
+/// ```
+/// use roussillon_type_system::facade::create_struct;
+/// use roussillon_type_system::identity::LabelBank;
+/// use roussillon_type_system::types::primitive::Primitive;
+/// use roussillon_type_system::value::number::{Float, Integer};
+/// use roussillon_type_system::value::record::RecordBuilder;
+///
+/// let my_struct = create_struct("MyStruct", LabelBank::from(&["field_a", "field_b"]), &[
+///     Primitive::Integer.to_rc(),
+///     Primitive::Float.to_rc(),
+/// ]);
+/// let record = RecordBuilder::new(&my_struct)
+///     .set("field_b", Float::new(1.5).to_cell()).unwrap()
+///     .set("field_a", Integer::new(1).to_cell()).unwrap()
+///     .build().unwrap();
+/// assert_eq!(record.field_from_name("field_a").unwrap().borrow().raw(), 1i64.to_be_bytes());
+///
+/// assert!(RecordBuilder::new(&my_struct).set("field_c", Integer::new(1).to_cell()).is_err());
+/// assert!(RecordBuilder::new(&my_struct).set("field_b", Float::new(1.5).to_cell()).unwrap().build().is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct RecordBuilder {
+    structure: Rc<Structure>,
+    values: Vec<Option<ValueCell>>,
+}
+
+impl RecordBuilder {
+    pub fn new(structure: &Rc<Structure>) -> Self {
+        Self { structure: structure.clone(), values: vec![None; structure.product_type.to_tuple().len()] }
+    }
+
+    /// Sets the field labelled `label`, which must not be set yet.
+    pub fn set(mut self, label: &str, value: ValueCell) -> TypeResult<Self> {
+        let index = self.structure.field_index(label)?;
+        if self.values[index].is_some() {
+            return Err(LabelError::Duplicate { typename: self.structure.typename(), label: label.to_string() }.promote());
+        }
+        value.borrow().validate_type(&self.structure.product_type.field(index).unwrap())?;
+        self.values[index] = Some(value);
+        Ok(self)
+    }
+
+    pub fn build(self) -> TypeResult<Record> {
+        let mut values = Vec::with_capacity(self.values.len());
+        for (index, value) in self.values.into_iter().enumerate() {
+            values.push(match value {
+                Some(value) => value,
+                None if self.structure.has_default(index) => {
+                    self.structure.product_type.field(index).unwrap().construct_from_raw(&self.structure.field_default_raw(index).unwrap())?
+                }
+                None => return Err(LabelError::Missing { typename: self.structure.typename(), label: self.structure.field_name(index) }.promote()),
+            });
+        }
+        Record::new(self.structure, &values)
+    }
+}
+
 impl DataValue for Record {
     fn data_type(&self) -> Type { self.of_type.clone() }
 
     fn raw(&self) -> Vec<u8> { self.value.raw() }
 
+    /// Sets every field from raw data, like decoding : the access of the fields is not checked,
+    /// see [Record::set_field] for a checked write.
     fn set(&mut self, raw: &[u8]) {
         self.value.set(raw);
     }
@@ -106,3 +266,114 @@ impl Labelled<ValueCell> for Record {
         self.get_field(self.of_type.labels.labelled(label)?)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::identity::{Identifier, LabelBank};
+    use crate::types::algebraic::ProductType;
+    use crate::types::primitive::Primitive;
+    use crate::types::typedef::{FieldAccess, Structure, Visibility};
+    use crate::value::concept::{DataValue, Downcast};
+    use crate::value::error::{AccessError, LabelError, TypeError};
+    use crate::value::number::{Float, Integer};
+    use crate::value::record::Record;
+
+    fn account() -> Rc<Structure> {
+        Structure::new("Bank/Account", LabelBank::from(&["id", "balance", "pin", "owner"]), ProductType::new(&[
+            Primitive::Integer.to_rc(),
+            Primitive::Integer.to_rc(),
+            Primitive::Integer.to_rc(),
+            Primitive::Integer.to_rc(),
+        ]))
+            .with_access("id", FieldAccess::new(Visibility::Public).read_only()).unwrap()
+            .with_access("balance", FieldAccess::new(Visibility::Namespace)).unwrap()
+            .with_access("pin", FieldAccess::new(Visibility::Private)).unwrap()
+            .to_rc()
+    }
+
+    fn record() -> Record {
+        let values: Vec<_> = (1..=4).map(|value| Integer::new(value).to_cell()).collect();
+        Record::new(account(), &values).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_access() {
+        let structure = Structure::new("Bank/Account", LabelBank::from(&["id"]), ProductType::new(&[Primitive::Integer.to_rc()]));
+        let Err(TypeError::LabelError(LabelError::Unknown { typename, label })) = structure.with_access("pin", FieldAccess::default()) else {
+            panic!("an unknown label has no access rules");
+        };
+        assert_eq!((typename.as_str(), label.as_str()), ("Bank/Account", "pin"));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let owner = Identifier::new("Bank/Account");
+        let bank = Identifier::new("Bank/transfer");
+        let shop = Identifier::new("Shop/checkout");
+        assert!(Visibility::Public.allows(&owner, &shop));
+        assert!(Visibility::Namespace.allows(&owner, &bank));
+        assert!(!Visibility::Namespace.allows(&owner, &shop));
+        assert!(Visibility::Private.allows(&owner, &owner));
+        assert!(!Visibility::Private.allows(&owner, &bank));
+    }
+
+    #[test]
+    fn test_checked_reads() {
+        let record = record();
+        let shop = Identifier::new("Shop/checkout");
+        let bank = Identifier::new("Bank/transfer");
+        let owner = Identifier::new("Bank/Account");
+        assert_eq!(record.field_from_name_as("balance", &bank).unwrap().as_integer().unwrap().value(), 2);
+        assert_eq!(record.field_from_name_as("pin", &owner).unwrap().as_integer().unwrap().value(), 3);
+        let Err(TypeError::AccessError(AccessError::Hidden { field, visibility, accessor, .. })) = record.field_from_name_as("balance", &shop) else {
+            panic!("a namespace field is hidden from other namespaces");
+        };
+        assert_eq!((field.as_str(), visibility, accessor.as_str()), ("balance", Visibility::Namespace, "Shop/checkout"));
+        assert!(matches!(record.field_from_name_as("pin", &bank), Err(TypeError::AccessError(AccessError::Hidden { .. }))));
+        assert!(matches!(record.get_field_as(4, &owner), Err(TypeError::AccessError(AccessError::NoSuchField { field, .. })) if field == "4"));
+        assert!(matches!(record.field_from_name_as("name", &owner), Err(TypeError::LabelError(LabelError::Unknown { .. }))));
+    }
+
+    #[test]
+    fn test_unchecked_reads() {
+        let record = record();
+        assert!(record.get_field(1).is_none());
+        assert!(record.field_from_name("pin").is_none());
+        assert!(record.get_field(4).is_none());
+        record.field_from_name("owner").unwrap().borrow_mut().set(&10i64.to_be_bytes());
+        assert_eq!(record.get_field(3).unwrap().as_integer().unwrap().value(), 10);
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut record = record();
+        let owner = Identifier::new("Bank/Account");
+        for id in [record.field_from_name("id").unwrap(), record.field_from_name_as("id", &owner).unwrap()] {
+            id.borrow_mut().set(&5i64.to_be_bytes());
+            assert_eq!(record.raw()[..8], 1i64.to_be_bytes());
+        }
+        let Err(TypeError::AccessError(AccessError::ReadOnly { structure, field })) = record.set_field_from_name("id", &Integer::new(5).to_cell(), &owner) else {
+            panic!("a read-only field cannot be set");
+        };
+        assert_eq!((structure.as_str(), field.as_str()), ("Bank/Account", "id"));
+        assert!(matches!(record.set_field_from_name("pin", &Integer::new(5).to_cell(), &Identifier::new("Bank/transfer")), Err(TypeError::AccessError(AccessError::Hidden { .. }))));
+        assert!(matches!(record.set_field_from_name("pin", &Float::new(5.0).to_cell(), &owner), Err(TypeError::InvalidType { .. })));
+        record.set_field_from_name("pin", &Integer::new(5).to_cell(), &owner).unwrap();
+        assert_eq!(record.raw()[16..24], 5i64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_guarded_sequences() {
+        let record = record();
+        for sequence in [record.clone_sequence(), record.clone().to_sequence()] {
+            for value in sequence.values() {
+                value.borrow_mut().set(&9i64.to_be_bytes());
+            }
+        }
+        let raw = record.raw();
+        let fields: Vec<_> = raw.chunks(8).map(|field| i64::from_be_bytes(field.try_into().unwrap())).collect();
+        assert_eq!(fields, vec![1, 2, 3, 9]);
+    }
+}
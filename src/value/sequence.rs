@@ -3,9 +3,10 @@ use std::fmt::Debug;
 use std::rc::Rc;
 use crate::parse::{parse_slice, Parsed};
 
-use crate::types::concept::{DataType, Type};
+use crate::types::concept::{expect_size, DataType, Type};
 use crate::types::sequence::Tuple;
 use crate::value::concept::{DataValue, ValueCell};
+use crate::value::decode::Decoder;
 use crate::value::error::{SequenceError, TypeResult};
 
 #[derive(Clone, Debug)]
@@ -25,7 +26,7 @@ impl Sequence {
     
     pub fn new(definition: Tuple, values: &[ValueCell]) -> TypeResult<Self> {
         if definition.len() != values.len() {
-            return Err(SequenceError::SequenceLengthMismatch { expected: 0, provided: 0 }.promote());
+            return Err(SequenceError::SequenceLengthMismatch { expected: definition.len(), provided: values.len() }.promote());
         }
         for (index, expected) in definition.iter().enumerate() {
             values[index].borrow().validate_type(expected)?;
@@ -33,6 +34,8 @@ impl Sequence {
         Ok(Self { definition, values: values.to_vec() })
     }
 
+    pub fn definition(&self) -> &Tuple { &self.definition }
+
     pub fn values(&self) -> &[ValueCell] {
         &self.values
     }
@@ -43,25 +46,31 @@ impl Sequence {
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
 
     pub fn from(definition: Tuple, raw: &[u8]) -> TypeResult<Self> {
-        let mut values = Vec::new();
+        Self::decode(definition, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [Sequence] from a [Tuple] scheme and raw data, within the limits of the `decoder`.
+    pub fn decode(definition: Tuple, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
+        decoder.enter(raw, definition.len())?;
+        expect_size(definition.size(), raw)?;
+        let mut values = Vec::with_capacity(definition.len());
         let mut start = 0;
         let mut end = 0;
         for t in definition.iter() {
             end += t.size();
-            values.push(t.construct_from_raw(&raw[start..end])?);
+            values.push(t.decode(&raw[start..end], decoder)?);
             start = end;
         }
-        Self::new(definition.clone(), &values)
+        decoder.leave();
+        Self::new(definition, &values)
     }
     
-    /// Returns a new [Sequence] from a [Tuple] scheme and raw data.
-    /// 
-    /// ## Panics
-    /// 
-    /// This methods could panic if the call to Self::from() return a [crate::value::error::TypeError].
-    pub fn parse(input: &[u8], definition: Tuple) -> Parsed<Self> {
-        let (Some(raw), rest) = parse_slice(input, definition.size()) else { return (None, input); };
-        (Some(Self::from(definition, raw).unwrap()), rest)
+    /// Returns a new [Sequence] from a [Tuple] scheme and the start of `input`, within the limits of the `decoder`.
+    ///
+    /// The [Sequence] is [None] if `input` is too short.
+    pub fn parse<'a>(input: &'a [u8], definition: Tuple, decoder: &mut Decoder) -> TypeResult<Parsed<'a, Self>> {
+        let (Some(raw), rest) = parse_slice(input, definition.size()) else { return Ok((None, input)); };
+        Ok((Some(Self::decode(definition, raw, decoder)?), rest))
     }
 }
 
@@ -2,10 +2,11 @@ use std::cell::RefCell;
 use std::fmt::{Debug, Formatter};
 use std::rc::Rc;
 
-use crate::types::concept::Type;
-use crate::types::algebraic::SumType;
+use crate::types::concept::{expect_size, DataType, Type};
+use crate::types::algebraic::{read_tag, SumType, TAG_SIZE};
 use crate::types::typedef::Enumeration;
 use crate::value::concept::{DataValue, ValueCell};
+use crate::value::decode::Decoder;
 use crate::value::error::{CanTypeError, SumTypeError, TypeResult};
 
 #[derive(Clone, Debug)]
@@ -49,10 +50,18 @@ impl SumValue {
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
 
     pub fn from(t: Rc<SumType>, raw: &[u8]) -> TypeResult<Self> {
-        let (tag_bytes, raw_value) = raw.split_at(8);
-        let tag = usize::from_be_bytes(tag_bytes.try_into().unwrap());
-        if let Some(variant_type) = t.variant(tag) {
-            let value = variant_type.construct_from_raw(&raw_value)?;
+        Self::decode(t, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [SumValue] from raw data, within the limits of the `decoder`.
+    pub fn decode(t: Rc<SumType>, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
+        decoder.enter(raw, 1)?;
+        expect_size(t.size(), raw)?;
+        let raw_value = &raw[TAG_SIZE..];
+        let tag = read_tag(raw).and_then(|tag| usize::try_from(tag).ok());
+        if let Some((tag, variant_type)) = tag.and_then(|tag| Some((tag, t.variant(tag)?))) {
+            let value = variant_type.decode(&raw_value[..variant_type.size()], decoder)?;
+            decoder.leave();
             Ok(Self::new(t.clone(), tag, value)?)
         } else {
             Err(SumTypeError::InvalidCase { provided_type: t.clone() }.promote())
@@ -65,13 +74,16 @@ impl DataValue for SumValue {
 
     fn raw(&self) -> Vec<u8> {
         let mut raw = Vec::new();
-        raw.extend(self.tag.to_be_bytes());
+        raw.extend((self.tag as u64).to_be_bytes());
         raw.extend(self.value.borrow().raw());
+        raw.resize(self.sum.size(), 0);
         raw
     }
 
     fn set(&mut self, raw: &[u8]) {
-        self.value.borrow_mut().set(raw)
+        if let Ok(value) = Self::from(self.sum.clone(), raw) {
+            *self = value;
+        }
     }
 }
 
@@ -86,6 +98,11 @@ impl Union {
     pub fn new(union: Rc<Enumeration>, tag: usize, value: ValueCell) -> TypeResult<Self> {
         Ok(Self { of_type: union.clone(), value: SumValue::new(union.sum_type.clone().to_rc(), tag, value)? })
     }
+    /// Returns a new [Union] of the variant labelled `label`.
+    pub fn from_label(union: &Rc<Enumeration>, label: &str, value: ValueCell) -> TypeResult<Self> {
+        Self::new(union.clone(), union.variant_index(label)?, value)
+    }
+    pub fn enumeration(&self) -> &Rc<Enumeration> { &self.of_type }
     pub fn current_type(&self) -> Type { self.value.current_type() }
     pub fn current_value(&self) -> &ValueCell { self.value.current_value() }
     pub fn tag(&self) -> usize {
@@ -97,7 +114,12 @@ impl Union {
     pub fn to_cell(self) -> ValueCell { Rc::new(RefCell::new(self)) }
     
     pub fn from(t: Rc<Enumeration>, raw: &[u8]) -> TypeResult<Self> {
-        Ok(Self{ of_type: t.clone(), value: SumValue::from(t.sum_type.clone().to_rc(), raw)? })
+        Self::decode(t, raw, &mut Decoder::default())
+    }
+
+    /// Returns a new [Union] from raw data, within the limits of the `decoder`.
+    pub fn decode(t: Rc<Enumeration>, raw: &[u8], decoder: &mut Decoder) -> TypeResult<Self> {
+        Ok(Self { of_type: t.clone(), value: SumValue::decode(t.sum_type.clone().to_rc(), raw, decoder)? })
     }
 }
 
@@ -114,3 +136,48 @@ impl Debug for Union {
         write!(f, "<{}:{}>{:x?}", self.data_type(), self.current_type().typename(), self.raw())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::facade::create_enum;
+    use crate::identity::LabelBank;
+    use crate::types::primitive::Primitive;
+    use crate::value::concept::DataValue;
+    use crate::value::error::{LabelError, SumTypeError, TypeError};
+    use crate::types::algebraic::SumType;
+    use crate::value::byte::{Byte, Bytes};
+    use crate::value::number::{Float, Integer};
+    use crate::value::union::{SumValue, Union};
+
+    #[test]
+    fn test_from_label() {
+        let number = create_enum("Math/Number", LabelBank::from(&["Integer", "Float"]), &[
+            Primitive::Integer.to_rc(),
+            Primitive::Float.to_rc(),
+        ]);
+        let float = Union::from_label(&number, "Float", Float::new(1.5).to_cell()).unwrap();
+        assert_eq!(float.raw(), [1u64.to_be_bytes(), 1.5f64.to_be_bytes()].concat());
+        let Err(TypeError::LabelError(LabelError::Unknown { typename, label })) = Union::from_label(&number, "Complex", Float::new(1.5).to_cell()) else {
+            panic!("an unknown variant cannot be built");
+        };
+        assert_eq!((typename.as_str(), label.as_str()), ("Math/Number", "Complex"));
+        let Err(TypeError::SumTypeError(SumTypeError::InvalidTag { provided_tag, .. })) = Union::from_label(&number, "Float", Integer::new(1).to_cell()) else {
+            panic!("the payload must have the type of the variant");
+        };
+        assert_eq!(provided_tag, 1);
+    }
+
+    #[test]
+    fn test_tag_encoding() {
+        let sum = SumType::new(&[Primitive::Byte.to_rc(), Primitive::Integer.to_rc()]).to_rc();
+        let byte = SumValue::new(sum.clone(), 0, Bytes::Byte(Byte::new(7)).to_cell()).unwrap();
+        assert_eq!(byte.raw(), [0u64.to_be_bytes().as_slice(), &[7], &[0; 7]].concat());
+        let integer = SumValue::from(sum.clone(), &[1u64.to_be_bytes(), 5i64.to_be_bytes()].concat()).unwrap();
+        assert_eq!((integer.tag(), integer.current_value().borrow().raw()), (1, 5i64.to_be_bytes().to_vec()));
+
+        for tag in [2u64, u64::MAX] {
+            let raw = [tag.to_be_bytes(), [0; 8]].concat();
+            assert!(matches!(SumValue::from(sum.clone(), &raw), Err(TypeError::SumTypeError(SumTypeError::InvalidCase { .. }))));
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Checks raw buffers against a [Type] without constructing any [crate::value::concept::ValueCell].
+//!
+//! Each [crate::types::concept::DataType] walks its own encoding in [crate::types::concept::DataType::validate_raw]
+//! and reports every [Violation] to a [Validator], with its offset in the buffer and its [Path].
+//!
+//! ## Example
+//!
+//! ```
+//! use roussillon_type_system::types::primitive::Primitive;
+//! use roussillon_type_system::value::validation::{validate, validate_all, ViolationKind};
+//!
+//! let flags = Primitive::list(Primitive::Boolean.to_rc(), 3).to_rc();
+//!
+//! assert!(validate(&flags, &[0, 1, 1]).is_ok());
+//!
+//! let violation = validate(&flags, &[0, 2, 1]).unwrap_err();
+//! assert_eq!(violation.offset, 1);
+//! assert_eq!(violation.path.to_string(), "[1]");
+//! assert!(matches!(violation.kind, ViolationKind::InvalidBoolean(2)));
+//!
+//! assert_eq!(validate_all(&flags, &[3, 2, 1]).len(), 2);
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use crate::types::concept::{DataType, Type};
+use crate::value::canonical::CanonicalForm;
+use crate::value::path::{Path, Segment};
+
+/// What is wrong with a raw buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// The buffer does not have the size of the type.
+    LengthMismatch {
+        expected: usize,
+        provided: usize,
+    },
+    /// A boolean byte which is neither `0` nor `1`.
+    InvalidBoolean(u8),
+    /// A sum tag which does not designate any variant.
+    InvalidTag {
+        tag: u64,
+        variants: usize,
+    },
+    /// A float which is not in its [CanonicalForm] (strict validation only).
+    NonCanonicalFloat(u64),
+    /// Non-zero bytes after the payload of a sum value (strict validation only).
+    NonZeroPadding,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ViolationKind::LengthMismatch { expected, provided } => write!(f, "expected {} bytes, {} provided", expected, provided),
+            ViolationKind::InvalidBoolean(byte) => write!(f, "invalid boolean byte {:#04x}", byte),
+            ViolationKind::InvalidTag { tag, variants } => write!(f, "tag {} is out of the {} variants", tag, variants),
+            ViolationKind::NonCanonicalFloat(bits) => write!(f, "non-canonical float {:#018x}", bits),
+            ViolationKind::NonZeroPadding => write!(f, "non-zero padding"),
+        }
+    }
+}
+
+/// A violation found at an `offset` of the validated buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub offset: usize,
+    pub path: Path,
+    pub kind: ViolationKind,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at offset {} ({})", self.kind, self.offset, self.path)
+    }
+}
+
+/// Collects the [Violation]s while a [DataType] walks a buffer.
+#[derive(Clone, Debug)]
+pub struct Validator {
+    offset: usize,
+    path: Path,
+    exhaustive: bool,
+    canonical: Option<CanonicalForm>,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    /// A validator which stops at the first [Violation].
+    pub fn first() -> Self {
+        Self { offset: 0, path: Path::root(), exhaustive: false, canonical: None, violations: Vec::new() }
+    }
+
+    /// A validator which collects every [Violation].
+    pub fn exhaustive() -> Self {
+        Self { exhaustive: true, ..Self::first() }
+    }
+
+    /// A validator which stops at the first [Violation], including non-canonical encodings.
+    pub fn strict(form: CanonicalForm) -> Self {
+        Self { canonical: Some(form), ..Self::first() }
+    }
+
+    /// The [CanonicalForm] to enforce, if the validation is strict.
+    pub fn canonical_form(&self) -> Option<&CanonicalForm> { self.canonical.as_ref() }
+
+    /// Returns `true` when the validation does not need to go further.
+    pub fn is_done(&self) -> bool {
+        !self.exhaustive && !self.violations.is_empty()
+    }
+
+    /// Reports a violation at an offset relative to the current value.
+    pub fn report(&mut self, at: usize, kind: ViolationKind) {
+        if self.is_done() {
+            return;
+        }
+        self.violations.push(Violation { offset: self.offset + at, path: self.path.clone(), kind });
+    }
+
+    /// Reports a [ViolationKind::LengthMismatch] if `raw` is not `expected` bytes long.
+    ///
+    /// Returns `true` when the size is right.
+    pub fn expect_size(&mut self, expected: usize, raw: &[u8]) -> bool {
+        if raw.len() == expected {
+            true
+        } else {
+            self.report(0, ViolationKind::LengthMismatch { expected, provided: raw.len() });
+            false
+        }
+    }
+
+    /// Validates the member `raw` of the current value, found at `at` bytes and designated by `segment`.
+    pub fn visit(&mut self, segment: Segment, at: usize, data_type: &dyn DataType, raw: &[u8]) {
+        if self.is_done() {
+            return;
+        }
+        self.path.push(segment);
+        self.offset += at;
+        data_type.validate_raw(raw, self);
+        self.offset -= at;
+        self.path.pop();
+    }
+
+    pub fn violations(&self) -> &[Violation] { &self.violations }
+
+    pub fn into_violations(self) -> Vec<Violation> { self.violations }
+}
+
+/// Returns the first [Violation] of `raw` as a value of `data_type`.
+pub fn validate(data_type: &Type, raw: &[u8]) -> Result<(), Violation> {
+    let mut validator = Validator::first();
+    data_type.validate_raw(raw, &mut validator);
+    match validator.into_violations().into_iter().next() {
+        Some(violation) => Err(violation),
+        None => Ok(()),
+    }
+}
+
+/// Returns every [Violation] of `raw` as a value of `data_type`.
+pub fn validate_all(data_type: &Type, raw: &[u8]) -> Vec<Violation> {
+    let mut validator = Validator::exhaustive();
+    data_type.validate_raw(raw, &mut validator);
+    validator.into_violations()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::algebraic::SumType;
+    use crate::types::concept::Type;
+    use crate::value::validation::{validate, validate_all, Validator, ViolationKind};
+    use crate::{data_type, enumeration, structure};
+
+    fn tagged(tag: u64, payload: &[u8]) -> Vec<u8> { [tag.to_be_bytes().as_slice(), payload].concat() }
+
+    #[test]
+    fn test_tags() {
+        let sum: Type = SumType::new(&[data_type!(byte), data_type!(boolean)]).to_rc();
+        assert!(validate(&sum, &tagged(1, &[1])).is_ok());
+        let violation = validate(&sum, &tagged(2, &[0])).unwrap_err();
+        assert_eq!((violation.offset, violation.path.to_string()), (0, ".".to_string()));
+        assert_eq!(violation.kind, ViolationKind::InvalidTag { tag: 2, variants: 2 });
+        assert_eq!(validate(&sum, &tagged(u64::MAX, &[0])).unwrap_err().kind, ViolationKind::InvalidTag { tag: u64::MAX, variants: 2 });
+
+        let status: Type = enumeration! { Status { Draft: (), Published: boolean } };
+        assert!(validate(&status, &tagged(0, &[0])).is_ok());
+        assert_eq!(validate(&status, &tagged(3, &[0])).unwrap_err().kind, ViolationKind::InvalidTag { tag: 3, variants: 2 });
+        let violation = validate(&status, &tagged(1, &[2])).unwrap_err();
+        assert_eq!((violation.offset, violation.path.to_string(), violation.kind), (8, "#1".to_string(), ViolationKind::InvalidBoolean(2)));
+    }
+
+    #[test]
+    fn test_lengths() {
+        let pair = data_type!((integer, boolean));
+        let violation = validate(&pair, &[0; 8]).unwrap_err();
+        assert_eq!(violation.kind, ViolationKind::LengthMismatch { expected: 9, provided: 8 });
+        assert_eq!((violation.offset, violation.path.is_root()), (0, true));
+        assert_eq!(validate_all(&pair, &[0; 10]).len(), 1);
+        assert_eq!(validate(&data_type!([byte; 2]), &[]).unwrap_err().kind, ViolationKind::LengthMismatch { expected: 2, provided: 0 });
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let grid = data_type!([[boolean; 2]; 2]);
+        assert!(validate(&grid, &[0, 1, 1, 0]).is_ok());
+        let violation = validate(&grid, &[0, 1, 1, 7]).unwrap_err();
+        assert_eq!((violation.offset, violation.path.to_string(), violation.kind), (3, "[1][1]".to_string(), ViolationKind::InvalidBoolean(7)));
+    }
+
+    #[test]
+    fn test_nested_fields() {
+        let inner = structure! { Inner { x: integer, flag: boolean } };
+        let outer: Type = structure! { Outer { a: byte, inner: inner, b: boolean } };
+        let mut raw = vec![0; 11];
+        raw[9] = 2;
+        let violation = validate(&outer, &raw).unwrap_err();
+        assert_eq!((violation.offset, violation.path.to_string()), (9, "inner.flag".to_string()));
+        assert_eq!(violation.to_string(), "invalid boolean byte 0x02 at offset 9 (inner.flag)");
+    }
+
+    #[test]
+    fn test_modes() {
+        let flags = data_type!([boolean; 4]);
+        let raw = [2, 1, 3, 4];
+        let first = validate(&flags, &raw).unwrap_err();
+        assert_eq!((first.offset, first.kind), (0, ViolationKind::InvalidBoolean(2)));
+
+        let every = validate_all(&flags, &raw);
+        let found: Vec<(usize, String)> = every.iter().map(|violation| (violation.offset, violation.path.to_string())).collect();
+        assert_eq!(found, [(0, "[0]".to_string()), (2, "[2]".to_string()), (3, "[3]".to_string())]);
+
+        let mut validator = Validator::first();
+        flags.validate_raw(&raw, &mut validator);
+        assert!(validator.is_done());
+        assert_eq!(validator.violations().len(), 1);
+        let mut validator = Validator::exhaustive();
+        flags.validate_raw(&raw, &mut validator);
+        assert!(!validator.is_done());
+        assert_eq!(validator.into_violations(), every);
+    }
+}
@@ -5,6 +5,7 @@ use crate::types::dynamic::Dynamic;
 use crate::value::concept::{DataValue, ValueCell};
 use crate::value::error::TypeResult;
 
+#[derive(Clone)]
 pub struct ValueType {
     pub label: Label,
     pub value: Dynamic,
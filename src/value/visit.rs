@@ -0,0 +1,309 @@
+//! Traversals of the members of nested values, located with a [Path].
+//!
+//! - [members] lists the members of a [Record], [ProductValue], [Sequence], [List], [Union] or [SumValue],
+//! - [walk_value] calls a [ValueVisitor] on a value and its members, depth first,
+//! - [fold_value] rewrites a value with a [ValueFolder], bottom-up :
+//!   a value is rebuilt only if one of its members is rewritten.
+//!
+//! ## Example
+//!
+//! Double every integer of a record :
+//! ```
+//! use roussillon_type_system::{record, structure};
+//! use roussillon_type_system::value::concept::{Downcast, ValueCell};
+//! use roussillon_type_system::value::error::TypeResult;
+//! use roussillon_type_system::value::number::Integer;
+//! use roussillon_type_system::value::path::Path;
+//! use roussillon_type_system::value::visit::{fold_value, walk_value, ValueFolder, ValueVisitor};
+//!
+//! struct Double;
+//!
+//! impl ValueFolder for Double {
+//!     fn fold(&mut self, _path: &Path, value: ValueCell) -> TypeResult<ValueCell> {
+//!         let doubled = value.as_integer().map(|integer| Integer::new(integer.value() * 2).to_cell());
+//!         Ok(doubled.unwrap_or(value))
+//!     }
+//! }
+//!
+//! struct Paths(Vec<String>);
+//!
+//! impl ValueVisitor for Paths {
+//!     fn enter(&mut self, path: &Path, _value: &ValueCell) -> bool {
+//!         self.0.push(path.to_string());
+//!         true
+//!     }
+//! }
+//!
+//! let point = structure! { Point { x: integer, tags: [integer; 2] } };
+//! let origin = record!(point { x: 1, tags: [2, 3] }).unwrap().to_cell();
+//!
+//! let doubled = fold_value(&origin, &mut Double).unwrap();
+//! assert_eq!(doubled.as_record().unwrap().field_from_name("x").unwrap().as_integer().unwrap().value(), 2);
+//!
+//! let mut paths = Paths(Vec::new());
+//! walk_value(&doubled, &mut paths);
+//! assert_eq!(paths.0, [".", "x", "tags", "tags[0]", "tags[1]"]);
+//! ```
+
+use std::rc::Rc;
+
+use crate::types::typedef::Structure;
+use crate::value::concept::ValueCell;
+use crate::value::error::TypeResult;
+use crate::value::list::List;
+use crate::value::path::{Path, Segment};
+use crate::value::record::{ProductValue, Record};
+use crate::value::sequence::Sequence;
+use crate::value::union::{SumValue, Union};
+
+/// The members of a composite value, with the [Segment] to each one.
+///
+/// Other values have no member.
+pub fn members(value: &ValueCell) -> Vec<(Segment, ValueCell)> {
+    let value = value.borrow();
+    if let Some(record) = value.downcast_ref::<Record>() {
+        return positions(record.as_sequence().values()).into_iter()
+            .map(|(segment, member)| (field_segment(record.structure(), segment), member))
+            .collect();
+    }
+    if let Some(product) = value.downcast_ref::<ProductValue>() {
+        return positions(product.as_sequence().values());
+    }
+    if let Some(sequence) = value.downcast_ref::<Sequence>() {
+        return positions(sequence.values());
+    }
+    if let Some(list) = value.downcast_ref::<List>() {
+        return (0..list.len()).map(|index| (Segment::Index(index), list[index].clone())).collect();
+    }
+    if let Some(union) = value.downcast_ref::<Union>() {
+        return vec![(Segment::Tag(union.tag()), union.current_value().clone())];
+    }
+    if let Some(sum) = value.downcast_ref::<SumValue>() {
+        return vec![(Segment::Tag(sum.tag()), sum.current_value().clone())];
+    }
+    Vec::new()
+}
+
+fn positions(values: &[ValueCell]) -> Vec<(Segment, ValueCell)> {
+    values.iter().enumerate().map(|(position, value)| (Segment::Position(position), value.clone())).collect()
+}
+
+/// The labelled [Segment::Field] of a field, or its [Segment::Position] if it has no label.
+fn field_segment(structure: &Structure, segment: Segment) -> Segment {
+    match segment {
+        Segment::Position(position) => structure.labels.label(position).cloned().map(Segment::Field).unwrap_or(segment),
+        _ => segment,
+    }
+}
+
+/// Visits a value and its members with [walk_value].
+pub trait ValueVisitor {
+    /// Called before the members of `value` at `path`, which are skipped if it returns `false`.
+    fn enter(&mut self, path: &Path, value: &ValueCell) -> bool;
+
+    /// Called after the members of `value` at `path`.
+    fn leave(&mut self, _path: &Path, _value: &ValueCell) {}
+}
+
+/// Calls `visitor` on `value` and its [members], depth first, from the root [Path].
+pub fn walk_value(value: &ValueCell, visitor: &mut impl ValueVisitor) {
+    walk_at(&mut Path::root(), value, visitor)
+}
+
+fn walk_at(path: &mut Path, value: &ValueCell, visitor: &mut impl ValueVisitor) {
+    if visitor.enter(path, value) {
+        for (segment, member) in members(value) {
+            path.push(segment);
+            walk_at(path, &member, visitor);
+            path.pop();
+        }
+    }
+    visitor.leave(path, value);
+}
+
+/// Rewrites a value and its members with [fold_value].
+pub trait ValueFolder {
+    /// Replaces `value` at `path` before its members are folded, `None` to fold them.
+    ///
+    /// A replacement is not folded.
+    fn replace(&mut self, _path: &Path, _value: &ValueCell) -> TypeResult<Option<ValueCell>> { Ok(None) }
+
+    /// Rewrites `value` at `path`, once its members are folded.
+    fn fold(&mut self, _path: &Path, value: ValueCell) -> TypeResult<ValueCell> { Ok(value) }
+}
+
+/// Rewrites `value` with `folder`, bottom-up, from the root [Path].
+///
+/// A value with rewritten members is rebuilt as a new value of the same type,
+/// so a member must be rewritten with a value of its type. The original value is left unchanged.
+pub fn fold_value(value: &ValueCell, folder: &mut impl ValueFolder) -> TypeResult<ValueCell> {
+    fold_at(&mut Path::root(), value, folder)
+}
+
+fn fold_at(path: &mut Path, value: &ValueCell, folder: &mut impl ValueFolder) -> TypeResult<ValueCell> {
+    if let Some(replacement) = folder.replace(path, value)? {
+        return Ok(replacement);
+    }
+    let members = members(value);
+    let mut folded = Vec::with_capacity(members.len());
+    for (segment, member) in members.iter() {
+        path.push(segment.clone());
+        folded.push(fold_at(path, member, folder)?);
+        path.pop();
+    }
+    let unchanged = members.iter().zip(folded.iter()).all(|((_, member), folded)| Rc::ptr_eq(member, folded));
+    let rebuilt = if unchanged { value.clone() } else { rebuild(value, folded)? };
+    folder.fold(path, rebuilt)
+}
+
+/// Rebuilds the composite `value` with new `members`, a [List] keeping its copy-on-write mode and alias check.
+pub(crate) fn rebuild(value: &ValueCell, mut members: Vec<ValueCell>) -> TypeResult<ValueCell> {
+    let value = value.borrow();
+    if let Some(record) = value.downcast_ref::<Record>() {
+        return Ok(Record::new(record.structure().clone(), &members)?.to_cell());
+    }
+    if let Some(product) = value.downcast_ref::<ProductValue>() {
+        return Ok(ProductValue::new(product.product().clone(), &members)?.to_cell());
+    }
+    if let Some(sequence) = value.downcast_ref::<Sequence>() {
+        return Ok(Sequence::new(sequence.definition().clone(), &members)?.to_cell());
+    }
+    if let Some(list) = value.downcast_ref::<List>() {
+        let mut rebuilt = List::empty(list.of_type().clone());
+        if list.is_copy_on_write() {
+            rebuilt = rebuilt.with_copy_on_write();
+        }
+        if list.checks_aliases() {
+            rebuilt = rebuilt.with_alias_check();
+        }
+        for member in members {
+            rebuilt.push(member)?;
+        }
+        return Ok(rebuilt.to_cell());
+    }
+    if let Some(union) = value.downcast_ref::<Union>() {
+        return Ok(Union::new(union.enumeration().clone(), union.tag(), members.remove(0))?.to_cell());
+    }
+    if let Some(sum) = value.downcast_ref::<SumValue>() {
+        return Ok(SumValue::new(sum.sum.clone(), sum.tag(), members.remove(0))?.to_cell());
+    }
+    unreachable!("only composite values have members")
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use crate::types::algebraic::SumType;
+    use crate::value::concept::{Downcast, ValueCell};
+    use crate::value::convert::{FromValue, IntoValue};
+    use crate::value::copy::CloneCell;
+    use crate::value::list::List;
+    use crate::value::error::TypeResult;
+    use crate::value::number::{Float, Integer};
+    use crate::value::path::{Path, Segment};
+    use crate::value::union::SumValue;
+    use crate::value::visit::{fold_value, members, walk_value, ValueFolder, ValueVisitor};
+    use crate::{data_type, record, structure};
+
+    fn segments(value: &ValueCell) -> Vec<String> {
+        members(value).iter().map(|(segment, _)| segment.to_string()).collect()
+    }
+
+    /// Records the entered and left paths, without entering lists.
+    struct Trace(Vec<String>);
+
+    impl ValueVisitor for Trace {
+        fn enter(&mut self, path: &Path, value: &ValueCell) -> bool {
+            self.0.push(format!("+{path}"));
+            value.as_list().is_none()
+        }
+
+        fn leave(&mut self, path: &Path, _value: &ValueCell) { self.0.push(format!("-{path}")) }
+    }
+
+    struct Double;
+
+    impl ValueFolder for Double {
+        fn fold(&mut self, _path: &Path, value: ValueCell) -> TypeResult<ValueCell> {
+            let doubled = value.as_integer().map(|integer| Integer::new(integer.value() * 2).to_cell());
+            Ok(doubled.unwrap_or(value))
+        }
+    }
+
+    /// Replaces the lists by zeroes, and rewrites integers as floats.
+    struct Retype;
+
+    impl ValueFolder for Retype {
+        fn replace(&mut self, _path: &Path, value: &ValueCell) -> TypeResult<Option<ValueCell>> {
+            let data_type = value.borrow().data_type();
+            Ok(if value.as_list().is_some() { Some(data_type.zeroed()?) } else { None })
+        }
+
+        fn fold(&mut self, _path: &Path, value: ValueCell) -> TypeResult<ValueCell> {
+            let float = value.as_integer().map(|integer| Float::new(integer.value() as f64).to_cell());
+            Ok(float.unwrap_or(value))
+        }
+    }
+
+    #[test]
+    fn test_members() {
+        let point = structure! { Point { x: integer, tags: [byte; 2] } };
+        let origin = record!(point { x: 1, tags: [2, 3] }).unwrap().to_cell();
+        assert_eq!(segments(&origin), [".x", ".tags"]);
+        assert_eq!(segments(&[4u8, 5, 6].into_value()), ["[0]", "[1]", "[2]"]);
+        assert_eq!(segments(&(true, 1u8).into_value()), [".0", ".1"]);
+        assert_eq!(segments(&Some(1i64).into_value()), ["#1"]);
+        let sum = SumType::new(&[data_type!(byte), data_type!(integer)]).to_rc();
+        assert_eq!(segments(&SumValue::new(sum, 0, 7u8.into_value()).unwrap().to_cell()), ["#0"]);
+        assert!(members(&().into_value()).is_empty());
+        assert!(members(&1i64.into_value()).is_empty());
+        assert!(matches!(members(&origin)[0].0, Segment::Field(_)));
+    }
+
+    #[test]
+    fn test_walk() {
+        let mut trace = Trace(Vec::new());
+        walk_value(&(1u8, [2u8, 3]).into_value(), &mut trace);
+        assert_eq!(trace.0, ["+.", "+0", "-0", "+1", "-1", "-."]);
+    }
+
+    #[test]
+    fn test_fold() {
+        let unchanged = (true, [1u8, 2]).into_value();
+        assert!(Rc::ptr_eq(&fold_value(&unchanged, &mut Double).unwrap(), &unchanged));
+
+        let original = (1i64, Some([2i64, 3])).into_value();
+        let doubled = fold_value(&original, &mut Double).unwrap();
+        assert_eq!(<(i64, Option<[i64; 2]>)>::from_value(&doubled).unwrap(), (2, Some([4, 6])));
+        assert_eq!(<(i64, Option<[i64; 2]>)>::from_value(&original).unwrap(), (1, Some([2, 3])));
+    }
+
+    #[test]
+    fn test_list_modes() {
+        let mut list = List::empty(data_type!(integer)).with_copy_on_write().with_alias_check();
+        list.push(1i64.into_value()).unwrap();
+        let list = list.to_cell();
+        let doubled = fold_value(&list, &mut Double).unwrap();
+        let rebuilt = doubled.as_list().unwrap();
+        assert!(rebuilt.is_copy_on_write() && rebuilt.checks_aliases());
+        assert_eq!(rebuilt[0].as_integer().unwrap().value(), 2);
+
+        let mut outer = List::empty(list.borrow().data_type()).with_alias_check();
+        outer.push(list).unwrap();
+        let outer = outer.to_cell();
+        let doubled = fold_value(&outer, &mut Double).unwrap();
+        let inner = doubled.as_list().unwrap()[0].clone();
+        assert!(inner.as_list().unwrap().is_copy_on_write() && inner.as_list().unwrap().checks_aliases());
+        let copy = outer.deep_clone().unwrap();
+        assert!(copy.as_list().unwrap().checks_aliases() && !copy.as_list().unwrap().is_copy_on_write());
+        assert!(copy.as_list().unwrap()[0].as_list().unwrap().is_copy_on_write());
+    }
+
+    #[test]
+    fn test_replace() {
+        let replaced = fold_value(&(true, [1i64, 2]).into_value(), &mut Retype).unwrap();
+        assert_eq!(<(bool, [i64; 2])>::from_value(&replaced).unwrap(), (true, [0, 0]));
+        assert!(fold_value(&(true, 1i64).into_value(), &mut Retype).is_err());
+    }
+}